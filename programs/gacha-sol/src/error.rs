@@ -56,4 +56,178 @@ pub enum GachaError {
 
     #[msg("Ciphertext balance conversion failed")]
     CipherTextBalanceConversionFailed,
+
+    #[msg("Invalid game vault")]
+    InvalidGameVault,
+
+    #[msg("Reward mint cannot change after pulls are created")]
+    RewardMintLocked,
+
+    #[msg("Invalid pending authority")]
+    InvalidPendingAuthority,
+
+    #[msg("Game is paused")]
+    GamePaused,
+
+    #[msg("Pull already verified")]
+    PullAlreadyVerified,
+
+    #[msg("Pull pool is full")]
+    PullPoolFull,
+
+    #[msg("No pull available")]
+    NoPullAvailable,
+
+    #[msg("Randomness not ready")]
+    RandomnessNotReady,
+
+    #[msg("Randomness expired")]
+    RandomnessExpired,
+
+    #[msg("Pull not selected")]
+    PullNotSelected,
+
+    #[msg("Refund timeout must be positive")]
+    InvalidRefundTimeout,
+
+    #[msg("Purchases pending in escrow")]
+    PurchasesPending,
+
+    #[msg("Pull not purchased")]
+    PullNotPurchased,
+
+    #[msg("Pull already refunded")]
+    PullAlreadyRefunded,
+
+    #[msg("Refund not available yet")]
+    RefundNotAvailable,
+
+    #[msg("Proceeds pending withdrawal")]
+    ProceedsPending,
+
+    #[msg("Withdraw amount is zero")]
+    InvalidZeroWithdrawAmount,
+
+    #[msg("Insufficient withdrawable proceeds")]
+    InsufficientProceeds,
+
+    #[msg("Withdraw proofs not published")]
+    WithdrawProofsNotPublished,
+
+    #[msg("Reward must be withdrawn in full")]
+    PartialRewardWithdraw,
+
+    #[msg("Pull not claimed")]
+    PullNotClaimed,
+
+    #[msg("Invalid transfer proofs")]
+    InvalidTransferProofs,
+
+    #[msg("Auditor ciphertext mismatch")]
+    AuditorCiphertextMismatch,
+
+    #[msg("Transfer proofs not verified")]
+    TransferProofsNotVerified,
+
+    #[msg("Proof context account or instructions sysvar missing")]
+    MissingProofLocation,
+
+    #[msg("Pull and reward vault accounts do not match the pulls to create")]
+    InvalidPullAccounts,
+
+    #[msg("Prize tier out of range")]
+    InvalidPrizeTier,
+
+    #[msg("Prize table must be committed before any pull is created")]
+    PullsAlreadyCreated,
+
+    #[msg("Prize table tiers are invalid")]
+    InvalidPrizeTable,
+
+    #[msg("Prize tier has no pull left to create")]
+    PrizeTierFull,
+
+    #[msg("Reward amount is outside the range of the prize tier")]
+    RewardOutOfPrizeRange,
+
+    #[msg("Prize table account missing")]
+    MissingPrizeTable,
+
+    #[msg("Pull bundle is invalid or not on sale")]
+    InvalidPullBundle,
+
+    #[msg("Sale end must be after sale start")]
+    InvalidSaleWindow,
+
+    #[msg("Sale has not started")]
+    SaleNotStarted,
+
+    #[msg("Sale has ended")]
+    SaleEnded,
+
+    #[msg("Wallet reached the maximum number of pulls")]
+    PurchaseLimitExceeded,
+
+    #[msg("Presale end must be after presale start")]
+    InvalidPresaleWindow,
+
+    #[msg("Buyer is not in the presale allowlist")]
+    NotAllowlisted,
+
+    #[msg("Buyer presale allocation exceeded")]
+    PresaleAllocationExceeded,
+
+    #[msg("Referral share exceeds the maximum")]
+    InvalidReferralBps,
+
+    #[msg("Buyer cannot refer itself")]
+    InvalidReferrer,
+
+    #[msg("Fee schedule recipients are invalid or do not sum to 10000 bps")]
+    InvalidFeeSchedule,
+
+    #[msg("Fee schedule is required")]
+    MissingFeeSchedule,
+
+    #[msg("Fee recipient accounts do not match the fee schedule")]
+    InvalidFeeRecipients,
+
+    #[msg("Not supported by the payment mode of the game")]
+    UnsupportedPaymentMode,
+
+    #[msg("Payment accounts do not match the payment mode of the game")]
+    InvalidPaymentAccounts,
+
+    #[msg("Pull not refunded")]
+    PullNotRefunded,
+
+    #[msg("Pull already reclaimed")]
+    PullAlreadyReclaimed,
+
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+
+    #[msg("No referral fee to pay")]
+    NoReferralFee,
+
+    #[msg("Referral fee not paid")]
+    ReferralFeeNotPaid,
+
+    #[msg("Randomness not expired")]
+    RandomnessNotExpired,
+
+    #[msg("Pity settings are locked once pulls are created")]
+    PitySettingsLocked,
+
+    #[msg("Confidential delivery cannot be checked against the prize table")]
+    ConfidentialDeliveryWithPrizeTable,
+
+    #[msg("No pity tier pull available for the guaranteed draws")]
+    NoPityPullAvailable,
+
+    #[msg("Referral fees must be paid before switching purchase mint")]
+    ReferralFeesPending,
+
+    #[msg("Published withdraw proofs must be closed with the pull")]
+    WithdrawProofsNotClosed,
 }
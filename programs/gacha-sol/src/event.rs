@@ -1,16 +1,96 @@
 use anchor_lang::prelude::*;
 
-use crate::state::ELGAMAL_PUBKEY_MAX_BASE64_LEN;
+use crate::state::{
+    FeeRecipient, PaymentMode, PrizeTier, PullBundle, ELGAMAL_PUBKEY_MAX_BASE64_LEN,
+    MAX_PULL_BUNDLES,
+};
 
 /// Event emitted when a game config is initialized
 #[event]
 pub struct GameConfigInitialized {
     pub game_config: Pubkey,
+    pub game_id: u64,
     pub authority: Pubkey,
     pub purchase_mint: Pubkey,
     pub reward_mint: Pubkey,
     pub game_vault: Pubkey,
     pub pull_price: u64,
+    pub refund_timeout: i64,
+    pub sale_start: i64,
+    pub sale_end: i64,
+    pub payment_mode: PaymentMode,
+}
+
+/// Event emitted when a game config is updated
+#[event]
+pub struct GameConfigUpdated {
+    pub game_config: Pubkey,
+    pub old_pull_price: u64,
+    pub new_pull_price: u64,
+    pub old_purchase_mint: Pubkey,
+    pub new_purchase_mint: Pubkey,
+    pub old_reward_mint: Pubkey,
+    pub new_reward_mint: Pubkey,
+    pub old_game_vault: Pubkey,
+    pub new_game_vault: Pubkey,
+    pub old_refund_timeout: i64,
+    pub new_refund_timeout: i64,
+    pub old_bundles: [PullBundle; MAX_PULL_BUNDLES],
+    pub new_bundles: [PullBundle; MAX_PULL_BUNDLES],
+    pub old_pity_threshold: u32,
+    pub new_pity_threshold: u32,
+    pub old_pity_tier: u8,
+    pub new_pity_tier: u8,
+    pub old_sale_start: i64,
+    pub new_sale_start: i64,
+    pub old_sale_end: i64,
+    pub new_sale_end: i64,
+    pub old_max_pulls_per_wallet: u32,
+    pub new_max_pulls_per_wallet: u32,
+    pub old_merkle_root: [u8; 32],
+    pub new_merkle_root: [u8; 32],
+    pub old_presale_start: i64,
+    pub new_presale_start: i64,
+    pub old_presale_end: i64,
+    pub new_presale_end: i64,
+    pub old_referral_bps: u16,
+    pub new_referral_bps: u16,
+}
+
+/// Event emitted when proceeds are withdrawn from the game vault
+#[event]
+pub struct ProceedsWithdrawn {
+    pub game_config: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+/// Event emitted when a new authority is proposed
+#[event]
+pub struct AuthorityProposed {
+    pub game_config: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+/// Event emitted when the pending authority accepts the game config
+#[event]
+pub struct AuthorityAccepted {
+    pub game_config: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+/// Event emitted when a game is paused
+#[event]
+pub struct GamePaused {
+    pub game_config: Pubkey,
+}
+
+/// Event emitted when a paused game is resumed
+#[event]
+pub struct GameResumed {
+    pub game_config: Pubkey,
 }
 
 /// Event emitted when a pull is created
@@ -19,6 +99,7 @@ pub struct PullCreated {
     pub id: u64,
     pub pull: Pubkey,
     pub encrypted_amount: [u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
+    pub tier: u8,
 }
 
 /// Event emitted when pending balance of a pull applied
@@ -35,12 +116,89 @@ pub struct PullVerified {
     pub pull: Pubkey,
 }
 
-/// Event emitted when a pull is created
+/// Event emitted when a pull ticket is bought
+#[event]
+pub struct PullTicketBought {
+    pub ticket: Pubkey,
+    pub buyer: Pubkey,
+    pub commit_slot: u64,
+}
+
+/// Event emitted when a ticket that can no longer be revealed is cancelled and refunded
+#[event]
+pub struct TicketCancelled {
+    pub ticket: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: u64,
+}
+
+/// Event emitted when a multi-pull bundle is bought, its pulls are assigned by reveal_pull
+#[event]
+pub struct BundleBought {
+    pub ticket: Pubkey,
+    pub buyer: Pubkey,
+    pub bundle_size: u8,
+    pub price: u64,
+}
+
+/// Event emitted when a pull is assigned to its buyer
 #[event]
 pub struct PullBought {
     pub id: u64,
     pub pull: Pubkey,
     pub buyer: Pubkey,
+    /// Escrowed price of the pull
+    pub purchase_price: u64,
+    /// Default when the purchase had no referrer
+    pub referrer: Pubkey,
+    /// Share of the purchase owed to the referrer once the pull is claimed
+    pub referral_fee: u64,
+}
+
+/// Event emitted when an unopened pull is refunded to its buyer
+#[event]
+pub struct PullRefunded {
+    pub id: u64,
+    pub pull: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: u64,
+}
+
+/// Event emitted when the authority withdraws the reward of a refunded pull
+#[event]
+pub struct PullReclaimed {
+    pub id: u64,
+    pub pull: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+/// Event emitted when the referral fee of a claimed pull is paid to the referrer
+#[event]
+pub struct ReferralFeePaid {
+    pub id: u64,
+    pub pull: Pubkey,
+    pub referrer: Pubkey,
+    pub amount: u64,
+}
+
+/// Event emitted when withdraw proofs are published for a pull
+#[event]
+pub struct WithdrawProofsPublished {
+    pub id: u64,
+    pub pull: Pubkey,
+    pub equality_proof: Pubkey,
+    pub range_proof: Pubkey,
+}
+
+/// Event emitted when confidential transfer proofs are verified for a pull
+#[event]
+pub struct TransferProofsVerified {
+    pub id: u64,
+    pub pull: Pubkey,
+    pub equality_proof: Pubkey,
+    pub ciphertext_validity_proof: Pubkey,
+    pub range_proof: Pubkey,
 }
 
 /// Event emitted when a pull is created
@@ -49,4 +207,31 @@ pub struct PullClaimed {
     pub id: u64,
     pub pull: Pubkey,
     pub buyer: Pubkey,
+    pub tier: u8,
+    /// Zero when the reward is delivered confidentially
+    pub revealed_amount: u64,
+}
+
+/// Event emitted when a claimed or reclaimed pull and its reward vault are closed
+#[event]
+pub struct PullClosed {
+    pub id: u64,
+    pub pull: Pubkey,
+    pub rent_receiver: Pubkey,
+}
+
+/// Event emitted when a prize table is committed
+#[event]
+pub struct PrizeTableCommitted {
+    pub game_config: Pubkey,
+    pub prize_table: Pubkey,
+    pub tiers: Vec<PrizeTier>,
+}
+
+/// Event emitted when the fee schedule of a game is set
+#[event]
+pub struct FeeScheduleSet {
+    pub game_config: Pubkey,
+    pub fee_schedule: Pubkey,
+    pub recipients: Vec<FeeRecipient>,
 }
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::GachaError, event::AuthorityAccepted, state::GameConfig};
+
+pub fn accept_authority<'info>(
+    ctx: Context<'_, '_, '_, 'info, AcceptAuthority<'info>>,
+) -> Result<()> {
+    let game_config = &mut ctx.accounts.game_config;
+    let old_authority = game_config.authority;
+
+    game_config.authority = ctx.accounts.pending_authority.key();
+    game_config.pending_authority = Pubkey::default();
+
+    emit!(AuthorityAccepted {
+        game_config: game_config.key(),
+        old_authority,
+        new_authority: game_config.authority
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(mut,
+        constraint = game_config.pending_authority == pending_authority.key() @ GachaError::InvalidPendingAuthority
+    )]
+    pub game_config: Box<Account<'info, GameConfig>>,
+    pub pending_authority: Signer<'info>,
+}
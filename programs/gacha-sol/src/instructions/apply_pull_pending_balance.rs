@@ -41,7 +41,7 @@ pub fn apply_pull_pending_balance<'info>(
 pub struct ApplyPullPendingBalance<'info> {
     #[account(has_one=authority)]
     pub game_config: Box<Account<'info, GameConfig>>,
-    #[account(has_one=reward_vault)]
+    #[account(has_one=game_config, has_one=reward_vault)]
     pub pull: Box<Account<'info, Pull>>,
     /// CHECK: Token account 2022
     #[account(mut)]
@@ -1,80 +1,176 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token::{Mint, Token, TokenAccount};
 
 use crate::{
     error::GachaError,
-    event::PullBought,
-    state::{BuyPullParams, GameConfig, Pull},
+    event::PullTicketBought,
+    state::{BuyPullParams, GameConfig, PaymentMode, PlayerState, PullPool, PullTicket, Size},
+    utils::payment::PaymentAccounts,
 };
 
 use super::BuyPullInstruction;
 
 pub fn buy_pull<'info>(
     ctx: Context<'_, '_, '_, 'info, BuyPull<'info>>,
-    _params: BuyPullParams,
+    params: BuyPullParams,
 ) -> Result<()> {
-    let pull = &mut ctx.accounts.pull;
     let game_config = &ctx.accounts.game_config;
+    let pull_price = game_config.pull_price;
+    let payment_mode = game_config.payment_mode;
 
+    // Native SOL purchases go to the treasury, without referral share
+    ctx.accounts.payment_accounts().verify()?;
     require!(
-        pull.buyer == Pubkey::default(),
-        GachaError::PullAlreadyPurchased
+        payment_mode == PaymentMode::Token || ctx.accounts.referrer_purchase_account.is_none(),
+        GachaError::InvalidPaymentAccounts
     );
-    require!(pull.verified, GachaError::PullNotVerified);
-    require!(!pull.claimed, GachaError::PullAlreadyClaimed);
 
-    pull.buyer = ctx.accounts.buyer.key();
+    // The referrer share is paid by pay_referral_fee once the pull is claimed
+    let referrer = match &ctx.accounts.referrer_purchase_account {
+        Some(referrer_purchase_account) => {
+            require!(
+                referrer_purchase_account.owner != ctx.accounts.buyer.key(),
+                GachaError::InvalidReferrer
+            );
+            referrer_purchase_account.owner
+        }
+        None => Pubkey::default(),
+    };
+    let referral_fee = match referrer == Pubkey::default() {
+        true => 0,
+        false => game_config.get_referral_fee(pull_price),
+    };
 
-    // Transfer
-    ctx.transfer_purchase(game_config.pull_price)?;
+    require!(!game_config.paused, GachaError::GamePaused);
+    game_config.verify_purchase(
+        Clock::get()?.unix_timestamp,
+        ctx.accounts.buyer.key,
+        params.allowlist.as_ref(),
+        ctx.accounts.player_state.pulls_bought + 1,
+    )?;
+
+    // Reserve a pull from the pool, the actual pull is assigned by reveal_pull.
+    // A pull guaranteed by the pity threshold also reserves a pity tier pull.
+    let pity_draws = ctx
+        .accounts
+        .player_state
+        .guaranteed_pity_draws(game_config, 1);
+    let pull_pool = &mut ctx.accounts.pull_pool;
+    require!(pull_pool.available() > 0, GachaError::NoPullAvailable);
+    require!(
+        pull_pool.available_pity() >= pity_draws,
+        GachaError::NoPityPullAvailable
+    );
+    pull_pool.pending_tickets += 1;
+    pull_pool.pending_pity_draws += pity_draws;
+
+    {
+        let pull_ticket = &mut ctx.accounts.pull_ticket;
+        pull_ticket.game_config = game_config.key();
+        pull_ticket.buyer = ctx.accounts.buyer.key();
+        pull_ticket.commit_slot = Clock::get()?.slot;
+        pull_ticket.purchase_price = pull_price;
+        pull_ticket.purchased_at = Clock::get()?.unix_timestamp;
+        pull_ticket.pull_count = 1;
+        pull_ticket.pity_draws = pity_draws as u8;
+        pull_ticket.referrer = referrer;
+        pull_ticket.referral_fee = referral_fee;
+        pull_ticket.bump = ctx.bumps.pull_ticket;
+    }
+
+    {
+        let player_state = &mut ctx.accounts.player_state;
+        player_state.game_config = game_config.key();
+        player_state.buyer = ctx.accounts.buyer.key();
+        player_state.record_purchase(1, game_config)?;
+        player_state.bump = ctx.bumps.player_state;
+    }
+
+    // Transfer, held in escrow until the pull is opened or refunded
+    ctx.transfer_purchase(pull_price)?;
+
+    let game_config = &mut ctx.accounts.game_config;
+    game_config.escrowed_amount += pull_price;
 
     // Emit an event
-    emit!(PullBought {
-        id: ctx.accounts.pull.id,
-        pull: ctx.accounts.pull.key(),
-        buyer: ctx.accounts.buyer.key()
+    emit!(PullTicketBought {
+        ticket: ctx.accounts.pull_ticket.key(),
+        buyer: ctx.accounts.buyer.key(),
+        commit_slot: ctx.accounts.pull_ticket.commit_slot
     });
 
     Ok(())
 }
 
 #[derive(Accounts)]
-#[instruction(params: BuyPullParams)]
 pub struct BuyPull<'info> {
-    #[account(has_one=game_vault)]
+    #[account(mut, has_one=game_vault)]
     pub game_config: Account<'info, GameConfig>,
     #[account(mut,
-        seeds = [b"pull", params.pull_id.to_le_bytes().as_ref()],
+        seeds = [b"pull_pool", game_config.key().as_ref()],
+        bump = pull_pool.bump
+    )]
+    pub pull_pool: Box<Account<'info, PullPool>>,
+    #[account(
+        init,
+        payer = buyer,
+        space = PullTicket::SIZE,
+        seeds = [b"pull_ticket", game_config.key().as_ref(), buyer.key().as_ref()],
         bump
     )]
-    pub pull: Account<'info, Pull>,
+    pub pull_ticket: Account<'info, PullTicket>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = PlayerState::SIZE,
+        seeds = [b"player_state", game_config.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub player_state: Box<Account<'info, PlayerState>>,
     #[account(mut)]
     pub buyer: Signer<'info>,
+    /// Required when the game is paid in tokens
     #[account(mut,
         token::mint = purchase_mint
     )]
-    pub buyer_purchase_account: Account<'info, TokenAccount>,
+    pub buyer_purchase_account: Option<Account<'info, TokenAccount>>,
+    /// Purchase token account of the referrer, receives the referral share from pay_referral_fee
+    #[account(
+        token::mint = purchase_mint
+    )]
+    pub referrer_purchase_account: Option<Account<'info, TokenAccount>>,
     #[account(mut)]
     pub game_vault: Account<'info, TokenAccount>,
     pub purchase_mint: Account<'info, Mint>,
+    /// Required when the game is paid in native SOL
+    #[account(mut,
+        seeds = [b"treasury", game_config.key().as_ref()],
+        bump = game_config.treasury_bump
+    )]
+    pub treasury: Option<SystemAccount<'info>>,
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> BuyPull<'info> {
+    fn payment_accounts(&self) -> PaymentAccounts<'_, 'info> {
+        PaymentAccounts {
+            game_config: &self.game_config,
+            buyer: self.buyer.as_ref(),
+            buyer_purchase_account: self
+                .buyer_purchase_account
+                .as_ref()
+                .map(|buyer_purchase_account| buyer_purchase_account.as_ref()),
+            game_vault: self.game_vault.as_ref(),
+            treasury: self.treasury.as_ref().map(|treasury| treasury.as_ref()),
+            token_program: self.token_program.as_ref(),
+            system_program: self.system_program.as_ref(),
+        }
+    }
 }
 
 impl<'info> BuyPullInstruction for Context<'_, '_, '_, 'info, BuyPull<'info>> {
     fn transfer_purchase(&self, amount: u64) -> Result<()> {
-        let cpi_accounts = Transfer {
-            from: self
-                .accounts
-                .buyer_purchase_account
-                .to_account_info()
-                .clone(),
-            to: self.accounts.game_vault.to_account_info().clone(),
-            authority: self.accounts.buyer.to_account_info().clone(),
-        };
-        let token_program = self.accounts.token_program.to_account_info().clone();
-        let cpi_context = CpiContext::new(token_program, cpi_accounts);
-
-        token::transfer(cpi_context, amount)?;
-        Ok(())
+        self.accounts.payment_accounts().pay(amount)
     }
 }
@@ -0,0 +1,161 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::{
+    error::GachaError,
+    event::{BundleBought, PullTicketBought},
+    state::{BuyPullsParams, GameConfig, PlayerState, PullPool, PullTicket, Size},
+    utils::payment::PaymentAccounts,
+};
+
+use super::BuyPullsInstruction;
+
+pub fn buy_pulls<'info>(
+    ctx: Context<'_, '_, '_, 'info, BuyPulls<'info>>,
+    params: BuyPullsParams,
+) -> Result<()> {
+    let game_config = &ctx.accounts.game_config;
+
+    require!(!game_config.paused, GachaError::GamePaused);
+    ctx.accounts.payment_accounts().verify()?;
+    let bundle = *game_config
+        .get_bundle(params.bundle_size)
+        .ok_or(GachaError::InvalidPullBundle)?;
+
+    game_config.verify_purchase(
+        Clock::get()?.unix_timestamp,
+        ctx.accounts.buyer.key,
+        params.allowlist.as_ref(),
+        ctx.accounts.player_state.pulls_bought + bundle.size as u64,
+    )?;
+
+    // Reserve the bundle pulls from the pool, the actual pulls are assigned by reveal_pull.
+    // Draws guaranteed by the pity threshold also reserve pity tier pulls.
+    let pity_draws = ctx
+        .accounts
+        .player_state
+        .guaranteed_pity_draws(game_config, bundle.size);
+    let pull_pool = &mut ctx.accounts.pull_pool;
+    require!(
+        pull_pool.available() >= bundle.size as u64,
+        GachaError::NoPullAvailable
+    );
+    require!(
+        pull_pool.available_pity() >= pity_draws,
+        GachaError::NoPityPullAvailable
+    );
+    pull_pool.pending_tickets += bundle.size as u64;
+    pull_pool.pending_pity_draws += pity_draws;
+
+    {
+        let pull_ticket = &mut ctx.accounts.pull_ticket;
+        pull_ticket.game_config = game_config.key();
+        pull_ticket.buyer = ctx.accounts.buyer.key();
+        pull_ticket.commit_slot = Clock::get()?.slot;
+        pull_ticket.purchase_price = bundle.price;
+        pull_ticket.purchased_at = Clock::get()?.unix_timestamp;
+        pull_ticket.pull_count = bundle.size;
+        pull_ticket.pity_draws = pity_draws as u8;
+        pull_ticket.referrer = Pubkey::default();
+        pull_ticket.referral_fee = 0;
+        pull_ticket.bump = ctx.bumps.pull_ticket;
+    }
+
+    {
+        let player_state = &mut ctx.accounts.player_state;
+        player_state.game_config = game_config.key();
+        player_state.buyer = ctx.accounts.buyer.key();
+        player_state.record_purchase(bundle.size as u64, game_config)?;
+        player_state.bump = ctx.bumps.player_state;
+    }
+
+    // Transfer, held in escrow until the pulls are opened or refunded
+    ctx.transfer_purchase(bundle.price)?;
+
+    let game_config = &mut ctx.accounts.game_config;
+    game_config.escrowed_amount += bundle.price;
+
+    // Emit events
+    emit!(PullTicketBought {
+        ticket: ctx.accounts.pull_ticket.key(),
+        buyer: ctx.accounts.buyer.key(),
+        commit_slot: ctx.accounts.pull_ticket.commit_slot
+    });
+
+    emit!(BundleBought {
+        ticket: ctx.accounts.pull_ticket.key(),
+        buyer: ctx.accounts.buyer.key(),
+        bundle_size: bundle.size,
+        price: bundle.price
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct BuyPulls<'info> {
+    #[account(mut, has_one=game_vault)]
+    pub game_config: Account<'info, GameConfig>,
+    #[account(mut,
+        seeds = [b"pull_pool", game_config.key().as_ref()],
+        bump = pull_pool.bump
+    )]
+    pub pull_pool: Box<Account<'info, PullPool>>,
+    #[account(
+        init,
+        payer = buyer,
+        space = PullTicket::SIZE,
+        seeds = [b"pull_ticket", game_config.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub pull_ticket: Account<'info, PullTicket>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = PlayerState::SIZE,
+        seeds = [b"player_state", game_config.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub player_state: Box<Account<'info, PlayerState>>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    /// Required when the game is paid in tokens
+    #[account(mut,
+        token::mint = purchase_mint
+    )]
+    pub buyer_purchase_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub game_vault: Account<'info, TokenAccount>,
+    pub purchase_mint: Account<'info, Mint>,
+    /// Required when the game is paid in native SOL
+    #[account(mut,
+        seeds = [b"treasury", game_config.key().as_ref()],
+        bump = game_config.treasury_bump
+    )]
+    pub treasury: Option<SystemAccount<'info>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> BuyPulls<'info> {
+    fn payment_accounts(&self) -> PaymentAccounts<'_, 'info> {
+        PaymentAccounts {
+            game_config: &self.game_config,
+            buyer: self.buyer.as_ref(),
+            buyer_purchase_account: self
+                .buyer_purchase_account
+                .as_ref()
+                .map(|buyer_purchase_account| buyer_purchase_account.as_ref()),
+            game_vault: self.game_vault.as_ref(),
+            treasury: self.treasury.as_ref().map(|treasury| treasury.as_ref()),
+            token_program: self.token_program.as_ref(),
+            system_program: self.system_program.as_ref(),
+        }
+    }
+}
+
+impl<'info> BuyPullsInstruction for Context<'_, '_, '_, 'info, BuyPulls<'info>> {
+    fn transfer_purchase(&self, amount: u64) -> Result<()> {
+        self.accounts.payment_accounts().pay(amount)
+    }
+}
@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    error::GachaError,
+    event::TicketCancelled,
+    state::{CancelTicketParams, GameConfig, PlayerState, PullPool, PullTicket},
+    utils::{payment::PaymentAccounts, slot_hashes::get_slot_hash},
+};
+
+use super::CancelTicketInstruction;
+
+pub fn cancel_ticket<'info>(
+    ctx: Context<'_, '_, '_, 'info, CancelTicket<'info>>,
+    _params: CancelTicketParams,
+) -> Result<()> {
+    // Only a ticket that can no longer be revealed is cancelled, and only once the authority
+    // had until the refund timeout to reveal it with fallback randomness, so the outcome of
+    // a skipped reveal cannot be traded for a refund
+    ctx.verify_randomness_expired()?;
+
+    let refundable_at = ctx
+        .accounts
+        .pull_ticket
+        .purchased_at
+        .checked_add(ctx.accounts.game_config.refund_timeout)
+        .ok_or(GachaError::MathOverflow)?;
+    require!(
+        Clock::get()?.unix_timestamp >= refundable_at,
+        GachaError::RefundNotAvailable
+    );
+
+    let pull_count = ctx.accounts.pull_ticket.pull_count.max(1) as u64;
+    let pity_draws = ctx.accounts.pull_ticket.pity_draws as u64;
+    let amount = ctx.accounts.pull_ticket.purchase_price;
+
+    ctx.transfer_refund(amount)?;
+
+    // Release the reserved pulls and the purchases counted against the wallet limit,
+    // the ticket is closed by the `close` constraint
+    ctx.accounts.pull_pool.pending_tickets -= pull_count;
+    ctx.accounts.pull_pool.pending_pity_draws -= pity_draws;
+    ctx.accounts.player_state.pulls_bought -= pull_count;
+    ctx.accounts.game_config.escrowed_amount -= amount;
+
+    // Emit an event
+    emit!(TicketCancelled {
+        ticket: ctx.accounts.pull_ticket.key(),
+        buyer: ctx.accounts.buyer.key(),
+        amount
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelTicket<'info> {
+    #[account(mut, has_one = game_vault)]
+    pub game_config: Account<'info, GameConfig>,
+    #[account(mut,
+        seeds = [b"pull_pool", game_config.key().as_ref()],
+        bump = pull_pool.bump
+    )]
+    pub pull_pool: Box<Account<'info, PullPool>>,
+    #[account(mut,
+        close = buyer,
+        has_one = game_config,
+        has_one = buyer,
+        seeds = [b"pull_ticket", game_config.key().as_ref(), buyer.key().as_ref()],
+        bump = pull_ticket.bump
+    )]
+    pub pull_ticket: Account<'info, PullTicket>,
+    #[account(mut,
+        has_one = game_config,
+        has_one = buyer,
+        seeds = [b"player_state", game_config.key().as_ref(), buyer.key().as_ref()],
+        bump = player_state.bump
+    )]
+    pub player_state: Box<Account<'info, PlayerState>>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    /// Required when the game is paid in tokens
+    #[account(mut,
+        token::mint = game_config.purchase_mint,
+        token::authority = buyer,
+    )]
+    pub buyer_purchase_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub game_vault: Account<'info, TokenAccount>,
+    /// Required when the game is paid in native SOL
+    #[account(mut,
+        seeds = [b"treasury", game_config.key().as_ref()],
+        bump = game_config.treasury_bump
+    )]
+    pub treasury: Option<SystemAccount<'info>>,
+    /// CHECK: SlotHashes sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CancelTicket<'info> {
+    fn payment_accounts(&self) -> PaymentAccounts<'_, 'info> {
+        PaymentAccounts {
+            game_config: &self.game_config,
+            buyer: self.buyer.as_ref(),
+            buyer_purchase_account: self
+                .buyer_purchase_account
+                .as_ref()
+                .map(|buyer_purchase_account| buyer_purchase_account.as_ref()),
+            game_vault: self.game_vault.as_ref(),
+            treasury: self.treasury.as_ref().map(|treasury| treasury.as_ref()),
+            token_program: self.token_program.as_ref(),
+            system_program: self.system_program.as_ref(),
+        }
+    }
+}
+
+impl<'info> CancelTicketInstruction for Context<'_, '_, '_, 'info, CancelTicket<'info>> {
+    fn verify_randomness_expired(&self) -> Result<()> {
+        let pull_ticket = &self.accounts.pull_ticket;
+
+        require!(
+            Clock::get()?.slot > pull_ticket.commit_slot,
+            GachaError::RandomnessNotExpired
+        );
+        require!(
+            get_slot_hash(&self.accounts.slot_hashes, pull_ticket.commit_slot)?.is_none(),
+            GachaError::RandomnessNotExpired
+        );
+
+        Ok(())
+    }
+
+    fn transfer_refund(&self, amount: u64) -> Result<()> {
+        self.accounts.payment_accounts().refund(amount)
+    }
+}
@@ -0,0 +1,190 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use spl_token_2022::extension::confidential_transfer::instruction::{
+    ZeroCiphertextProofContext, ZeroCiphertextProofData,
+};
+
+use crate::{
+    error::GachaError,
+    event::PullClaimed,
+    state::{
+        ClaimPullParams, GameConfig, PlayerState, PrizeTable, Pull, AE_CIPHERTEXT_MAX_BASE64_LEN,
+    },
+    utils::{
+        reward_vault::RewardWithdrawAccounts,
+        token_2022::{verify_full_withdraw, Token2022},
+        zk_elgamal_proof_program::{get_proof_context, ZkElgamalProof},
+    },
+};
+
+use super::ClaimPullInstruction;
+
+pub fn claim_pull<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClaimPull<'info>>,
+    _params: ClaimPullParams,
+) -> Result<()> {
+    let pull_account = &ctx.accounts.pull;
+
+    require!(
+        pull_account.buyer == ctx.accounts.buyer.key(),
+        GachaError::InvalidBuyer
+    );
+    require!(!pull_account.claimed, GachaError::PullAlreadyClaimed);
+    require!(!pull_account.refunded, GachaError::PullAlreadyRefunded);
+    require!(
+        pull_account.withdraw_proofs_published,
+        GachaError::WithdrawProofsNotPublished
+    );
+
+    let amount = pull_account.withdraw_amount;
+
+    // The revealed reward has to match the odds disclosed for its tier
+    if ctx.accounts.game_config.has_prize_table() {
+        let prize_table = ctx
+            .accounts
+            .prize_table
+            .as_ref()
+            .ok_or(GachaError::MissingPrizeTable)?;
+        prize_table.verify_amount(pull_account.tier, amount)?;
+    }
+
+    // The whole verified reward has to leave the vault
+    let zero_balance_proof =
+        get_proof_context::<ZeroCiphertextProofData, ZeroCiphertextProofContext>(
+            Some(&ctx.accounts.zero_balance_proof_account),
+            None,
+            0,
+            None,
+        )?;
+    verify_full_withdraw(&ctx.accounts.reward_vault, &zero_balance_proof, amount)?;
+
+    let pull_account = &mut ctx.accounts.pull;
+    let new_decryptable_available_balance = pull_account.withdraw_decryptable_available_balance;
+    let decimals = ctx.accounts.reward_mint.decimals;
+
+    pull_account.revealed_amount = amount;
+    pull_account.claimed = true;
+
+    // Release the escrowed purchase for withdrawal, the referral fee is kept for pay_referral_fee
+    let purchase_price = pull_account.purchase_price;
+    let released_price = pull_account.released_price();
+    let referral_fee = pull_account.referral_fee;
+    let game_config = &mut ctx.accounts.game_config;
+    game_config.escrowed_amount -= purchase_price;
+    game_config.released_amount += released_price;
+    game_config.outstanding_referral_fees += referral_fee;
+
+    let player_state = &mut ctx.accounts.player_state;
+    player_state.pulls_opened += 1;
+
+    // Withdraw confidential fund with the published proofs, prepare for transferring
+    ctx.withdraw_reward(amount, decimals, new_decryptable_available_balance)?;
+
+    // Transfer reward to buyer
+    ctx.transfer_reward(amount, decimals)?;
+
+    // Emit an event
+    emit!(PullClaimed {
+        id: ctx.accounts.pull.id,
+        pull: ctx.accounts.pull.key(),
+        buyer: ctx.accounts.buyer.key(),
+        tier: ctx.accounts.pull.tier,
+        revealed_amount: ctx.accounts.pull.revealed_amount
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: ClaimPullParams)]
+pub struct ClaimPull<'info> {
+    #[account(mut,
+        has_one=game_config,
+        has_one=reward_vault,
+        seeds = [b"pull", game_config.key().as_ref(), params.pull_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pull: Account<'info, Pull>,
+    #[account(mut, has_one=reward_mint)]
+    pub game_config: Account<'info, GameConfig>,
+    pub buyer: Signer<'info>,
+    #[account(mut,
+        seeds = [b"player_state", game_config.key().as_ref(), buyer.key().as_ref()],
+        bump = player_state.bump
+    )]
+    pub player_state: Box<Account<'info, PlayerState>>,
+    /// CHECK reward vault
+    #[account(mut)]
+    pub reward_vault: AccountInfo<'info>,
+    #[account(mut,
+        token::mint = reward_mint,
+        token::authority = buyer,
+        token::token_program = token_2022_program
+    )]
+    pub buyer_reward_account: InterfaceAccount<'info, TokenAccount>,
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    /// Committed prize table, required when the game has one
+    #[account(address = game_config.prize_table)]
+    pub prize_table: Option<Box<Account<'info, PrizeTable>>>,
+
+    /// CHECK: Equality proof account published for the pull
+    #[account(
+        address = pull.withdraw_equality_proof,
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub equality_proof_account: AccountInfo<'info>,
+
+    /// CHECK: Range proof account published for the pull
+    #[account(
+        address = pull.withdraw_range_proof,
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub range_proof_account: AccountInfo<'info>,
+
+    /// CHECK: Zero ciphertext proof account published for the pull
+    #[account(
+        address = pull.withdraw_zero_balance_proof,
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub zero_balance_proof_account: AccountInfo<'info>,
+    pub zk_elgamal_proof_program: Program<'info, ZkElgamalProof>,
+    pub token_2022_program: Program<'info, Token2022>,
+}
+
+impl<'info> ClaimPullInstruction for Context<'_, '_, '_, 'info, ClaimPull<'info>> {
+    fn transfer_reward(&self, amount: u64, decimals: u8) -> Result<()> {
+        self.accounts.reward_withdraw_accounts().transfer_reward(
+            self.accounts.buyer_reward_account.as_ref(),
+            amount,
+            decimals,
+        )
+    }
+
+    fn withdraw_reward(
+        &self,
+        amount: u64,
+        decimals: u8,
+        new_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+    ) -> Result<()> {
+        self.accounts.reward_withdraw_accounts().withdraw_reward(
+            amount,
+            decimals,
+            new_decryptable_available_balance,
+        )
+    }
+}
+
+impl<'info> ClaimPull<'info> {
+    fn reward_withdraw_accounts(&self) -> RewardWithdrawAccounts<'_, 'info> {
+        RewardWithdrawAccounts {
+            pull: &self.pull,
+            reward_vault: &self.reward_vault,
+            reward_mint: self.reward_mint.as_ref(),
+            equality_proof_account: &self.equality_proof_account,
+            range_proof_account: &self.range_proof_account,
+            zk_elgamal_proof_program: &self.zk_elgamal_proof_program,
+            token_2022_program: &self.token_2022_program,
+        }
+    }
+}
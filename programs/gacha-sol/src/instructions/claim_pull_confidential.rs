@@ -0,0 +1,199 @@
+use std::str::FromStr;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use spl_token_2022::{
+    extension::confidential_transfer::instruction::transfer,
+    solana_zk_sdk::encryption::pod::{
+        auth_encryption::PodAeCiphertext, elgamal::PodElGamalCiphertext,
+    },
+};
+use spl_token_confidential_transfer_proof_extraction::instruction::ProofLocation;
+
+use crate::{
+    error::GachaError,
+    event::PullClaimed,
+    state::{ClaimPullConfidentialParams, GameConfig, PlayerState, Pull},
+    utils::{
+        cpi::invoke_signed_with_accounts, token_2022::Token2022,
+        zk_elgamal_proof_program::ZkElgamalProof,
+    },
+};
+
+use super::ClaimPullConfidentialInstruction;
+
+pub fn claim_pull_confidential<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClaimPullConfidential<'info>>,
+    _params: ClaimPullConfidentialParams,
+) -> Result<()> {
+    let pull_account = &ctx.accounts.pull;
+
+    require!(
+        pull_account.buyer == ctx.accounts.buyer.key(),
+        GachaError::InvalidBuyer
+    );
+    require!(!pull_account.claimed, GachaError::PullAlreadyClaimed);
+    require!(!pull_account.refunded, GachaError::PullAlreadyRefunded);
+    require!(
+        pull_account.transfer_proofs_verified,
+        GachaError::TransferProofsNotVerified
+    );
+
+    // A confidential reward cannot be checked against the tier ranges of the prize table
+    require!(
+        !ctx.accounts.game_config.has_prize_table(),
+        GachaError::ConfidentialDeliveryWithPrizeTable
+    );
+
+    // The reward amount stays confidential, revealed_amount is left unset
+    let pull_account = &mut ctx.accounts.pull;
+    pull_account.claimed = true;
+
+    // Release the escrowed purchase for withdrawal, the referral fee is kept for pay_referral_fee
+    let purchase_price = pull_account.purchase_price;
+    let released_price = pull_account.released_price();
+    let referral_fee = pull_account.referral_fee;
+    let game_config = &mut ctx.accounts.game_config;
+    game_config.escrowed_amount -= purchase_price;
+    game_config.released_amount += released_price;
+    game_config.outstanding_referral_fees += referral_fee;
+
+    let player_state = &mut ctx.accounts.player_state;
+    player_state.pulls_opened += 1;
+
+    // Transfer the confidential reward balance to the buyer
+    ctx.transfer_reward_confidential()?;
+
+    // Emit an event
+    emit!(PullClaimed {
+        id: ctx.accounts.pull.id,
+        pull: ctx.accounts.pull.key(),
+        buyer: ctx.accounts.buyer.key(),
+        tier: ctx.accounts.pull.tier,
+        revealed_amount: ctx.accounts.pull.revealed_amount
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: ClaimPullConfidentialParams)]
+pub struct ClaimPullConfidential<'info> {
+    #[account(mut,
+        has_one=game_config,
+        has_one=reward_vault,
+        seeds = [b"pull", game_config.key().as_ref(), params.pull_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pull: Box<Account<'info, Pull>>,
+    #[account(mut, has_one=reward_mint)]
+    pub game_config: Box<Account<'info, GameConfig>>,
+    pub buyer: Signer<'info>,
+    #[account(mut,
+        seeds = [b"player_state", game_config.key().as_ref(), buyer.key().as_ref()],
+        bump = player_state.bump
+    )]
+    pub player_state: Box<Account<'info, PlayerState>>,
+    /// CHECK reward vault
+    #[account(mut)]
+    pub reward_vault: AccountInfo<'info>,
+    #[account(mut,
+        token::mint = reward_mint,
+        token::authority = buyer,
+        token::token_program = token_2022_program
+    )]
+    pub buyer_reward_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Equality proof account verified for the pull
+    #[account(
+        address = pull.transfer_equality_proof,
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub equality_proof_account: AccountInfo<'info>,
+
+    /// CHECK: Ciphertext validity proof account verified for the pull
+    #[account(
+        address = pull.transfer_ciphertext_validity_proof,
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub ciphertext_validity_proof_account: AccountInfo<'info>,
+
+    /// CHECK: Range proof account verified for the pull
+    #[account(
+        address = pull.transfer_range_proof,
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub range_proof_account: AccountInfo<'info>,
+    pub zk_elgamal_proof_program: Program<'info, ZkElgamalProof>,
+    pub token_2022_program: Program<'info, Token2022>,
+}
+
+impl<'info> ClaimPullConfidentialInstruction
+    for Context<'_, '_, '_, 'info, ClaimPullConfidential<'info>>
+{
+    fn transfer_reward_confidential(&self) -> Result<()> {
+        let pull = &self.accounts.pull;
+
+        let signer_seeds = &pull.get_signer_seeds();
+        let signer = &[&signer_seeds[..]];
+
+        let new_decryptable_available_balance = PodAeCiphertext::from_str(
+            std::str::from_utf8(&pull.transfer_decryptable_available_balance)
+                .map_err(|_| GachaError::DecryptableBalanceConversionFailed)?,
+        )
+        .map_err(|_| GachaError::DecryptableBalanceConversionFailed)?;
+
+        let transfer_amount_auditor_ciphertext_lo = PodElGamalCiphertext::from_str(
+            std::str::from_utf8(&pull.transfer_amount_auditor_ciphertext_lo)
+                .map_err(|_| GachaError::CipherTextBalanceConversionFailed)?,
+        )
+        .map_err(|_| GachaError::CipherTextBalanceConversionFailed)?;
+
+        let transfer_amount_auditor_ciphertext_hi = PodElGamalCiphertext::from_str(
+            std::str::from_utf8(&pull.transfer_amount_auditor_ciphertext_hi)
+                .map_err(|_| GachaError::CipherTextBalanceConversionFailed)?,
+        )
+        .map_err(|_| GachaError::CipherTextBalanceConversionFailed)?;
+
+        let equality_proof_data_location =
+            ProofLocation::ContextStateAccount(self.accounts.equality_proof_account.key);
+
+        let ciphertext_validity_proof_data_location =
+            ProofLocation::ContextStateAccount(self.accounts.ciphertext_validity_proof_account.key);
+
+        let range_proof_data_location =
+            ProofLocation::ContextStateAccount(self.accounts.range_proof_account.key);
+
+        let transfer_instructions = transfer(
+            self.accounts.token_2022_program.key,
+            &self.accounts.reward_vault.key(),
+            &self.accounts.reward_mint.key(),
+            &self.accounts.buyer_reward_account.key(),
+            &new_decryptable_available_balance,
+            &transfer_amount_auditor_ciphertext_lo,
+            &transfer_amount_auditor_ciphertext_hi,
+            &pull.key(),
+            &[],
+            equality_proof_data_location,
+            ciphertext_validity_proof_data_location,
+            range_proof_data_location,
+        )?;
+
+        let account_infos = [
+            self.accounts.reward_vault.as_ref(),
+            &self.accounts.reward_mint.to_account_info(),
+            &self.accounts.buyer_reward_account.to_account_info(),
+            self.accounts.equality_proof_account.as_ref(),
+            self.accounts.ciphertext_validity_proof_account.as_ref(),
+            self.accounts.range_proof_account.as_ref(),
+            &self.accounts.pull.to_account_info(),
+        ];
+
+        for ix in transfer_instructions {
+            invoke_signed_with_accounts(&ix, &account_infos, signer)?;
+        }
+
+        Ok(())
+    }
+}
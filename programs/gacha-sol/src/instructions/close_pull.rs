@@ -0,0 +1,171 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{self, CloseAccount};
+use spl_token_2022::extension::confidential_transfer::instruction::empty_account;
+use spl_token_confidential_transfer_proof_extraction::instruction::ProofLocation;
+
+use crate::{
+    error::GachaError,
+    event::PullClosed,
+    state::{ClosePullParams, GameConfig, Pull},
+    utils::{
+        cpi::invoke_signed_with_accounts,
+        token_2022::Token2022,
+        zk_elgamal_proof_program::{close_context_state_account, ZkElgamalProof},
+    },
+};
+
+use super::ClosePullInstruction;
+
+pub fn close_pull<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClosePull<'info>>,
+    _params: ClosePullParams,
+) -> Result<()> {
+    // The reward has to be out of the vault, with the buyer or back with the authority
+    let pull = &ctx.accounts.pull;
+    require!(pull.claimed || pull.reclaimed, GachaError::PullNotClaimed);
+    require!(
+        !pull.has_unpaid_referral_fee(),
+        GachaError::ReferralFeeNotPaid
+    );
+
+    // Zero the confidential balance before the vault can be closed
+    ctx.empty_reward_vault()?;
+
+    // Close the reward vault, the pull account is closed by the `close` constraint
+    ctx.close_reward_vault()?;
+
+    // Published withdraw proofs can only be closed by the pull
+    if ctx.accounts.pull.withdraw_proofs_published {
+        ctx.close_withdraw_proofs()?;
+    }
+
+    // Emit an event
+    emit!(PullClosed {
+        id: ctx.accounts.pull.id,
+        pull: ctx.accounts.pull.key(),
+        rent_receiver: ctx.accounts.rent_receiver.key()
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: ClosePullParams)]
+pub struct ClosePull<'info> {
+    #[account(has_one = authority)]
+    pub game_config: Account<'info, GameConfig>,
+    #[account(mut,
+        has_one = game_config,
+        has_one = reward_vault,
+        seeds = [b"pull", game_config.key().as_ref(), params.pull_id.to_le_bytes().as_ref()],
+        bump = pull.bump,
+        close = rent_receiver
+    )]
+    pub pull: Account<'info, Pull>,
+    /// CHECK: reward vault
+    #[account(mut)]
+    pub reward_vault: AccountInfo<'info>,
+
+    /// CHECK: Zero ciphertext proof account for the vault available balance, the published one
+    /// once withdraw proofs were published
+    #[account(mut, owner = zk_elgamal_proof_program.key())]
+    pub zero_balance_proof_account: AccountInfo<'info>,
+    /// CHECK: Equality proof account published for the pull, required once published
+    #[account(mut,
+        address = pull.withdraw_equality_proof,
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub equality_proof_account: Option<AccountInfo<'info>>,
+    /// CHECK: Range proof account published for the pull, required once published
+    #[account(mut,
+        address = pull.withdraw_range_proof,
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub range_proof_account: Option<AccountInfo<'info>>,
+    pub authority: Signer<'info>,
+    /// CHECK: Receives the rent of the closed accounts
+    #[account(mut)]
+    pub rent_receiver: AccountInfo<'info>,
+    pub zk_elgamal_proof_program: Program<'info, ZkElgamalProof>,
+    pub token_2022_program: Program<'info, Token2022>,
+}
+
+impl<'info> ClosePullInstruction for Context<'_, '_, '_, 'info, ClosePull<'info>> {
+    fn empty_reward_vault(&self) -> Result<()> {
+        let signer_seeds = &self.accounts.pull.get_signer_seeds();
+        let signer = &[&signer_seeds[..]];
+
+        let zero_balance_proof_data_location =
+            ProofLocation::ContextStateAccount(self.accounts.zero_balance_proof_account.key);
+
+        let empty_account_instructions = empty_account(
+            self.accounts.token_2022_program.key,
+            &self.accounts.reward_vault.key(),
+            &self.accounts.pull.key(),
+            &[],
+            zero_balance_proof_data_location,
+        )?;
+
+        let account_infos = [
+            self.accounts.reward_vault.as_ref(),
+            self.accounts.zero_balance_proof_account.as_ref(),
+            self.accounts.pull.as_ref(),
+        ];
+
+        for ix in empty_account_instructions {
+            invoke_signed_with_accounts(&ix, &account_infos, signer)?;
+        }
+
+        Ok(())
+    }
+
+    fn close_reward_vault(&self) -> Result<()> {
+        let signer_seeds = &self.accounts.pull.get_signer_seeds();
+        let signer = &[&signer_seeds[..]];
+
+        let cpi_accounts = CloseAccount {
+            account: self.accounts.reward_vault.to_account_info(),
+            destination: self.accounts.rent_receiver.to_account_info(),
+            authority: self.accounts.pull.to_account_info(),
+        };
+        let token_program = self.accounts.token_2022_program.to_account_info().clone();
+        let cpi_context = CpiContext::new_with_signer(token_program, cpi_accounts, signer);
+
+        token_2022::close_account(cpi_context)?;
+
+        Ok(())
+    }
+
+    fn close_withdraw_proofs(&self) -> Result<()> {
+        let (Some(equality_proof_account), Some(range_proof_account)) = (
+            self.accounts.equality_proof_account.as_ref(),
+            self.accounts.range_proof_account.as_ref(),
+        ) else {
+            return err!(GachaError::WithdrawProofsNotClosed);
+        };
+        require_keys_eq!(
+            self.accounts.zero_balance_proof_account.key(),
+            self.accounts.pull.withdraw_zero_balance_proof,
+            GachaError::WithdrawProofsNotClosed
+        );
+
+        let signer_seeds = &self.accounts.pull.get_signer_seeds();
+        let signer = &[&signer_seeds[..]];
+
+        for proof_account in [
+            &self.accounts.zero_balance_proof_account,
+            equality_proof_account,
+            range_proof_account,
+        ] {
+            close_context_state_account(
+                proof_account,
+                &self.accounts.rent_receiver,
+                self.accounts.pull.as_ref(),
+                &self.accounts.zk_elgamal_proof_program,
+                signer,
+            )?;
+        }
+
+        Ok(())
+    }
+}
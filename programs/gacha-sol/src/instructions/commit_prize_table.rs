@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::GachaError,
+    event::PrizeTableCommitted,
+    state::{CommitPrizeTableParams, GameConfig, PrizeTable, Size, MAX_PRIZE_TIERS},
+};
+
+pub fn commit_prize_table<'info>(
+    ctx: Context<'_, '_, '_, 'info, CommitPrizeTable<'info>>,
+    params: CommitPrizeTableParams,
+) -> Result<()> {
+    // Every pull has to be covered by the table
+    require!(
+        ctx.accounts.game_config.last_pull_id == 0,
+        GachaError::PullsAlreadyCreated
+    );
+    require!(
+        !params.tiers.is_empty() && params.tiers.len() <= MAX_PRIZE_TIERS,
+        GachaError::InvalidPrizeTable
+    );
+    require!(
+        params
+            .tiers
+            .iter()
+            .all(|tier| tier.count > 0 && tier.min_amount <= tier.max_amount),
+        GachaError::InvalidPrizeTable
+    );
+
+    let prize_table = &mut ctx.accounts.prize_table;
+    prize_table.game_config = ctx.accounts.game_config.key();
+    prize_table.tiers = params.tiers;
+    prize_table.created_per_tier = [0; MAX_PRIZE_TIERS];
+    prize_table.bump = ctx.bumps.prize_table;
+
+    let game_config = &mut ctx.accounts.game_config;
+    game_config.prize_table = prize_table.key();
+
+    emit!(PrizeTableCommitted {
+        game_config: game_config.key(),
+        prize_table: prize_table.key(),
+        tiers: prize_table.tiers.clone()
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CommitPrizeTable<'info> {
+    #[account(mut, has_one = authority)]
+    pub game_config: Box<Account<'info, GameConfig>>,
+    #[account(
+        init,
+        payer = payer,
+        space = PrizeTable::SIZE,
+        seeds = [b"prize_table", game_config.key().as_ref()],
+        bump
+    )]
+    pub prize_table: Box<Account<'info, PrizeTable>>,
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,26 +1,17 @@
-use std::str::FromStr as _;
-
-use anchor_lang::{
-    prelude::*,
-    solana_program::{
-        self,
-        program::{invoke, invoke_signed},
-    },
-};
+use anchor_lang::{prelude::*, solana_program::sysvar};
 
 use crate::{
     error::GachaError,
     event::PullCreated,
-    state::{CreatePullParams, GameConfig, Pull, Size, AE_CIPHERTEXT_MAX_BASE64_LEN},
-    utils::token_2022::Token2022,
+    state::{CreatePullParams, GameConfig, PrizeTable, Pull, Size, AE_CIPHERTEXT_MAX_BASE64_LEN},
+    utils::{
+        reward_vault::RewardVaultAccounts, token_2022::Token2022,
+        zk_elgamal_proof_program::get_instruction_proof_data,
+    },
 };
-use spl_token_2022::{
-    extension::{confidential_transfer::instruction::configure_account, ExtensionType},
-    instruction::initialize_account3,
-    solana_zk_sdk::encryption::pod::auth_encryption::PodAeCiphertext,
-    state::Account as TokenAccount,
+use spl_token_2022::extension::confidential_transfer::instruction::{
+    PubkeyValidityProofContext, PubkeyValidityProofData,
 };
-use spl_token_confidential_transfer_proof_extraction::instruction::ProofLocation;
 
 use super::CreatePullInstruction;
 
@@ -28,30 +19,40 @@ pub fn create_pull<'info>(
     ctx: Context<'_, '_, '_, 'info, CreatePull<'info>>,
     params: CreatePullParams,
 ) -> Result<()> {
+    require!(!ctx.accounts.game_config.paused, GachaError::GamePaused);
     require!(
         ctx.accounts.game_config.last_pull_id + 1 == params.pull_id,
         GachaError::InvalidPullId
     );
-
-    {
-        let pull = &mut ctx.accounts.pull;
-        pull.id = params.pull_id;
-        pull.reward_vault = ctx.accounts.reward_vault.key();
-        pull.encrypted_amount = params.encrypted_amount;
-        pull.buyer = Pubkey::default();
-        pull.verified = false;
-        pull.claimed = false;
-        pull.revealed_amount = 0;
-        pull.pull_id_bytes = params.pull_id.to_le_bytes();
-        pull.bump = ctx.bumps.pull;
+    if ctx.accounts.game_config.has_prize_table() {
+        let prize_table = ctx
+            .accounts
+            .prize_table
+            .as_mut()
+            .ok_or(GachaError::MissingPrizeTable)?;
+        prize_table.record_pull(params.tier)?;
     }
 
+    let pull = Pull::new(
+        params.pull_id,
+        ctx.accounts.game_config.key(),
+        ctx.accounts.reward_vault.key(),
+        params.encrypted_amount,
+        params.tier,
+        ctx.bumps.pull,
+    );
+    ctx.accounts.pull.set_inner(pull);
+
     {
         let game_config = &mut ctx.accounts.game_config;
         game_config.last_pull_id = params.pull_id;
+        game_config.record_created_pull(params.tier)?;
     }
 
-    ctx.create_and_configure_reward_vault(&params.decryptable_zero_balance_base64)?;
+    ctx.create_and_configure_reward_vault(
+        &params.decryptable_zero_balance_base64,
+        params.proof_instruction_offset,
+    )?;
 
     // Emit an event
     {
@@ -59,7 +60,8 @@ pub fn create_pull<'info>(
         emit!(PullCreated {
             id: pull.id,
             pull: ctx.accounts.pull.key(),
-            encrypted_amount: pull.encrypted_amount
+            encrypted_amount: pull.encrypted_amount,
+            tier: pull.tier
         });
     }
 
@@ -69,18 +71,18 @@ pub fn create_pull<'info>(
 #[derive(Accounts)]
 #[instruction(params : CreatePullParams)]
 pub struct CreatePull<'info> {
+    #[account(mut, has_one = authority, has_one=reward_mint)]
+    pub game_config: Box<Account<'info, GameConfig>>,
+
     #[account(
         init,
         payer = payer,
         space = Pull::SIZE,
-        seeds = [b"pull", params.pull_id.to_le_bytes().as_ref()],
+        seeds = [b"pull", game_config.key().as_ref(), params.pull_id.to_le_bytes().as_ref()],
         bump
     )]
     pub pull: Account<'info, Pull>,
 
-    #[account(mut, has_one = authority, has_one=reward_mint)]
-    pub game_config: Box<Account<'info, GameConfig>>,
-
     /// CHECK: Token account to be internally created and initialized
     #[account(
         mut,
@@ -92,8 +94,16 @@ pub struct CreatePull<'info> {
     /// CHECK: Token-2022 mint with Confidential Transfer extension
     pub reward_mint: AccountInfo<'info>,
 
-    /// CHECK: A PubkeyValidityProofData account
-    pub pubkey_validity_proof_data: AccountInfo<'info>,
+    /// Committed prize table, required when the game has one
+    #[account(mut, address = game_config.prize_table)]
+    pub prize_table: Option<Box<Account<'info, PrizeTable>>>,
+
+    /// CHECK: A PubkeyValidityProofData account, not needed when the proof is in a sibling instruction
+    pub pubkey_validity_proof_data: Option<AccountInfo<'info>>,
+
+    /// CHECK: Instructions sysvar, needed when the proof is in a sibling instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<AccountInfo<'info>>,
 
     pub authority: Signer<'info>,
     #[account(mut)]
@@ -105,120 +115,51 @@ pub struct CreatePull<'info> {
 
 impl<'info> CreatePullInstruction for Context<'_, '_, '_, 'info, CreatePull<'info>> {
     fn initialize_token_account_with_extension(&self) -> Result<()> {
-        let required_space = spl_token_2022::extension::ExtensionType::try_calculate_account_len::<
-            TokenAccount,
-        >(&[ExtensionType::ConfidentialTransferAccount])?;
-
-        let lamports = self.accounts.rent.minimum_balance(required_space);
-        let pull_pubkey = self.accounts.pull.key();
-        let reward_vault_pubkey = self.accounts.reward_vault.key();
-        let mint_pubkey = self.accounts.reward_mint.key();
-        let payer_pubkey = self.accounts.payer.key();
-        let token_program_id = self.accounts.token_program.key();
-
-        let create_account_ix = solana_program::system_instruction::create_account(
-            &payer_pubkey,
-            &reward_vault_pubkey,
-            lamports,
-            required_space as u64,
-            &token_program_id,
-        );
-
-        let init_account_ix = initialize_account3(
-            &token_program_id,
-            &reward_vault_pubkey,
-            &mint_pubkey,
-            &pull_pubkey,
-        )?;
-
-        let reward_vault_seeds = &[
-            b"reward_vault",
-            pull_pubkey.as_ref(),
-            &[self.bumps.reward_vault],
-        ];
-
-        invoke_signed(
-            &create_account_ix,
-            &[
-                self.accounts.payer.to_account_info(),
-                self.accounts.reward_vault.to_account_info(),
-                self.accounts.system_program.to_account_info(),
-            ],
-            &[&reward_vault_seeds[..]],
-        )?;
-
-        invoke(
-            &init_account_ix,
-            &[
-                self.accounts.reward_vault.to_account_info(),
-                self.accounts.reward_mint.to_account_info(),
-                self.accounts.pull.to_account_info(),
-                self.accounts.rent.to_account_info(),
-            ],
-        )?;
-
-        Ok(())
+        self.accounts.reward_vault_accounts().create_reward_vault(
+            self.accounts.pull.as_ref(),
+            &self.accounts.reward_vault,
+            self.bumps.reward_vault,
+        )
     }
 
     fn configure_token_account(
         &self,
         decryptable_zero_balance_base64: &[u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+        proof_instruction_offset: i8,
     ) -> Result<()> {
-        let signer_seeds = &self.accounts.pull.get_signer_seeds();
-        let signer = &[&signer_seeds[..]];
-
-        let decryptable_zero_balance = PodAeCiphertext::from_str(
-            std::str::from_utf8(decryptable_zero_balance_base64)
-                .map_err(|_| GachaError::DecryptableBalanceConversionFailed)?,
-        )
-        .map_err(|_| GachaError::DecryptableBalanceConversionFailed)?;
-
-        let proof_data_location =
-            ProofLocation::ContextStateAccount(self.accounts.pubkey_validity_proof_data.key);
-
-        let configure_account_ixs = configure_account(
-            &self.accounts.token_program.key,
-            &self.accounts.reward_vault.key(),
-            &self.accounts.reward_mint.key(),
-            &decryptable_zero_balance,
-            65536, // maximum_pending_balance_credit_counter
-            &self.accounts.pull.key(),
-            &[],
-            proof_data_location,
-        )?;
-
-        for ix in configure_account_ixs {
-            let accounts = ix
-                .accounts
-                .iter()
-                .map(|acc| {
-                    Ok(match acc.pubkey {
-                        k if k == self.accounts.reward_vault.key() => {
-                            self.accounts.reward_vault.to_account_info().clone()
-                        }
-                        k if k == self.accounts.reward_mint.key() => {
-                            self.accounts.reward_mint.to_account_info().clone()
-                        }
-                        k if k == self.accounts.pubkey_validity_proof_data.key() => self
-                            .accounts
-                            .pubkey_validity_proof_data
-                            .to_account_info()
-                            .clone(),
-                        k if k == self.accounts.pull.key() => {
-                            self.accounts.pull.to_account_info().clone()
-                        }
-                        _ => return Err(GachaError::InvalidAccount.into()),
-                    })
-                })
-                .collect::<Result<Vec<_>>>()?;
-
-            invoke_signed(&ix, &accounts, signer)?;
-        }
-
-        Ok(())
+        let proof_data =
+            get_instruction_proof_data::<PubkeyValidityProofData, PubkeyValidityProofContext>(
+                self.accounts.instructions_sysvar.as_ref(),
+                proof_instruction_offset,
+            )?;
+
+        self.accounts
+            .reward_vault_accounts()
+            .configure_reward_vault(
+                &self.accounts.pull,
+                self.accounts.pull.as_ref(),
+                &self.accounts.reward_vault,
+                decryptable_zero_balance_base64,
+                proof_data.as_ref(),
+                proof_instruction_offset,
+            )
     }
 
     fn get_reward_vault_pubkey(&self) -> Pubkey {
         self.accounts.reward_vault.key()
     }
 }
+
+impl<'info> CreatePull<'info> {
+    fn reward_vault_accounts(&self) -> RewardVaultAccounts<'_, 'info> {
+        RewardVaultAccounts {
+            reward_mint: &self.reward_mint,
+            payer: &self.payer,
+            pubkey_validity_proof_account: self.pubkey_validity_proof_data.as_ref(),
+            instructions_sysvar: self.instructions_sysvar.as_ref(),
+            system_program: &self.system_program,
+            token_program: &self.token_program,
+            rent: &self.rent,
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use anchor_lang::{prelude::*, solana_program::sysvar};
+use spl_token_2022::extension::confidential_transfer::instruction::{
+    PubkeyValidityProofContext, PubkeyValidityProofData,
+};
+
+use crate::{
+    error::GachaError,
+    event::PullCreated,
+    state::{CreatePullsParams, GameConfig, NewPull, PrizeTable, Pull, Size},
+    utils::{
+        account::create_pda_account, reward_vault::RewardVaultAccounts, token_2022::Token2022,
+        zk_elgamal_proof_program::get_instruction_proof_data,
+    },
+};
+
+use super::CreatePullsInstruction;
+
+/// Remaining accounts per pull: the pull and its reward vault
+const ACCOUNTS_PER_PULL: usize = 2;
+
+pub fn create_pulls<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePulls<'info>>,
+    params: CreatePullsParams,
+) -> Result<()> {
+    require!(!ctx.accounts.game_config.paused, GachaError::GamePaused);
+    require!(!params.pulls.is_empty(), GachaError::InvalidPullId);
+    require!(
+        ctx.accounts.game_config.last_pull_id + 1 == params.first_pull_id,
+        GachaError::InvalidPullId
+    );
+    require!(
+        ctx.remaining_accounts.len() == params.pulls.len() * ACCOUNTS_PER_PULL,
+        GachaError::InvalidPullAccounts
+    );
+
+    // All reward vaults share the same ElGamal pubkey, so one proof configures them all
+    let proof_data =
+        get_instruction_proof_data::<PubkeyValidityProofData, PubkeyValidityProofContext>(
+            ctx.accounts.instructions_sysvar.as_ref(),
+            params.proof_instruction_offset,
+        )?;
+
+    for (index, (new_pull, pull_accounts)) in params
+        .pulls
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(ACCOUNTS_PER_PULL))
+        .enumerate()
+    {
+        let pull_id = params.first_pull_id + index as u64;
+        let (pull_account, reward_vault) = (&pull_accounts[0], &pull_accounts[1]);
+
+        if ctx.accounts.game_config.has_prize_table() {
+            let prize_table = ctx
+                .accounts
+                .prize_table
+                .as_mut()
+                .ok_or(GachaError::MissingPrizeTable)?;
+            prize_table.record_pull(new_pull.tier)?;
+        }
+        ctx.accounts
+            .game_config
+            .record_created_pull(new_pull.tier)?;
+
+        let (pull, reward_vault_bump) =
+            ctx.create_pull_account(pull_account, reward_vault, pull_id, new_pull)?;
+        let reward_vault_accounts = ctx.accounts.reward_vault_accounts();
+        reward_vault_accounts.create_reward_vault(pull_account, reward_vault, reward_vault_bump)?;
+        reward_vault_accounts.configure_reward_vault(
+            &pull,
+            pull_account,
+            reward_vault,
+            &params.decryptable_zero_balance_base64,
+            proof_data.as_ref(),
+            params.proof_instruction_offset,
+        )?;
+
+        emit!(PullCreated {
+            id: pull.id,
+            pull: pull_account.key(),
+            encrypted_amount: pull.encrypted_amount,
+            tier: pull.tier
+        });
+    }
+
+    let game_config = &mut ctx.accounts.game_config;
+    game_config.last_pull_id = params.first_pull_id + params.pulls.len() as u64 - 1;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreatePulls<'info> {
+    #[account(mut, has_one = authority, has_one=reward_mint)]
+    pub game_config: Box<Account<'info, GameConfig>>,
+
+    /// CHECK: Token-2022 mint with Confidential Transfer extension
+    pub reward_mint: AccountInfo<'info>,
+
+    /// Committed prize table, required when the game has one
+    #[account(mut, address = game_config.prize_table)]
+    pub prize_table: Option<Box<Account<'info, PrizeTable>>>,
+
+    /// CHECK: A PubkeyValidityProofData account, not needed when the proof is in a sibling instruction
+    pub pubkey_validity_proof_data: Option<AccountInfo<'info>>,
+
+    /// CHECK: Instructions sysvar, needed when the proof is in a sibling instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<AccountInfo<'info>>,
+
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> CreatePullsInstruction<'info> for Context<'_, '_, '_, 'info, CreatePulls<'info>> {
+    fn create_pull_account(
+        &self,
+        pull_account: &AccountInfo<'info>,
+        reward_vault: &AccountInfo<'info>,
+        pull_id: u64,
+        new_pull: &NewPull,
+    ) -> Result<(Pull, u8)> {
+        let game_config_pubkey = self.accounts.game_config.key();
+        let pull_id_bytes = pull_id.to_le_bytes();
+
+        let (pull_pubkey, pull_bump) = Pubkey::find_program_address(
+            &[b"pull", game_config_pubkey.as_ref(), pull_id_bytes.as_ref()],
+            &crate::ID,
+        );
+        let (reward_vault_pubkey, reward_vault_bump) =
+            Pubkey::find_program_address(&[b"reward_vault", pull_pubkey.as_ref()], &crate::ID);
+
+        require!(
+            pull_account.key() == pull_pubkey && reward_vault.key() == reward_vault_pubkey,
+            GachaError::InvalidPullAccounts
+        );
+
+        let pull_seeds = &[
+            b"pull",
+            game_config_pubkey.as_ref(),
+            pull_id_bytes.as_ref(),
+            &[pull_bump],
+        ];
+
+        create_pda_account(
+            &self.accounts.payer,
+            pull_account,
+            &self.accounts.system_program,
+            &self.accounts.rent,
+            Pull::SIZE,
+            &crate::ID,
+            &[&pull_seeds[..]],
+        )?;
+
+        let pull = Pull::new(
+            pull_id,
+            game_config_pubkey,
+            reward_vault_pubkey,
+            new_pull.encrypted_amount,
+            new_pull.tier,
+            pull_bump,
+        );
+
+        let mut data = pull_account.try_borrow_mut_data()?;
+        pull.try_serialize(&mut &mut data[..])?;
+
+        Ok((pull, reward_vault_bump))
+    }
+}
+
+impl<'info> CreatePulls<'info> {
+    fn reward_vault_accounts(&self) -> RewardVaultAccounts<'_, 'info> {
+        RewardVaultAccounts {
+            reward_mint: &self.reward_mint,
+            payer: &self.payer,
+            pubkey_validity_proof_account: self.pubkey_validity_proof_data.as_ref(),
+            instructions_sysvar: self.instructions_sysvar.as_ref(),
+            system_program: &self.system_program,
+            token_program: &self.token_program,
+            rent: &self.rent,
+        }
+    }
+}
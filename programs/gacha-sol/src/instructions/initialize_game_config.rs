@@ -1,17 +1,17 @@
-use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, TokenAccount};
-use spl_token_2022::{
-    extension::{
-        confidential_transfer::ConfidentialTransferMint, BaseStateWithExtensions,
-        StateWithExtensions,
-    },
-    state::Mint as Mint2022,
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer},
 };
+use anchor_spl::token::{Mint, Token, TokenAccount};
 
 use crate::{
     error::GachaError,
     event::GameConfigInitialized,
-    state::{GameConfig, InitializeGameConfigParams, Size},
+    state::{
+        GameConfig, InitializeGameConfigParams, PaymentMode, PullBundle, PullPool, Size,
+        MAX_PRIZE_TIERS, MAX_PULL_BUNDLES,
+    },
+    utils::token_2022::verify_confidential_transfer_mint,
 };
 
 use super::InitializeGameConfigInstruction;
@@ -21,54 +21,128 @@ pub fn initialize_game_config<'info>(
     params: InitializeGameConfigParams,
 ) -> Result<()> {
     require!(params.pull_price > 0, GachaError::InvalidZeroPullPrice);
+    require!(params.refund_timeout > 0, GachaError::InvalidRefundTimeout);
+    require!(
+        GameConfig::is_valid_sale_window(params.sale_start, params.sale_end),
+        GachaError::InvalidSaleWindow
+    );
 
     // Verify reward mint
     ctx.verify_reward_mint()?;
 
+    // Keep the treasury rent exempt so native SOL purchases of any price can land in it,
+    // token games have no use for it
+    match params.payment_mode {
+        PaymentMode::Token => require!(
+            ctx.accounts.treasury.is_none(),
+            GachaError::InvalidPaymentAccounts
+        ),
+        PaymentMode::NativeSol => ctx.fund_treasury()?,
+    }
+
     // Setup game config
     let game_config = &mut ctx.accounts.game_config;
+    game_config.game_id = params.game_id;
     game_config.authority = ctx.accounts.authority.key();
+    game_config.pending_authority = Pubkey::default();
     game_config.purchase_mint = ctx.accounts.purchase_mint.key();
     game_config.reward_mint = ctx.accounts.reward_mint.key();
     game_config.game_vault = ctx.accounts.game_vault.key();
     game_config.pull_price = params.pull_price;
     game_config.last_pull_id = 0;
+    game_config.paused = false;
+    game_config.refund_timeout = params.refund_timeout;
+    game_config.escrowed_amount = 0;
+    game_config.released_amount = 0;
+    game_config.withdrawn_amount = 0;
+    game_config.outstanding_referral_fees = 0;
+    game_config.unsold_pulls_per_tier = [0; MAX_PRIZE_TIERS];
+    game_config.prize_table = Pubkey::default();
+    game_config.bundles = [PullBundle::default(); MAX_PULL_BUNDLES];
+    game_config.pity_threshold = 0;
+    game_config.pity_tier = 0;
+    game_config.sale_start = params.sale_start;
+    game_config.sale_end = params.sale_end;
+    game_config.max_pulls_per_wallet = 0;
+    game_config.merkle_root = [0; 32];
+    game_config.presale_start = 0;
+    game_config.presale_end = 0;
+    game_config.referral_bps = 0;
+    game_config.fee_schedule = Pubkey::default();
+    game_config.payment_mode = params.payment_mode;
+    game_config.treasury_bump = ctx.bumps.treasury.unwrap_or_default();
+    game_config.game_id_bytes = params.game_id.to_le_bytes();
+    game_config.bump = ctx.bumps.game_config;
+
+    // Setup pull pool
+    let pull_pool = &mut ctx.accounts.pull_pool;
+    pull_pool.game_config = game_config.key();
+    pull_pool.pending_tickets = 0;
+    pull_pool.pending_pity_draws = 0;
+    pull_pool.pull_ids = Vec::new();
+    pull_pool.pull_pity = Vec::new();
+    pull_pool.bump = ctx.bumps.pull_pool;
 
     // Emit event
     emit!(GameConfigInitialized {
         game_config: game_config.key(),
+        game_id: game_config.game_id,
         authority: game_config.authority,
         purchase_mint: game_config.purchase_mint,
         reward_mint: game_config.reward_mint,
         game_vault: game_config.game_vault,
-        pull_price: game_config.pull_price
+        pull_price: game_config.pull_price,
+        refund_timeout: game_config.refund_timeout,
+        sale_start: game_config.sale_start,
+        sale_end: game_config.sale_end,
+        payment_mode: game_config.payment_mode
     });
 
     Ok(())
 }
 
 #[derive(Accounts)]
+#[instruction(params: InitializeGameConfigParams)]
 pub struct InitializeGameConfig<'info> {
     #[account(
         init,
         payer = payer,
         space = GameConfig::SIZE,
-        seeds = [b"game_config"],
+        seeds = [b"game_config", params.game_id.to_le_bytes().as_ref()],
         bump
     )]
     pub game_config: Account<'info, GameConfig>,
+    #[account(
+        init,
+        payer = payer,
+        space = PullPool::SIZE,
+        seeds = [b"pull_pool", game_config.key().as_ref()],
+        bump
+    )]
+    pub pull_pool: Box<Account<'info, PullPool>>,
     /// CHECK: Authority account.
     pub authority: AccountInfo<'info>,
     pub purchase_mint: Box<Account<'info, Mint>>,
     /// CHECK: Token-2022 mint with Confidential Transfer extension
     pub reward_mint: AccountInfo<'info>,
     #[account(
+        init,
+        payer = payer,
+        seeds = [b"game_vault", game_config.key().as_ref(), purchase_mint.key().as_ref()],
+        bump,
         token::mint = purchase_mint,
-        token::authority = authority,
+        token::authority = game_config,
     )]
     pub game_vault: Box<Account<'info, TokenAccount>>,
+    /// Holds native SOL purchases, required when the game is paid in native SOL
+    #[account(mut,
+        seeds = [b"treasury", game_config.key().as_ref()],
+        bump
+    )]
+    pub treasury: Option<SystemAccount<'info>>,
     #[account(mut)]
     pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
@@ -76,16 +150,28 @@ impl<'info> InitializeGameConfigInstruction
     for Context<'_, '_, '_, 'info, InitializeGameConfig<'info>>
 {
     fn verify_reward_mint(&self) -> Result<()> {
-        let mint_data = self.accounts.reward_mint.data.borrow();
-        let mint_state = StateWithExtensions::<Mint2022>::unpack(&mint_data)?;
+        verify_confidential_transfer_mint(&self.accounts.reward_mint)
+    }
+
+    fn fund_treasury(&self) -> Result<()> {
+        let treasury = self
+            .accounts
+            .treasury
+            .as_ref()
+            .ok_or(GachaError::InvalidPaymentAccounts)?;
+        let minimum_balance = Rent::get()?.minimum_balance(0);
+        if treasury.lamports() >= minimum_balance {
+            return Ok(());
+        }
 
-        require!(
-            mint_state
-                .get_extension::<ConfidentialTransferMint>()
-                .is_ok(),
-            GachaError::InvalidRewardMint
-        );
+        let cpi_accounts = Transfer {
+            from: self.accounts.payer.to_account_info(),
+            to: treasury.to_account_info(),
+        };
+        let system_program = self.accounts.system_program.to_account_info();
+        let cpi_context = CpiContext::new(system_program, cpi_accounts);
 
+        system_program::transfer(cpi_context, minimum_balance - treasury.lamports())?;
         Ok(())
     }
 }
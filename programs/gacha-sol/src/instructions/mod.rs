@@ -1,15 +1,55 @@
+mod accept_authority;
 mod apply_pull_pending_balance;
 mod buy_pull;
+mod buy_pulls;
+mod cancel_ticket;
+mod claim_pull;
+mod claim_pull_confidential;
+mod close_pull;
+mod commit_prize_table;
 mod create_pull;
+mod create_pulls;
 mod initialize_game_config;
 mod open_pull;
+mod pause_game;
+mod pay_referral_fee;
+mod propose_authority;
+mod publish_withdraw_proofs;
+mod reclaim_pull;
+mod refund_pull;
+mod resume_game;
+mod reveal_pull;
+mod set_fee_schedule;
 mod traits;
+mod update_game_config;
 mod verify_pull;
+mod verify_pull_transfer;
+mod withdraw_proceeds;
 
+pub use accept_authority::*;
 pub use apply_pull_pending_balance::*;
 pub use buy_pull::*;
+pub use buy_pulls::*;
+pub use cancel_ticket::*;
+pub use claim_pull::*;
+pub use claim_pull_confidential::*;
+pub use close_pull::*;
+pub use commit_prize_table::*;
 pub use create_pull::*;
+pub use create_pulls::*;
 pub use initialize_game_config::*;
 pub use open_pull::*;
+pub use pause_game::*;
+pub use pay_referral_fee::*;
+pub use propose_authority::*;
+pub use publish_withdraw_proofs::*;
+pub use reclaim_pull::*;
+pub use refund_pull::*;
+pub use resume_game::*;
+pub use reveal_pull::*;
+pub use set_fee_schedule::*;
 pub use traits::*;
+pub use update_game_config::*;
 pub use verify_pull::*;
+pub use verify_pull_transfer::*;
+pub use withdraw_proceeds::*;
@@ -1,22 +1,34 @@
 use std::str::FromStr;
 
-use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+use anchor_lang::{prelude::*, solana_program::sysvar};
 use anchor_spl::{
     token::Token,
     token_2022::{self, TransferChecked},
     token_interface::{Mint, TokenAccount},
 };
 use spl_token_2022::{
-    extension::confidential_transfer::instruction::withdraw,
+    extension::confidential_transfer::instruction::{
+        inner_withdraw, BatchedRangeProofContext, BatchedRangeProofU64Data,
+        CiphertextCommitmentEqualityProofContext, CiphertextCommitmentEqualityProofData,
+        ZeroCiphertextProofContext, ZeroCiphertextProofData,
+    },
     solana_zk_sdk::encryption::pod::auth_encryption::PodAeCiphertext,
 };
-use spl_token_confidential_transfer_proof_extraction::instruction::ProofLocation;
 
 use crate::{
     error::GachaError,
     event::PullClaimed,
-    state::{GameConfig, OpenPullParams, Pull, AE_CIPHERTEXT_MAX_BASE64_LEN},
-    utils::{token_2022::Token2022, zk_elgamal_proof_program::ZkElgamalProof},
+    state::{
+        GameConfig, OpenPullParams, PlayerState, PrizeTable, Pull, AE_CIPHERTEXT_MAX_BASE64_LEN,
+    },
+    utils::{
+        cpi::invoke_signed_with_accounts,
+        token_2022::{verify_full_withdraw, Token2022},
+        zk_elgamal_proof_program::{
+            close_context_state_account, get_instruction_proof_data, get_proof_context,
+            get_proof_location, ZkElgamalProof,
+        },
+    },
 };
 
 use super::OpenPullInstruction;
@@ -25,32 +37,77 @@ pub fn open_pull<'info>(
     ctx: Context<'_, '_, '_, 'info, OpenPull<'info>>,
     params: OpenPullParams,
 ) -> Result<()> {
-    let pull_account = &mut ctx.accounts.pull;
+    let pull_account = &ctx.accounts.pull;
 
     require!(
         pull_account.buyer == ctx.accounts.buyer.key(),
         GachaError::InvalidBuyer
     );
     require!(!pull_account.claimed, GachaError::PullAlreadyClaimed);
+    require!(!pull_account.refunded, GachaError::PullAlreadyRefunded);
+
+    // The revealed reward has to match the odds disclosed for its tier
+    if ctx.accounts.game_config.has_prize_table() {
+        let prize_table = ctx
+            .accounts
+            .prize_table
+            .as_ref()
+            .ok_or(GachaError::MissingPrizeTable)?;
+        prize_table.verify_amount(pull_account.tier, params.amount)?;
+    }
 
+    // The whole verified reward has to leave the vault
+    let zero_balance_proof =
+        get_proof_context::<ZeroCiphertextProofData, ZeroCiphertextProofContext>(
+            ctx.accounts.zero_balance_proof_account.as_ref(),
+            ctx.accounts.instructions_sysvar.as_ref(),
+            params.zero_balance_proof_instruction_offset,
+            None,
+        )?;
+    verify_full_withdraw(
+        &ctx.accounts.reward_vault,
+        &zero_balance_proof,
+        params.amount,
+    )?;
+
+    let pull_account = &mut ctx.accounts.pull;
     pull_account.revealed_amount = params.amount;
     pull_account.claimed = true;
 
+    // Release the escrowed purchase for withdrawal, the referral fee is kept for pay_referral_fee
+    let purchase_price = pull_account.purchase_price;
+    let released_price = pull_account.released_price();
+    let referral_fee = pull_account.referral_fee;
+    let game_config = &mut ctx.accounts.game_config;
+    game_config.escrowed_amount -= purchase_price;
+    game_config.released_amount += released_price;
+    game_config.outstanding_referral_fees += referral_fee;
+
+    let player_state = &mut ctx.accounts.player_state;
+    player_state.pulls_opened += 1;
+
     // Withdraw confidential fund, prepare for transferring
     ctx.withdraw_reward(
         params.amount,
         params.decimals,
         params.new_decryptable_available_balance,
+        params.equality_proof_instruction_offset,
+        params.range_proof_instruction_offset,
     )?;
 
     // Transfer reward to buyer
     ctx.transfer_reward(params.amount, params.decimals)?;
 
+    // Reclaim the proof rent when a destination is given
+    ctx.close_proof_contexts()?;
+
     // Emit an event
     emit!(PullClaimed {
         id: ctx.accounts.pull.id,
         pull: ctx.accounts.pull.key(),
-        buyer: ctx.accounts.buyer.key()
+        buyer: ctx.accounts.buyer.key(),
+        tier: ctx.accounts.pull.tier,
+        revealed_amount: ctx.accounts.pull.revealed_amount
     });
 
     Ok(())
@@ -60,12 +117,13 @@ pub fn open_pull<'info>(
 #[instruction(params: OpenPullParams)]
 pub struct OpenPull<'info> {
     #[account(mut,
+        has_one=game_config,
         has_one=reward_vault,
-        seeds = [b"pull", params.pull_id.to_le_bytes().as_ref()],
+        seeds = [b"pull", game_config.key().as_ref(), params.pull_id.to_le_bytes().as_ref()],
         bump
     )]
     pub pull: Account<'info, Pull>,
-    #[account(
+    #[account(mut,
         has_one=reward_mint,
         has_one=authority,
     )]
@@ -73,6 +131,11 @@ pub struct OpenPull<'info> {
     /// CHECK buyer account
     #[account()]
     pub buyer: AccountInfo<'info>,
+    #[account(mut,
+        seeds = [b"player_state", game_config.key().as_ref(), buyer.key().as_ref()],
+        bump = player_state.bump
+    )]
+    pub player_state: Box<Account<'info, PlayerState>>,
     /// CHECK reward vault
     #[account(mut,
         // token::token_program = token_2022_program
@@ -87,17 +150,36 @@ pub struct OpenPull<'info> {
     pub buyer_reward_account: InterfaceAccount<'info, TokenAccount>,
     pub reward_mint: InterfaceAccount<'info, Mint>,
 
-    /// CHECK: Equality proof account
-    #[account(
+    /// Committed prize table, required when the game has one
+    #[account(address = game_config.prize_table)]
+    pub prize_table: Option<Box<Account<'info, PrizeTable>>>,
+
+    /// CHECK: Equality proof account, not needed when the proof is in a sibling instruction
+    #[account(mut,
         owner = zk_elgamal_proof_program.key()
     )]
-    pub equality_proof_account: AccountInfo<'info>,
+    pub equality_proof_account: Option<AccountInfo<'info>>,
 
-    /// CHECK: Range proof account
-    #[account(
+    /// CHECK: Range proof account, not needed when the proof is in a sibling instruction
+    #[account(mut,
         owner = zk_elgamal_proof_program.key()
     )]
-    pub range_proof_account: AccountInfo<'info>,
+    pub range_proof_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: Zero ciphertext proof account for the remaining balance, not needed when the
+    /// proof is in a sibling instruction
+    #[account(mut,
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub zero_balance_proof_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: Receives the proof accounts rent, the proof accounts are kept open when missing
+    #[account(mut)]
+    pub context_state_destination: Option<AccountInfo<'info>>,
+
+    /// CHECK: Instructions sysvar, needed when a proof is in a sibling instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<AccountInfo<'info>>,
     pub authority: Signer<'info>,
     pub zk_elgamal_proof_program: Program<'info, ZkElgamalProof>,
     pub token_program: Program<'info, Token>,
@@ -128,6 +210,8 @@ impl<'info> OpenPullInstruction for Context<'_, '_, '_, 'info, OpenPull<'info>>
         amount: u64,
         decimals: u8,
         new_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+        equality_proof_instruction_offset: i8,
+        range_proof_instruction_offset: i8,
     ) -> Result<()> {
         let pull = &self.accounts.pull;
 
@@ -140,13 +224,33 @@ impl<'info> OpenPullInstruction for Context<'_, '_, '_, 'info, OpenPull<'info>>
         )
         .map_err(|_| GachaError::DecryptableBalanceConversionFailed)?;
 
-        let equality_proof_data_location =
-            ProofLocation::ContextStateAccount(self.accounts.equality_proof_account.key);
+        let equality_proof_account = self.accounts.equality_proof_account.as_ref();
+        let range_proof_account = self.accounts.range_proof_account.as_ref();
+        let instructions_sysvar = self.accounts.instructions_sysvar.as_ref();
 
-        let range_proof_data_location =
-            ProofLocation::ContextStateAccount(self.accounts.range_proof_account.key);
+        let equality_proof_data = get_instruction_proof_data::<
+            CiphertextCommitmentEqualityProofData,
+            CiphertextCommitmentEqualityProofContext,
+        >(instructions_sysvar, equality_proof_instruction_offset)?;
 
-        let withdraw_instructions = withdraw(
+        let range_proof_data = get_instruction_proof_data::<
+            BatchedRangeProofU64Data,
+            BatchedRangeProofContext,
+        >(instructions_sysvar, range_proof_instruction_offset)?;
+
+        let equality_proof_data_location = get_proof_location(
+            equality_proof_account,
+            equality_proof_data.as_ref(),
+            equality_proof_instruction_offset,
+        )?;
+
+        let range_proof_data_location = get_proof_location(
+            range_proof_account,
+            range_proof_data.as_ref(),
+            range_proof_instruction_offset,
+        )?;
+
+        let withdraw_instruction = inner_withdraw(
             self.accounts.token_2022_program.key,
             &self.accounts.reward_vault.key(),
             &self.accounts.reward_mint.key(),
@@ -159,43 +263,44 @@ impl<'info> OpenPullInstruction for Context<'_, '_, '_, 'info, OpenPull<'info>>
             range_proof_data_location,
         )?;
 
-        for ix in withdraw_instructions {
-            let accounts = ix
-                .accounts
-                .iter()
-                .map(|acc| {
-                    Ok(match acc.pubkey {
-                        k if k == self.accounts.reward_vault.key() => {
-                            self.accounts.reward_vault.to_account_info().clone()
-                        }
-                        k if k == self.accounts.reward_mint.key() => {
-                            self.accounts.reward_mint.to_account_info().clone()
-                        }
-                        k if k == self.accounts.buyer_reward_account.key() => {
-                            self.accounts.buyer_reward_account.to_account_info().clone()
-                        }
-                        k if k == self.accounts.equality_proof_account.key() => self
-                            .accounts
-                            .equality_proof_account
-                            .to_account_info()
-                            .clone(),
-                        k if k == self.accounts.range_proof_account.key() => {
-                            self.accounts.range_proof_account.to_account_info().clone()
-                        }
-                        k if k == self.accounts.zk_elgamal_proof_program.key() => self
-                            .accounts
-                            .zk_elgamal_proof_program
-                            .to_account_info()
-                            .clone(),
-                        k if k == self.accounts.pull.key() => {
-                            self.accounts.pull.to_account_info().clone()
-                        }
-                        _ => return Err(GachaError::InvalidAccount.into()),
-                    })
-                })
-                .collect::<Result<Vec<_>>>()?;
-
-            invoke_signed(&ix, &accounts, signer)?;
+        let account_infos = [
+            Some(self.accounts.reward_vault.as_ref()),
+            Some(self.accounts.reward_mint.as_ref()),
+            Some(self.accounts.pull.as_ref()),
+            equality_proof_account,
+            range_proof_account,
+            instructions_sysvar,
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        invoke_signed_with_accounts(&withdraw_instruction, &account_infos, signer)?;
+
+        Ok(())
+    }
+
+    fn close_proof_contexts(&self) -> Result<()> {
+        let Some(context_state_destination) = self.accounts.context_state_destination.as_ref()
+        else {
+            return Ok(());
+        };
+
+        for context_state_account in [
+            self.accounts.equality_proof_account.as_ref(),
+            self.accounts.range_proof_account.as_ref(),
+            self.accounts.zero_balance_proof_account.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            close_context_state_account(
+                context_state_account,
+                context_state_destination,
+                &self.accounts.authority.to_account_info(),
+                &self.accounts.zk_elgamal_proof_program.to_account_info(),
+                &[],
+            )?;
         }
 
         Ok(())
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+use crate::{event::GamePaused, state::GameConfig};
+
+pub fn pause_game<'info>(ctx: Context<'_, '_, '_, 'info, PauseGame<'info>>) -> Result<()> {
+    // open_pull stays available so paid buyers still receive their rewards
+    let game_config = &mut ctx.accounts.game_config;
+    game_config.paused = true;
+
+    emit!(GamePaused {
+        game_config: game_config.key()
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PauseGame<'info> {
+    #[account(mut, has_one = authority)]
+    pub game_config: Box<Account<'info, GameConfig>>,
+    pub authority: Signer<'info>,
+}
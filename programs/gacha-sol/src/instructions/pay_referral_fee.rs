@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::{
+    error::GachaError,
+    event::ReferralFeePaid,
+    state::{GameConfig, PayReferralFeeParams, Pull},
+};
+
+use super::PayReferralFeeInstruction;
+
+pub fn pay_referral_fee<'info>(
+    ctx: Context<'_, '_, '_, 'info, PayReferralFee<'info>>,
+    _params: PayReferralFeeParams,
+) -> Result<()> {
+    let pull = &ctx.accounts.pull;
+
+    // The fee stays in escrow with the purchase until the pull is claimed,
+    // a refunded pull returns it to the buyer instead
+    require!(pull.claimed, GachaError::PullNotClaimed);
+    require!(pull.has_unpaid_referral_fee(), GachaError::NoReferralFee);
+
+    let amount = pull.referral_fee;
+    ctx.transfer_referral_fee(amount)?;
+
+    let pull = &mut ctx.accounts.pull;
+    pull.referral_fee_paid = true;
+
+    let game_config = &mut ctx.accounts.game_config;
+    game_config.outstanding_referral_fees -= amount;
+
+    // Emit an event
+    emit!(ReferralFeePaid {
+        id: pull.id,
+        pull: pull.key(),
+        referrer: pull.referrer,
+        amount
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: PayReferralFeeParams)]
+pub struct PayReferralFee<'info> {
+    #[account(mut, has_one = game_vault)]
+    pub game_config: Account<'info, GameConfig>,
+    #[account(mut,
+        has_one = game_config,
+        seeds = [b"pull", game_config.key().as_ref(), params.pull_id.to_le_bytes().as_ref()],
+        bump = pull.bump
+    )]
+    pub pull: Account<'info, Pull>,
+    #[account(mut,
+        token::mint = game_config.purchase_mint,
+        token::authority = pull.referrer,
+    )]
+    pub referrer_purchase_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub game_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> PayReferralFeeInstruction for Context<'_, '_, '_, 'info, PayReferralFee<'info>> {
+    fn transfer_referral_fee(&self, amount: u64) -> Result<()> {
+        let signer_seeds = &self.accounts.game_config.get_signer_seeds();
+        let signer = &[&signer_seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: self.accounts.game_vault.to_account_info(),
+            to: self.accounts.referrer_purchase_account.to_account_info(),
+            authority: self.accounts.game_config.to_account_info(),
+        };
+        let token_program = self.accounts.token_program.to_account_info().clone();
+        let cpi_context = CpiContext::new_with_signer(token_program, cpi_accounts, signer);
+
+        token::transfer(cpi_context, amount)?;
+
+        Ok(())
+    }
+}
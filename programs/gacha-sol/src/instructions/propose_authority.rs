@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::{event::AuthorityProposed, state::GameConfig};
+
+pub fn propose_authority<'info>(
+    ctx: Context<'_, '_, '_, 'info, ProposeAuthority<'info>>,
+) -> Result<()> {
+    // The proposed authority only takes over once it signs accept_authority
+    let game_config = &mut ctx.accounts.game_config;
+    game_config.pending_authority = ctx.accounts.new_authority.key();
+
+    emit!(AuthorityProposed {
+        game_config: game_config.key(),
+        authority: game_config.authority,
+        pending_authority: game_config.pending_authority
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(mut, has_one = authority)]
+    pub game_config: Box<Account<'info, GameConfig>>,
+    pub authority: Signer<'info>,
+    /// CHECK: Proposed authority account.
+    pub new_authority: AccountInfo<'info>,
+}
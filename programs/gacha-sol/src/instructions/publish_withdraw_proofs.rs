@@ -0,0 +1,158 @@
+use anchor_lang::prelude::*;
+use spl_pod::bytemuck::pod_from_bytes;
+use spl_token_2022::{
+    extension::{
+        confidential_transfer::{
+            instruction::{
+                pod::PodProofType, BatchedRangeProofContext,
+                CiphertextCommitmentEqualityProofContext, ProofContextState, ProofType,
+                ZeroCiphertextProofContext, ZeroCiphertextProofData,
+            },
+            ConfidentialTransferAccount,
+        },
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::Account as Token2022Account,
+};
+
+use crate::{
+    error::GachaError,
+    event::WithdrawProofsPublished,
+    state::{GameConfig, PublishWithdrawProofsParams, Pull},
+    utils::{
+        token_2022::verify_full_withdraw,
+        zk_elgamal_proof_program::{get_proof_context, ZkElgamalProof},
+    },
+};
+
+use super::PublishWithdrawProofsInstruction;
+
+pub fn publish_withdraw_proofs<'info>(
+    ctx: Context<'_, '_, '_, 'info, PublishWithdrawProofs<'info>>,
+    params: PublishWithdrawProofsParams,
+) -> Result<()> {
+    let pull = &ctx.accounts.pull;
+
+    require!(pull.verified, GachaError::PullNotVerified);
+    require!(
+        pull.buyer != Pubkey::default(),
+        GachaError::PullNotPurchased
+    );
+    require!(!pull.claimed, GachaError::PullAlreadyClaimed);
+    require!(!pull.reclaimed, GachaError::PullAlreadyReclaimed);
+
+    // verify the proofs target the reward vault and can only be closed by the pull,
+    // so the authority cannot close them to block the claim
+    ctx.verify_withdraw_proofs()?;
+
+    // The whole verified reward has to leave the vault
+    let zero_balance_proof =
+        get_proof_context::<ZeroCiphertextProofData, ZeroCiphertextProofContext>(
+            Some(&ctx.accounts.zero_balance_proof_account),
+            None,
+            0,
+            Some(&ctx.accounts.pull.key()),
+        )?;
+    verify_full_withdraw(
+        &ctx.accounts.reward_vault,
+        &zero_balance_proof,
+        params.amount,
+    )?;
+
+    // Store the proofs, the buyer withdraws with them in claim_pull, or the authority in
+    // reclaim_pull once the pull is refunded
+    let pull = &mut ctx.accounts.pull;
+    pull.withdraw_proofs_published = true;
+    pull.withdraw_amount = params.amount;
+    pull.withdraw_equality_proof = ctx.accounts.equality_proof_account.key();
+    pull.withdraw_range_proof = ctx.accounts.range_proof_account.key();
+    pull.withdraw_zero_balance_proof = ctx.accounts.zero_balance_proof_account.key();
+    pull.withdraw_decryptable_available_balance = params.new_decryptable_available_balance;
+
+    // Emit an event
+    emit!(WithdrawProofsPublished {
+        id: pull.id,
+        pull: pull.key(),
+        equality_proof: pull.withdraw_equality_proof,
+        range_proof: pull.withdraw_range_proof
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: PublishWithdrawProofsParams)]
+pub struct PublishWithdrawProofs<'info> {
+    #[account(has_one = authority)]
+    pub game_config: Box<Account<'info, GameConfig>>,
+    #[account(mut,
+        has_one = game_config,
+        has_one = reward_vault,
+        seeds = [b"pull", game_config.key().as_ref(), params.pull_id.to_le_bytes().as_ref()],
+        bump = pull.bump
+    )]
+    pub pull: Box<Account<'info, Pull>>,
+    /// CHECK: Token account 2022
+    pub reward_vault: AccountInfo<'info>,
+    /// CHECK: Equality proof account
+    #[account(
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub equality_proof_account: AccountInfo<'info>,
+    /// CHECK: Range proof account
+    #[account(
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub range_proof_account: AccountInfo<'info>,
+    /// CHECK: Zero ciphertext proof account for the remaining balance
+    #[account(
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub zero_balance_proof_account: AccountInfo<'info>,
+    pub authority: Signer<'info>,
+    pub zk_elgamal_proof_program: Program<'info, ZkElgamalProof>,
+}
+
+impl<'info> PublishWithdrawProofsInstruction
+    for Context<'_, '_, '_, 'info, PublishWithdrawProofs<'info>>
+{
+    fn verify_withdraw_proofs(&self) -> Result<()> {
+        let reward_vault_data = self.accounts.reward_vault.try_borrow_data()?;
+        let state = StateWithExtensions::<Token2022Account>::unpack(&reward_vault_data)?;
+        let confidential_transfer_account = state.get_extension::<ConfidentialTransferAccount>()?;
+
+        let equality_proof_data = self.accounts.equality_proof_account.data.borrow();
+        let equality_proof = pod_from_bytes::<
+            ProofContextState<CiphertextCommitmentEqualityProofContext>,
+        >(&equality_proof_data)?;
+
+        require!(
+            equality_proof.proof_type
+                == PodProofType::from(ProofType::CiphertextCommitmentEquality),
+            GachaError::InvalidProofType
+        );
+        require!(
+            equality_proof.proof_context.pubkey == confidential_transfer_account.elgamal_pubkey,
+            GachaError::InvalidElgamalPubkey
+        );
+        require!(
+            equality_proof.context_state_authority == self.accounts.pull.key(),
+            GachaError::InvalidContextAuthority
+        );
+
+        let range_proof_data = self.accounts.range_proof_account.data.borrow();
+        let range_proof =
+            pod_from_bytes::<ProofContextState<BatchedRangeProofContext>>(&range_proof_data)?;
+
+        require!(
+            range_proof.proof_type == PodProofType::from(ProofType::BatchedRangeProofU64),
+            GachaError::InvalidProofType
+        );
+        require!(
+            range_proof.context_state_authority == self.accounts.pull.key(),
+            GachaError::InvalidContextAuthority
+        );
+
+        Ok(())
+    }
+}
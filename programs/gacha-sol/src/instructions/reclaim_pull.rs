@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use spl_token_2022::extension::confidential_transfer::instruction::{
+    ZeroCiphertextProofContext, ZeroCiphertextProofData,
+};
+
+use crate::{
+    error::GachaError,
+    event::PullReclaimed,
+    state::{GameConfig, Pull, ReclaimPullParams},
+    utils::{
+        reward_vault::RewardWithdrawAccounts,
+        token_2022::{verify_full_withdraw, Token2022},
+        zk_elgamal_proof_program::{get_proof_context, ZkElgamalProof},
+    },
+};
+
+pub fn reclaim_pull<'info>(
+    ctx: Context<'_, '_, '_, 'info, ReclaimPull<'info>>,
+    _params: ReclaimPullParams,
+) -> Result<()> {
+    let pull_account = &ctx.accounts.pull;
+
+    require!(pull_account.refunded, GachaError::PullNotRefunded);
+    require!(!pull_account.reclaimed, GachaError::PullAlreadyReclaimed);
+    require!(
+        pull_account.withdraw_proofs_published,
+        GachaError::WithdrawProofsNotPublished
+    );
+
+    let amount = pull_account.withdraw_amount;
+
+    // The whole reward has to leave the vault so it can be closed
+    let zero_balance_proof =
+        get_proof_context::<ZeroCiphertextProofData, ZeroCiphertextProofContext>(
+            Some(&ctx.accounts.zero_balance_proof_account),
+            None,
+            0,
+            None,
+        )?;
+    verify_full_withdraw(&ctx.accounts.reward_vault, &zero_balance_proof, amount)?;
+
+    let new_decryptable_available_balance = pull_account.withdraw_decryptable_available_balance;
+    let decimals = ctx.accounts.reward_mint.decimals;
+
+    // Withdraw the reward with the published proofs and return it to the authority
+    let reward_withdraw_accounts = RewardWithdrawAccounts {
+        pull: &ctx.accounts.pull,
+        reward_vault: &ctx.accounts.reward_vault,
+        reward_mint: ctx.accounts.reward_mint.as_ref(),
+        equality_proof_account: &ctx.accounts.equality_proof_account,
+        range_proof_account: &ctx.accounts.range_proof_account,
+        zk_elgamal_proof_program: &ctx.accounts.zk_elgamal_proof_program,
+        token_2022_program: &ctx.accounts.token_2022_program,
+    };
+    reward_withdraw_accounts.withdraw_reward(
+        amount,
+        decimals,
+        new_decryptable_available_balance,
+    )?;
+    reward_withdraw_accounts.transfer_reward(
+        ctx.accounts.destination.as_ref(),
+        amount,
+        decimals,
+    )?;
+
+    ctx.accounts.pull.reclaimed = true;
+
+    // Emit an event
+    emit!(PullReclaimed {
+        id: ctx.accounts.pull.id,
+        pull: ctx.accounts.pull.key(),
+        destination: ctx.accounts.destination.key(),
+        amount
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: ReclaimPullParams)]
+pub struct ReclaimPull<'info> {
+    #[account(has_one = authority, has_one = reward_mint)]
+    pub game_config: Account<'info, GameConfig>,
+    #[account(mut,
+        has_one = game_config,
+        has_one = reward_vault,
+        seeds = [b"pull", game_config.key().as_ref(), params.pull_id.to_le_bytes().as_ref()],
+        bump = pull.bump
+    )]
+    pub pull: Account<'info, Pull>,
+    /// CHECK: reward vault
+    #[account(mut)]
+    pub reward_vault: AccountInfo<'info>,
+    /// Receives the reward of the refunded pull
+    #[account(mut,
+        token::mint = reward_mint,
+        token::token_program = token_2022_program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Equality proof account published for the pull
+    #[account(
+        address = pull.withdraw_equality_proof,
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub equality_proof_account: AccountInfo<'info>,
+
+    /// CHECK: Range proof account published for the pull
+    #[account(
+        address = pull.withdraw_range_proof,
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub range_proof_account: AccountInfo<'info>,
+
+    /// CHECK: Zero ciphertext proof account published for the pull
+    #[account(
+        address = pull.withdraw_zero_balance_proof,
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub zero_balance_proof_account: AccountInfo<'info>,
+    pub authority: Signer<'info>,
+    pub zk_elgamal_proof_program: Program<'info, ZkElgamalProof>,
+    pub token_2022_program: Program<'info, Token2022>,
+}
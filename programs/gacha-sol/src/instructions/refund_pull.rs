@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    error::GachaError,
+    event::PullRefunded,
+    state::{GameConfig, Pull, RefundPullParams},
+    utils::payment::PaymentAccounts,
+};
+
+use super::RefundPullInstruction;
+
+pub fn refund_pull<'info>(
+    ctx: Context<'_, '_, '_, 'info, RefundPull<'info>>,
+    _params: RefundPullParams,
+) -> Result<()> {
+    let pull = &ctx.accounts.pull;
+
+    require!(
+        pull.buyer != Pubkey::default(),
+        GachaError::PullNotPurchased
+    );
+    require!(
+        pull.buyer == ctx.accounts.buyer.key(),
+        GachaError::InvalidBuyer
+    );
+    require!(!pull.claimed, GachaError::PullAlreadyClaimed);
+    require!(!pull.refunded, GachaError::PullAlreadyRefunded);
+
+    // The authority has until the refund timeout to open the pull
+    let refundable_at = pull
+        .purchased_at
+        .checked_add(ctx.accounts.game_config.refund_timeout)
+        .ok_or(GachaError::MathOverflow)?;
+    require!(
+        Clock::get()?.unix_timestamp >= refundable_at,
+        GachaError::RefundNotAvailable
+    );
+
+    // The whole price is returned, the referral fee is only paid for claimed pulls
+    let amount = pull.purchase_price;
+    ctx.transfer_refund(amount)?;
+
+    ctx.accounts.pull.refunded = true;
+    ctx.accounts.game_config.escrowed_amount -= amount;
+
+    // Emit an event
+    emit!(PullRefunded {
+        id: ctx.accounts.pull.id,
+        pull: ctx.accounts.pull.key(),
+        buyer: ctx.accounts.buyer.key(),
+        amount
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: RefundPullParams)]
+pub struct RefundPull<'info> {
+    #[account(mut, has_one = game_vault)]
+    pub game_config: Account<'info, GameConfig>,
+    #[account(mut,
+        has_one = game_config,
+        seeds = [b"pull", game_config.key().as_ref(), params.pull_id.to_le_bytes().as_ref()],
+        bump = pull.bump
+    )]
+    pub pull: Account<'info, Pull>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    /// Required when the game is paid in tokens
+    #[account(mut,
+        token::mint = game_config.purchase_mint,
+        token::authority = buyer,
+    )]
+    pub buyer_purchase_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub game_vault: Account<'info, TokenAccount>,
+    /// Required when the game is paid in native SOL
+    #[account(mut,
+        seeds = [b"treasury", game_config.key().as_ref()],
+        bump = game_config.treasury_bump
+    )]
+    pub treasury: Option<SystemAccount<'info>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RefundPull<'info> {
+    fn payment_accounts(&self) -> PaymentAccounts<'_, 'info> {
+        PaymentAccounts {
+            game_config: &self.game_config,
+            buyer: self.buyer.as_ref(),
+            buyer_purchase_account: self
+                .buyer_purchase_account
+                .as_ref()
+                .map(|buyer_purchase_account| buyer_purchase_account.as_ref()),
+            game_vault: self.game_vault.as_ref(),
+            treasury: self.treasury.as_ref().map(|treasury| treasury.as_ref()),
+            token_program: self.token_program.as_ref(),
+            system_program: self.system_program.as_ref(),
+        }
+    }
+}
+
+impl<'info> RefundPullInstruction for Context<'_, '_, '_, 'info, RefundPull<'info>> {
+    fn transfer_refund(&self, amount: u64) -> Result<()> {
+        self.accounts.payment_accounts().refund(amount)
+    }
+}
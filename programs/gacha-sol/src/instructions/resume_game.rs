@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::{event::GameResumed, state::GameConfig};
+
+pub fn resume_game<'info>(ctx: Context<'_, '_, '_, 'info, ResumeGame<'info>>) -> Result<()> {
+    let game_config = &mut ctx.accounts.game_config;
+    game_config.paused = false;
+
+    emit!(GameResumed {
+        game_config: game_config.key()
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ResumeGame<'info> {
+    #[account(mut, has_one = authority)]
+    pub game_config: Box<Account<'info, GameConfig>>,
+    pub authority: Signer<'info>,
+}
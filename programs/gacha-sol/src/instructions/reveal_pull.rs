@@ -0,0 +1,230 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::GachaError,
+    event::PullBought,
+    state::{GameConfig, PlayerState, Pull, PullPool, PullTicket, RevealPullParams},
+    utils::slot_hashes::{get_latest_slot_hash, get_slot_hash},
+};
+
+use super::RevealPullInstruction;
+
+pub fn reveal_pull<'info>(
+    ctx: Context<'_, '_, '_, 'info, RevealPull<'info>>,
+    _params: RevealPullParams,
+) -> Result<()> {
+    let pull_ticket = &ctx.accounts.pull_ticket;
+
+    // Bundle pulls after the first one are passed as remaining accounts
+    let pull_count = pull_ticket.pull_count.max(1);
+    require!(
+        ctx.remaining_accounts.len() + 1 == pull_count as usize,
+        GachaError::InvalidPullAccounts
+    );
+
+    // The escrowed bundle price is split across its pulls, the first pull keeps the remainder
+    // and the referral fee
+    let escrowed_price = pull_ticket.purchase_price;
+    let pull_price = escrowed_price / pull_count as u64;
+    let first_pull_price = escrowed_price - pull_price * (pull_count as u64 - 1);
+    let purchased_at = pull_ticket.purchased_at;
+    let referrer = pull_ticket.referrer;
+    let referral_fee = pull_ticket.referral_fee;
+    let pity_draws = pull_ticket.pity_draws as u64;
+
+    // Pick the pulls from the pool, anyone can reveal as soon as the commit slot hash exists
+    // so the buyer cannot hold the ticket back until the pool suits them. A ticket left
+    // unrevealed until the hash expired is revealed by the authority instead of refunded.
+    let randomness = ctx.get_randomness()?;
+
+    let game_config_pubkey = ctx.accounts.game_config.key();
+    let buyer = ctx.accounts.buyer.key();
+    let pull_pool = &mut ctx.accounts.pull_pool;
+    let game_config = &mut ctx.accounts.game_config;
+    let player_state = &mut ctx.accounts.player_state;
+
+    // The draws of the ticket take over its reserved pity tier pulls
+    pull_pool.pending_pity_draws -= pity_draws;
+
+    let pull = &mut ctx.accounts.pull;
+    assign_pull(
+        pull_pool,
+        game_config,
+        player_state,
+        pull,
+        &randomness,
+        0,
+        pull_count,
+        buyer,
+        first_pull_price,
+        purchased_at,
+    )?;
+    pull.referrer = referrer;
+    pull.referral_fee = referral_fee;
+
+    // Emit an event
+    emit!(PullBought {
+        id: pull.id,
+        pull: pull.key(),
+        buyer,
+        purchase_price: first_pull_price,
+        referrer,
+        referral_fee
+    });
+
+    for (index, pull_account) in ctx.remaining_accounts.iter().enumerate() {
+        require!(
+            pull_account.owner == &crate::ID && pull_account.is_writable,
+            GachaError::InvalidPullAccounts
+        );
+
+        let mut pull = Pull::try_deserialize(&mut &pull_account.try_borrow_data()?[..])?;
+        require!(
+            pull.game_config == game_config_pubkey,
+            GachaError::InvalidPullAccounts
+        );
+
+        assign_pull(
+            pull_pool,
+            game_config,
+            player_state,
+            &mut pull,
+            &randomness,
+            index as u8 + 1,
+            pull_count - index as u8 - 1,
+            buyer,
+            pull_price,
+            purchased_at,
+        )?;
+
+        pull.try_serialize(&mut &mut pull_account.try_borrow_mut_data()?[..])?;
+
+        emit!(PullBought {
+            id: pull.id,
+            pull: pull_account.key(),
+            buyer,
+            purchase_price: pull_price,
+            referrer,
+            referral_fee: 0
+        });
+    }
+
+    pull_pool.pending_tickets -= pull_count as u64;
+
+    Ok(())
+}
+
+/// Take the `draw`-th pull of the ticket out of the pool and assign it to the buyer,
+/// from the pity tier once the buyer hit the pity threshold. `remaining_draws` counts
+/// this draw and the later ones of the ticket.
+#[allow(clippy::too_many_arguments)]
+fn assign_pull(
+    pull_pool: &mut PullPool,
+    game_config: &mut GameConfig,
+    player_state: &mut PlayerState,
+    pull: &mut Pull,
+    randomness: &[u8; 32],
+    draw: u8,
+    remaining_draws: u8,
+    buyer: Pubkey,
+    purchase_price: u64,
+    purchased_at: i64,
+) -> Result<()> {
+    require!(
+        pull.buyer == Pubkey::default(),
+        GachaError::PullAlreadyPurchased
+    );
+    require!(pull.verified, GachaError::PullNotVerified);
+    require!(!pull.claimed, GachaError::PullAlreadyClaimed);
+
+    // Pity tier pulls reserved by other tickets and by the later guaranteed draws of this one
+    // are left in the pool
+    let pity_reserved = pull_pool.pending_pity_draws
+        + player_state.guaranteed_pity_draws(game_config, remaining_draws);
+    let (pull_id, _) = pull_pool
+        .take_pull(
+            randomness,
+            draw,
+            player_state.pity_guaranteed(game_config),
+            pity_reserved,
+        )
+        .ok_or(GachaError::NoPullAvailable)?;
+    require!(pull_id == pull.id, GachaError::PullNotSelected);
+
+    player_state.record_pull(game_config.is_pity_tier(pull.tier));
+
+    pull.buyer = buyer;
+    pull.purchase_price = purchase_price;
+    pull.purchased_at = purchased_at;
+
+    game_config.record_sold_pull(pull.tier);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevealPull<'info> {
+    #[account(mut)]
+    pub game_config: Account<'info, GameConfig>,
+    #[account(mut,
+        seeds = [b"pull_pool", game_config.key().as_ref()],
+        bump = pull_pool.bump
+    )]
+    pub pull_pool: Box<Account<'info, PullPool>>,
+    #[account(mut,
+        close = buyer,
+        has_one = game_config,
+        has_one = buyer,
+        seeds = [b"pull_ticket", game_config.key().as_ref(), buyer.key().as_ref()],
+        bump = pull_ticket.bump
+    )]
+    pub pull_ticket: Account<'info, PullTicket>,
+    #[account(mut,
+        has_one = game_config,
+        has_one = buyer,
+        seeds = [b"player_state", game_config.key().as_ref(), buyer.key().as_ref()],
+        bump = player_state.bump
+    )]
+    pub player_state: Box<Account<'info, PlayerState>>,
+    #[account(mut, has_one = game_config)]
+    pub pull: Account<'info, Pull>,
+    /// Receives the ticket rent back
+    #[account(mut)]
+    pub buyer: SystemAccount<'info>,
+    /// CHECK: SlotHashes sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: AccountInfo<'info>,
+    /// Required once the commit slot hash expired
+    #[account(address = game_config.authority)]
+    pub authority: Option<Signer<'info>>,
+}
+
+impl<'info> RevealPullInstruction for Context<'_, '_, '_, 'info, RevealPull<'info>> {
+    fn get_randomness(&self) -> Result<[u8; 32]> {
+        let pull_ticket = &self.accounts.pull_ticket;
+
+        // The commit slot hash only exists once that slot has been completed
+        require!(
+            Clock::get()?.slot > pull_ticket.commit_slot,
+            GachaError::RandomnessNotReady
+        );
+
+        // Without the commit slot hash the authority falls back on the most recent one, so
+        // skipping the reveal of a drawn pull gets the buyer another draw but no refund
+        let slot_hash = match get_slot_hash(&self.accounts.slot_hashes, pull_ticket.commit_slot)? {
+            Some(slot_hash) => slot_hash,
+            None => {
+                require!(
+                    self.accounts.authority.is_some(),
+                    GachaError::RandomnessExpired
+                );
+                get_latest_slot_hash(&self.accounts.slot_hashes)?
+            }
+        };
+
+        Ok(PullTicket::compute_randomness(
+            &pull_ticket.key(),
+            &slot_hash,
+        ))
+    }
+}
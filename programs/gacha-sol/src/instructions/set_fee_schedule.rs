@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::GachaError,
+    event::FeeScheduleSet,
+    state::{FeeSchedule, GameConfig, PaymentMode, SetFeeScheduleParams, Size},
+};
+
+pub fn set_fee_schedule<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetFeeSchedule<'info>>,
+    params: SetFeeScheduleParams,
+) -> Result<()> {
+    // Recipients are token accounts, native SOL proceeds cannot be split
+    require!(
+        ctx.accounts.game_config.payment_mode == PaymentMode::Token,
+        GachaError::UnsupportedPaymentMode
+    );
+    FeeSchedule::verify_recipients(&params.recipients)?;
+
+    // Shares are paid out of the game vault
+    require!(
+        params
+            .recipients
+            .iter()
+            .all(|recipient| recipient.token_account != ctx.accounts.game_config.game_vault),
+        GachaError::InvalidFeeSchedule
+    );
+
+    let fee_schedule = &mut ctx.accounts.fee_schedule;
+    fee_schedule.game_config = ctx.accounts.game_config.key();
+    fee_schedule.recipients = params.recipients;
+    fee_schedule.bump = ctx.bumps.fee_schedule;
+
+    let game_config = &mut ctx.accounts.game_config;
+    game_config.fee_schedule = fee_schedule.key();
+
+    emit!(FeeScheduleSet {
+        game_config: game_config.key(),
+        fee_schedule: fee_schedule.key(),
+        recipients: fee_schedule.recipients.clone()
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetFeeSchedule<'info> {
+    #[account(mut, has_one = authority)]
+    pub game_config: Box<Account<'info, GameConfig>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = FeeSchedule::SIZE,
+        seeds = [b"fee_schedule", game_config.key().as_ref()],
+        bump
+    )]
+    pub fee_schedule: Box<Account<'info, FeeSchedule>>,
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
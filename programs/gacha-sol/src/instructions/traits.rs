@@ -1,19 +1,33 @@
 use anchor_lang::prelude::*;
 
-use crate::{error::GachaError, state::AE_CIPHERTEXT_MAX_BASE64_LEN};
+use crate::{
+    error::GachaError,
+    state::{NewPull, Pull, VerifyPullTransferParams, AE_CIPHERTEXT_MAX_BASE64_LEN},
+};
 
 pub trait InitializeGameConfigInstruction {
     fn verify_reward_mint(&self) -> Result<()>;
+
+    fn fund_treasury(&self) -> Result<()>;
+}
+
+pub trait UpdateGameConfigInstruction {
+    fn verify_reward_mint(&self) -> Result<()>;
+
+    fn verify_game_vault(&self) -> Result<()>;
+
+    fn create_game_vault(&self) -> Result<()>;
 }
 
 pub trait CreatePullInstruction {
     fn create_and_configure_reward_vault(
         &self,
         decryptable_zero_balance_base64: &[u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+        proof_instruction_offset: i8,
     ) -> Result<()> {
         self.initialize_token_account_with_extension()?;
 
-        self.configure_token_account(decryptable_zero_balance_base64)
+        self.configure_token_account(decryptable_zero_balance_base64, proof_instruction_offset)
             .map_err(|_| GachaError::ConfigureTokenAccountFailed)?;
 
         msg!(
@@ -29,11 +43,22 @@ pub trait CreatePullInstruction {
     fn configure_token_account(
         &self,
         decryptable_zero_balance_base64: &[u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+        proof_instruction_offset: i8,
     ) -> Result<()>;
 
     fn get_reward_vault_pubkey(&self) -> Pubkey;
 }
 
+pub trait CreatePullsInstruction<'info> {
+    fn create_pull_account(
+        &self,
+        pull_account: &AccountInfo<'info>,
+        reward_vault: &AccountInfo<'info>,
+        pull_id: u64,
+        new_pull: &NewPull,
+    ) -> Result<(Pull, u8)>;
+}
+
 pub trait ApplyPullPendingBalanceInstruction {
     fn apply_pending_balance(
         &self,
@@ -42,20 +67,89 @@ pub trait ApplyPullPendingBalanceInstruction {
 }
 
 pub trait VerifyPullInstruction {
-    fn verify_reward_balance(&self) -> Result<()>;
+    fn verify_reward_balance(&self, proof_instruction_offset: i8) -> Result<()>;
+
+    fn verify_reward_range(&self, min_amount: u64, max_amount: u64) -> Result<()>;
+
+    fn close_proof_contexts(&self) -> Result<()>;
 }
 
 pub trait BuyPullInstruction {
     fn transfer_purchase(&self, amount: u64) -> Result<()>;
 }
 
+pub trait BuyPullsInstruction {
+    fn transfer_purchase(&self, amount: u64) -> Result<()>;
+}
+
+pub trait RevealPullInstruction {
+    fn get_randomness(&self) -> Result<[u8; 32]>;
+}
+
+pub trait RefundPullInstruction {
+    fn transfer_refund(&self, amount: u64) -> Result<()>;
+}
+
+pub trait PublishWithdrawProofsInstruction {
+    fn verify_withdraw_proofs(&self) -> Result<()>;
+}
+
+pub trait VerifyPullTransferInstruction {
+    fn verify_transfer_proofs(&self, params: &VerifyPullTransferParams) -> Result<()>;
+}
+
+pub trait ClaimPullInstruction {
+    fn withdraw_reward(
+        &self,
+        amount: u64,
+        decimals: u8,
+        new_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+    ) -> Result<()>;
+
+    fn transfer_reward(&self, amount: u64, decimals: u8) -> Result<()>;
+}
+
+pub trait ClaimPullConfidentialInstruction {
+    fn transfer_reward_confidential(&self) -> Result<()>;
+}
+
+pub trait ClosePullInstruction {
+    fn empty_reward_vault(&self) -> Result<()>;
+
+    fn close_reward_vault(&self) -> Result<()>;
+
+    fn close_withdraw_proofs(&self) -> Result<()>;
+}
+
+pub trait WithdrawProceedsInstruction {
+    fn transfer_proceeds(&self, amount: u64) -> Result<()>;
+
+    fn transfer_fees(&self, amounts: &[u64]) -> Result<()>;
+
+    fn transfer_proceeds_native(&self, amount: u64) -> Result<()>;
+}
+
 pub trait OpenPullInstruction {
     fn withdraw_reward(
         &self,
         amount: u64,
         decimals: u8,
         new_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+        equality_proof_instruction_offset: i8,
+        range_proof_instruction_offset: i8,
     ) -> Result<()>;
 
     fn transfer_reward(&self, amount: u64, decimals: u8) -> Result<()>;
+
+    fn close_proof_contexts(&self) -> Result<()>;
+}
+
+pub trait PayReferralFeeInstruction {
+    fn transfer_referral_fee(&self, amount: u64) -> Result<()>;
+}
+
+pub trait CancelTicketInstruction {
+    fn verify_randomness_expired(&self) -> Result<()>;
+
+    fn transfer_refund(&self, amount: u64) -> Result<()>;
 }
@@ -0,0 +1,371 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        self,
+        program::{invoke, invoke_signed},
+        program_pack::Pack,
+    },
+};
+use anchor_spl::token::{
+    spl_token::{self, instruction::initialize_account3},
+    Mint, Token,
+};
+
+use crate::{
+    error::GachaError,
+    event::GameConfigUpdated,
+    state::{
+        GameConfig, PullBundle, UpdateGameConfigParams, MAX_BUNDLE_SIZE, MAX_PRIZE_TIERS,
+        MAX_PULL_BUNDLES, MAX_REFERRAL_BPS,
+    },
+    utils::token_2022::verify_confidential_transfer_mint,
+};
+
+use super::UpdateGameConfigInstruction;
+
+pub fn update_game_config<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateGameConfig<'info>>,
+    params: UpdateGameConfigParams,
+) -> Result<()> {
+    if let Some(pull_price) = params.pull_price {
+        require!(pull_price > 0, GachaError::InvalidZeroPullPrice);
+    }
+    if let Some(refund_timeout) = params.refund_timeout {
+        require!(refund_timeout > 0, GachaError::InvalidRefundTimeout);
+    }
+    if let Some(bundles) = params.bundles {
+        verify_bundles(&bundles)?;
+    }
+    if let Some(referral_bps) = params.referral_bps {
+        require!(
+            referral_bps <= MAX_REFERRAL_BPS,
+            GachaError::InvalidReferralBps
+        );
+    }
+    if let Some(pity_tier) = params.pity_tier {
+        require!(
+            (pity_tier as usize) < MAX_PRIZE_TIERS,
+            GachaError::InvalidPrizeTier
+        );
+    }
+    if params.pity_threshold.is_some() || params.pity_tier.is_some() {
+        // The pull pool holds the pity flag of each pull under the current settings
+        require!(
+            ctx.accounts.game_config.last_pull_id == 0,
+            GachaError::PitySettingsLocked
+        );
+    }
+
+    // Verify mints and vaults
+    ctx.verify_reward_mint()?;
+    ctx.verify_game_vault()?;
+
+    // Each purchase mint has its own game vault
+    if ctx.accounts.game_vault.is_some() {
+        ctx.create_game_vault()?;
+    }
+
+    let game_config = &ctx.accounts.game_config;
+    require!(
+        GameConfig::is_valid_sale_window(
+            params.sale_start.unwrap_or(game_config.sale_start),
+            params.sale_end.unwrap_or(game_config.sale_end)
+        ),
+        GachaError::InvalidSaleWindow
+    );
+    require!(
+        GameConfig::is_valid_presale_window(
+            params.presale_start.unwrap_or(game_config.presale_start),
+            params.presale_end.unwrap_or(game_config.presale_end)
+        ),
+        GachaError::InvalidPresaleWindow
+    );
+
+    let old_pull_price = game_config.pull_price;
+    let old_purchase_mint = game_config.purchase_mint;
+    let old_reward_mint = game_config.reward_mint;
+    let old_game_vault = game_config.game_vault;
+    let old_refund_timeout = game_config.refund_timeout;
+    let old_bundles = game_config.bundles;
+    let old_pity_threshold = game_config.pity_threshold;
+    let old_pity_tier = game_config.pity_tier;
+    let old_sale_start = game_config.sale_start;
+    let old_sale_end = game_config.sale_end;
+    let old_max_pulls_per_wallet = game_config.max_pulls_per_wallet;
+    let old_merkle_root = game_config.merkle_root;
+    let old_presale_start = game_config.presale_start;
+    let old_presale_end = game_config.presale_end;
+    let old_referral_bps = game_config.referral_bps;
+
+    // Update game config
+    let new_purchase_mint = ctx.accounts.purchase_mint.as_ref().map(|mint| mint.key());
+    let new_reward_mint = ctx.accounts.reward_mint.as_ref().map(|mint| mint.key());
+    let new_game_vault = ctx.accounts.game_vault.as_ref().map(|vault| vault.key());
+
+    let game_config = &mut ctx.accounts.game_config;
+    if let Some(pull_price) = params.pull_price {
+        game_config.pull_price = pull_price;
+    }
+    if let Some(refund_timeout) = params.refund_timeout {
+        game_config.refund_timeout = refund_timeout;
+    }
+    if let Some(bundles) = params.bundles {
+        game_config.bundles = bundles;
+    }
+    if let Some(pity_threshold) = params.pity_threshold {
+        game_config.pity_threshold = pity_threshold;
+    }
+    if let Some(pity_tier) = params.pity_tier {
+        game_config.pity_tier = pity_tier;
+    }
+    if let Some(sale_start) = params.sale_start {
+        game_config.sale_start = sale_start;
+    }
+    if let Some(sale_end) = params.sale_end {
+        game_config.sale_end = sale_end;
+    }
+    if let Some(max_pulls_per_wallet) = params.max_pulls_per_wallet {
+        game_config.max_pulls_per_wallet = max_pulls_per_wallet;
+    }
+    if let Some(merkle_root) = params.merkle_root {
+        game_config.merkle_root = merkle_root;
+    }
+    if let Some(presale_start) = params.presale_start {
+        game_config.presale_start = presale_start;
+    }
+    if let Some(presale_end) = params.presale_end {
+        game_config.presale_end = presale_end;
+    }
+    if let Some(referral_bps) = params.referral_bps {
+        game_config.referral_bps = referral_bps;
+    }
+    if let Some(purchase_mint) = new_purchase_mint {
+        game_config.purchase_mint = purchase_mint;
+    }
+    if let Some(reward_mint) = new_reward_mint {
+        game_config.reward_mint = reward_mint;
+    }
+    if let Some(game_vault) = new_game_vault {
+        game_config.game_vault = game_vault;
+    }
+
+    // Emit event
+    emit!(GameConfigUpdated {
+        game_config: game_config.key(),
+        old_pull_price,
+        new_pull_price: game_config.pull_price,
+        old_purchase_mint,
+        new_purchase_mint: game_config.purchase_mint,
+        old_reward_mint,
+        new_reward_mint: game_config.reward_mint,
+        old_game_vault,
+        new_game_vault: game_config.game_vault,
+        old_refund_timeout,
+        new_refund_timeout: game_config.refund_timeout,
+        old_bundles,
+        new_bundles: game_config.bundles,
+        old_pity_threshold,
+        new_pity_threshold: game_config.pity_threshold,
+        old_pity_tier,
+        new_pity_tier: game_config.pity_tier,
+        old_sale_start,
+        new_sale_start: game_config.sale_start,
+        old_sale_end,
+        new_sale_end: game_config.sale_end,
+        old_max_pulls_per_wallet,
+        new_max_pulls_per_wallet: game_config.max_pulls_per_wallet,
+        old_merkle_root,
+        new_merkle_root: game_config.merkle_root,
+        old_presale_start,
+        new_presale_start: game_config.presale_start,
+        old_presale_end,
+        new_presale_end: game_config.presale_end,
+        old_referral_bps,
+        new_referral_bps: game_config.referral_bps,
+    });
+
+    Ok(())
+}
+
+/// Bundles hold at least two pulls, with distinct sizes
+fn verify_bundles(bundles: &[PullBundle; MAX_PULL_BUNDLES]) -> Result<()> {
+    for (index, bundle) in bundles.iter().enumerate() {
+        if bundle.size == 0 {
+            continue;
+        }
+
+        require!(
+            bundle.size > 1 && bundle.size <= MAX_BUNDLE_SIZE && bundle.price > 0,
+            GachaError::InvalidPullBundle
+        );
+        require!(
+            bundles[..index]
+                .iter()
+                .all(|other| other.size != bundle.size),
+            GachaError::InvalidPullBundle
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateGameConfig<'info> {
+    #[account(mut, has_one = authority)]
+    pub game_config: Box<Account<'info, GameConfig>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub purchase_mint: Option<Box<Account<'info, Mint>>>,
+    /// CHECK: Token-2022 mint with Confidential Transfer extension
+    pub reward_mint: Option<AccountInfo<'info>>,
+    /// CHECK: Game vault for the new purchase mint, to be internally created and initialized
+    #[account(mut)]
+    pub game_vault: Option<AccountInfo<'info>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UpdateGameConfig<'info> {
+    fn purchase_mint_key(&self) -> Pubkey {
+        match &self.purchase_mint {
+            Some(purchase_mint) => purchase_mint.key(),
+            None => self.game_config.purchase_mint,
+        }
+    }
+
+    fn find_game_vault_address(&self, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"game_vault",
+                self.game_config.key().as_ref(),
+                self.purchase_mint_key().as_ref(),
+            ],
+            program_id,
+        )
+    }
+}
+
+impl<'info> UpdateGameConfigInstruction for Context<'_, '_, '_, 'info, UpdateGameConfig<'info>> {
+    fn verify_reward_mint(&self) -> Result<()> {
+        let Some(reward_mint) = &self.accounts.reward_mint else {
+            return Ok(());
+        };
+
+        // Existing reward vaults are bound to the current reward mint
+        require!(
+            self.accounts.game_config.last_pull_id == 0,
+            GachaError::RewardMintLocked
+        );
+
+        verify_confidential_transfer_mint(reward_mint)
+    }
+
+    fn verify_game_vault(&self) -> Result<()> {
+        let game_config = &self.accounts.game_config;
+
+        if self.accounts.purchase_mint_key() == game_config.purchase_mint {
+            require!(
+                self.accounts.game_vault.is_none(),
+                GachaError::InvalidGameVault
+            );
+            return Ok(());
+        }
+
+        // The current vault must be settled before switching purchase mint
+        require!(
+            game_config.escrowed_amount == 0,
+            GachaError::PurchasesPending
+        );
+        require!(
+            game_config.withdrawable_amount()? == 0,
+            GachaError::ProceedsPending
+        );
+        // Unpaid referral fees are paid from the current vault in the current purchase mint
+        require!(
+            game_config.outstanding_referral_fees == 0,
+            GachaError::ReferralFeesPending
+        );
+
+        let Some(game_vault) = &self.accounts.game_vault else {
+            return err!(GachaError::InvalidGameVault);
+        };
+        let (game_vault_pubkey, _) = self.accounts.find_game_vault_address(self.program_id);
+        require_keys_eq!(
+            game_vault.key(),
+            game_vault_pubkey,
+            GachaError::InvalidGameVault
+        );
+
+        Ok(())
+    }
+
+    fn create_game_vault(&self) -> Result<()> {
+        let (Some(game_vault), Some(purchase_mint)) =
+            (&self.accounts.game_vault, &self.accounts.purchase_mint)
+        else {
+            return err!(GachaError::InvalidGameVault);
+        };
+
+        // Switching back to an earlier purchase mint reuses its vault
+        if !game_vault.data_is_empty() {
+            require_keys_eq!(
+                *game_vault.owner,
+                self.accounts.token_program.key(),
+                GachaError::InvalidGameVault
+            );
+            return Ok(());
+        }
+
+        let game_config_pubkey = self.accounts.game_config.key();
+        let purchase_mint_pubkey = purchase_mint.key();
+        let authority_pubkey = self.accounts.authority.key();
+        let token_program_id = self.accounts.token_program.key();
+        let (game_vault_pubkey, game_vault_bump) =
+            self.accounts.find_game_vault_address(self.program_id);
+
+        let required_space = spl_token::state::Account::LEN;
+        let lamports = Rent::get()?.minimum_balance(required_space);
+
+        let create_account_ix = solana_program::system_instruction::create_account(
+            &authority_pubkey,
+            &game_vault_pubkey,
+            lamports,
+            required_space as u64,
+            &token_program_id,
+        );
+
+        let init_account_ix = initialize_account3(
+            &token_program_id,
+            &game_vault_pubkey,
+            &purchase_mint_pubkey,
+            &game_config_pubkey,
+        )?;
+
+        let game_vault_seeds = &[
+            b"game_vault",
+            game_config_pubkey.as_ref(),
+            purchase_mint_pubkey.as_ref(),
+            &[game_vault_bump],
+        ];
+
+        invoke_signed(
+            &create_account_ix,
+            &[
+                self.accounts.authority.to_account_info(),
+                game_vault.to_account_info(),
+                self.accounts.system_program.to_account_info(),
+            ],
+            &[&game_vault_seeds[..]],
+        )?;
+
+        invoke(
+            &init_account_ix,
+            &[
+                game_vault.to_account_info(),
+                purchase_mint.to_account_info(),
+            ],
+        )?;
+
+        Ok(())
+    }
+}
@@ -1,39 +1,74 @@
-use std::str::FromStr;
-
 use anchor_lang::prelude::*;
-use spl_pod::bytemuck::pod_from_bytes;
+use anchor_lang::solana_program::sysvar;
+use bytemuck::Zeroable;
 use spl_token_2022::{
     extension::{
         confidential_transfer::{
             instruction::{
-                pod::PodProofType, ProofContextState, ProofType, ZeroCiphertextProofContext,
+                BatchedRangeProofContext, BatchedRangeProofU128Data,
+                CiphertextCommitmentEqualityProofContext, CiphertextCommitmentEqualityProofData,
+                ZeroCiphertextProofContext, ZeroCiphertextProofData,
             },
             ConfidentialTransferAccount,
         },
         BaseStateWithExtensions, StateWithExtensions,
     },
-    solana_zk_sdk::encryption::pod::elgamal::PodElGamalCiphertext,
+    solana_zk_sdk::encryption::pod::elgamal::{PodElGamalCiphertext, PodElGamalPubkey},
     state::Account as Token2022Account,
 };
-use spl_token_confidential_transfer_ciphertext_arithmetic::subtract;
+use spl_token_confidential_transfer_ciphertext_arithmetic::{add_to, subtract, subtract_from};
 
 use crate::{
     error::GachaError,
     event::PullVerified,
-    state::{GameConfig, Pull},
-    utils::{token_2022::Token2022, zk_elgamal_proof_program::ZkElgamalProof},
+    state::{GameConfig, PrizeTable, Pull, PullPool, VerifyPullParams, PULL_POOL_CAPACITY},
+    utils::{
+        token_2022::{parse_ciphertext, Token2022},
+        zk_elgamal_proof_program::{
+            close_context_state_account, get_proof_context, ZkElgamalProof,
+        },
+    },
 };
 
 use super::VerifyPullInstruction;
 
-pub fn verify_pull<'info>(ctx: Context<'_, '_, '_, 'info, VerifyPull<'info>>) -> Result<()> {
+pub fn verify_pull<'info>(
+    ctx: Context<'_, '_, '_, 'info, VerifyPull<'info>>,
+    params: VerifyPullParams,
+) -> Result<()> {
+    require!(!ctx.accounts.game_config.paused, GachaError::GamePaused);
+    require!(!ctx.accounts.pull.verified, GachaError::PullAlreadyVerified);
+
     // verify the current reward balance
-    ctx.verify_reward_balance()?;
+    ctx.verify_reward_balance(params.zero_ciphertext_proof_instruction_offset)?;
+
+    // A reward outside the range of its tier never enters the pool
+    if ctx.accounts.game_config.has_prize_table() {
+        let prize_table = ctx
+            .accounts
+            .prize_table
+            .as_ref()
+            .ok_or(GachaError::MissingPrizeTable)?;
+        let (min_amount, max_amount) = prize_table.amount_range(ctx.accounts.pull.tier)?;
+
+        ctx.verify_reward_range(min_amount, max_amount)?;
+    }
+
+    // Reclaim the proof rent when a destination is given
+    ctx.close_proof_contexts()?;
 
     // Set verification flag
     let pull = &mut ctx.accounts.pull;
     pull.verified = true;
 
+    // Make the pull available for random assignment
+    let pull_pool = &mut ctx.accounts.pull_pool;
+    require!(
+        pull_pool.pull_ids.len() < PULL_POOL_CAPACITY,
+        GachaError::PullPoolFull
+    );
+    pull_pool.add_pull(pull.id, ctx.accounts.game_config.is_pity_tier(pull.tier));
+
     emit!(PullVerified {
         id: pull.id,
         pull: pull.key()
@@ -46,22 +81,51 @@ pub fn verify_pull<'info>(ctx: Context<'_, '_, '_, 'info, VerifyPull<'info>>) ->
 pub struct VerifyPull<'info> {
     #[account( has_one=authority)]
     pub game_config: Box<Account<'info, GameConfig>>,
-    #[account(mut, has_one=reward_vault)]
+    #[account(mut,
+        seeds = [b"pull_pool", game_config.key().as_ref()],
+        bump = pull_pool.bump
+    )]
+    pub pull_pool: Box<Account<'info, PullPool>>,
+    #[account(mut, has_one=game_config, has_one=reward_vault)]
     pub pull: Box<Account<'info, Pull>>,
     /// CHECK: Token account 2022
     pub reward_vault: AccountInfo<'info>,
     pub authority: Signer<'info>,
-    /// CHECK: Zero proof account
-    #[account(
+    /// CHECK: Zero proof account, not needed when the proof is in a sibling instruction
+    #[account(mut,
         owner = zk_elgamal_proof_program.key()
     )]
-    pub zero_ciphertext_proof_context: AccountInfo<'info>,
+    pub zero_ciphertext_proof_context: Option<AccountInfo<'info>>,
+    /// Committed prize table, required when the game has one
+    #[account(address = game_config.prize_table)]
+    pub prize_table: Option<Box<Account<'info, PrizeTable>>>,
+    /// CHECK: Equality proof of the reward less the smallest prize, required with a prize table
+    #[account(mut,
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub min_amount_equality_proof: Option<AccountInfo<'info>>,
+    /// CHECK: Equality proof of the largest prize less the reward, required with a prize table
+    #[account(mut,
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub max_amount_equality_proof: Option<AccountInfo<'info>>,
+    /// CHECK: Range proof of both amounts, required with a prize table
+    #[account(mut,
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub amount_range_proof: Option<AccountInfo<'info>>,
+    /// CHECK: Receives the proof account rent, the proof account is kept open when missing
+    #[account(mut)]
+    pub context_state_destination: Option<AccountInfo<'info>>,
+    /// CHECK: Instructions sysvar, needed when the proof is in a sibling instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<AccountInfo<'info>>,
     pub zk_elgamal_proof_program: Program<'info, ZkElgamalProof>,
     pub token_program: Program<'info, Token2022>,
 }
 
 impl<'info> VerifyPullInstruction for Context<'_, '_, '_, 'info, VerifyPull<'info>> {
-    fn verify_reward_balance(&self) -> Result<()> {
+    fn verify_reward_balance(&self, proof_instruction_offset: i8) -> Result<()> {
         let reward_vault = &self.accounts.reward_vault;
 
         let data = reward_vault.try_borrow_data()?;
@@ -71,39 +135,127 @@ impl<'info> VerifyPullInstruction for Context<'_, '_, '_, 'info, VerifyPull<'inf
         let available_balance: PodElGamalCiphertext =
             confidential_transfer_account.available_balance;
 
-        let expected_amount = PodElGamalCiphertext::from_str(
-            std::str::from_utf8(&self.accounts.pull.encrypted_amount)
-                .map_err(|_| GachaError::CipherTextBalanceConversionFailed)?,
-        )
-        .map_err(|_| GachaError::CipherTextBalanceConversionFailed)?;
+        let expected_amount = parse_ciphertext(&self.accounts.pull.encrypted_amount)?;
 
-        // use check verified account method
-        let context_state_account_data = self.accounts.zero_ciphertext_proof_context.data.borrow();
-        let context_state = pod_from_bytes::<ProofContextState<ZeroCiphertextProofContext>>(
-            &context_state_account_data,
+        let proof_context = get_proof_context::<ZeroCiphertextProofData, ZeroCiphertextProofContext>(
+            self.accounts.zero_ciphertext_proof_context.as_ref(),
+            self.accounts.instructions_sysvar.as_ref(),
+            proof_instruction_offset,
+            Some(self.accounts.authority.key),
         )?;
 
         require!(
-            context_state.proof_type == PodProofType::from(ProofType::ZeroCiphertext),
-            GachaError::InvalidProofType
+            proof_context.pubkey == confidential_transfer_account.elgamal_pubkey,
+            GachaError::InvalidElgamalPubkey
         );
+
+        let remaining_balance = subtract(&available_balance, &expected_amount)
+            .ok_or(GachaError::CiphertextArithmeticFailed)?;
+
         require!(
-            context_state.proof_context.pubkey == confidential_transfer_account.elgamal_pubkey,
-            GachaError::InvalidElgamalPubkey
+            remaining_balance == proof_context.ciphertext,
+            GachaError::CiphertextZeroBalanceMismatch
         );
+
+        Ok(())
+    }
+
+    fn verify_reward_range(&self, min_amount: u64, max_amount: u64) -> Result<()> {
+        let authority = Some(self.accounts.authority.key);
+
+        let min_amount_equality_proof = get_proof_context::<
+            CiphertextCommitmentEqualityProofData,
+            CiphertextCommitmentEqualityProofContext,
+        >(
+            self.accounts.min_amount_equality_proof.as_ref(),
+            None,
+            0,
+            authority,
+        )?;
+        let max_amount_equality_proof = get_proof_context::<
+            CiphertextCommitmentEqualityProofData,
+            CiphertextCommitmentEqualityProofContext,
+        >(
+            self.accounts.max_amount_equality_proof.as_ref(),
+            None,
+            0,
+            authority,
+        )?;
+        let amount_range_proof =
+            get_proof_context::<BatchedRangeProofU128Data, BatchedRangeProofContext>(
+                self.accounts.amount_range_proof.as_ref(),
+                None,
+                0,
+                authority,
+            )?;
+
+        let elgamal_pubkey = self.accounts.get_reward_vault_elgamal_pubkey()?;
         require!(
-            context_state.context_state_authority == self.accounts.authority.key(),
-            GachaError::InvalidContextAuthority
+            min_amount_equality_proof.pubkey == elgamal_pubkey
+                && max_amount_equality_proof.pubkey == elgamal_pubkey,
+            GachaError::InvalidElgamalPubkey
         );
 
-        let remaining_balance = subtract(&available_balance, &expected_amount)
+        // The equality proofs commit to the reward less the smallest prize and to the largest
+        // prize less the reward
+        let expected_amount = parse_ciphertext(&self.accounts.pull.encrypted_amount)?;
+        let above_min_amount = subtract_from(&expected_amount, min_amount)
+            .ok_or(GachaError::CiphertextArithmeticFailed)?;
+        let below_max_amount = add_to(&PodElGamalCiphertext::zeroed(), max_amount)
+            .and_then(|max_amount| subtract(&max_amount, &expected_amount))
             .ok_or(GachaError::CiphertextArithmeticFailed)?;
 
         require!(
-            remaining_balance == context_state.proof_context.ciphertext,
-            GachaError::CiphertextZeroBalanceMismatch
+            min_amount_equality_proof.ciphertext == above_min_amount
+                && max_amount_equality_proof.ciphertext == below_max_amount,
+            GachaError::RewardOutOfPrizeRange
+        );
+
+        // Both differences are 64-bit amounts, a reward outside the range would wrap around
+        require!(
+            amount_range_proof.commitments[0] == min_amount_equality_proof.commitment
+                && amount_range_proof.commitments[1] == max_amount_equality_proof.commitment
+                && amount_range_proof.bit_lengths[..2] == [u64::BITS as u8; 2],
+            GachaError::RewardOutOfPrizeRange
         );
 
         Ok(())
     }
+
+    fn close_proof_contexts(&self) -> Result<()> {
+        let Some(context_state_destination) = self.accounts.context_state_destination.as_ref()
+        else {
+            return Ok(());
+        };
+
+        for proof_context in [
+            self.accounts.zero_ciphertext_proof_context.as_ref(),
+            self.accounts.min_amount_equality_proof.as_ref(),
+            self.accounts.max_amount_equality_proof.as_ref(),
+            self.accounts.amount_range_proof.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            close_context_state_account(
+                proof_context,
+                context_state_destination,
+                &self.accounts.authority.to_account_info(),
+                &self.accounts.zk_elgamal_proof_program.to_account_info(),
+                &[],
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl VerifyPull<'_> {
+    fn get_reward_vault_elgamal_pubkey(&self) -> Result<PodElGamalPubkey> {
+        let data = self.reward_vault.try_borrow_data()?;
+        let state = StateWithExtensions::<Token2022Account>::unpack(&data)?;
+        let confidential_transfer_account = state.get_extension::<ConfidentialTransferAccount>()?;
+
+        Ok(confidential_transfer_account.elgamal_pubkey)
+    }
 }
@@ -0,0 +1,289 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use spl_pod::bytemuck::pod_from_bytes;
+use spl_token_2022::{
+    extension::{
+        confidential_transfer::{
+            instruction::{
+                pod::PodProofType, BatchedGroupedCiphertext3HandlesValidityProofContext,
+                BatchedRangeProofContext, CiphertextCommitmentEqualityProofContext,
+                ProofContextState, ProofType, ZeroCiphertextProofContext,
+            },
+            ConfidentialTransferAccount, ConfidentialTransferMint,
+        },
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::{Account as Token2022Account, Mint as Mint2022},
+};
+use spl_token_confidential_transfer_ciphertext_arithmetic::subtract_with_lo_hi;
+use spl_token_confidential_transfer_proof_extraction::transfer::TransferProofContext;
+
+use crate::{
+    error::GachaError,
+    event::TransferProofsVerified,
+    state::{GameConfig, Pull, VerifyPullTransferParams},
+    utils::{
+        token_2022::{parse_ciphertext, Token2022},
+        zk_elgamal_proof_program::ZkElgamalProof,
+    },
+};
+
+use super::VerifyPullTransferInstruction;
+
+pub fn verify_pull_transfer<'info>(
+    ctx: Context<'_, '_, '_, 'info, VerifyPullTransfer<'info>>,
+    params: VerifyPullTransferParams,
+) -> Result<()> {
+    require!(!ctx.accounts.game_config.paused, GachaError::GamePaused);
+
+    let pull = &ctx.accounts.pull;
+
+    require!(pull.verified, GachaError::PullNotVerified);
+    require!(
+        pull.buyer != Pubkey::default(),
+        GachaError::PullNotPurchased
+    );
+    require!(!pull.claimed, GachaError::PullAlreadyClaimed);
+    require!(!pull.refunded, GachaError::PullAlreadyRefunded);
+
+    // A confidential reward cannot be checked against the tier ranges of the prize table
+    require!(
+        !ctx.accounts.game_config.has_prize_table(),
+        GachaError::ConfidentialDeliveryWithPrizeTable
+    );
+
+    // verify the proofs transfer the whole reward vault to the buyer
+    ctx.verify_transfer_proofs(&params)?;
+
+    // Store the proofs for the confidential reward delivery
+    let pull = &mut ctx.accounts.pull;
+    pull.transfer_proofs_verified = true;
+    pull.transfer_amount_auditor_ciphertext_lo = params.transfer_amount_auditor_ciphertext_lo;
+    pull.transfer_amount_auditor_ciphertext_hi = params.transfer_amount_auditor_ciphertext_hi;
+    pull.transfer_decryptable_available_balance = params.final_decryptable_available_balance;
+    pull.transfer_equality_proof = ctx.accounts.equality_proof_account.key();
+    pull.transfer_ciphertext_validity_proof = ctx.accounts.ciphertext_validity_proof_account.key();
+    pull.transfer_range_proof = ctx.accounts.range_proof_account.key();
+
+    // Emit an event
+    emit!(TransferProofsVerified {
+        id: pull.id,
+        pull: pull.key(),
+        equality_proof: pull.transfer_equality_proof,
+        ciphertext_validity_proof: pull.transfer_ciphertext_validity_proof,
+        range_proof: pull.transfer_range_proof
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: VerifyPullTransferParams)]
+pub struct VerifyPullTransfer<'info> {
+    #[account(has_one = authority)]
+    pub game_config: Box<Account<'info, GameConfig>>,
+    #[account(mut,
+        has_one = game_config,
+        has_one = reward_vault,
+        seeds = [b"pull", game_config.key().as_ref(), params.pull_id.to_le_bytes().as_ref()],
+        bump = pull.bump
+    )]
+    pub pull: Box<Account<'info, Pull>>,
+    /// CHECK: Token account 2022
+    pub reward_vault: AccountInfo<'info>,
+    /// CHECK: Reward mint of the game
+    #[account(address = game_config.reward_mint)]
+    pub reward_mint: AccountInfo<'info>,
+    #[account(
+        token::mint = reward_mint,
+        token::authority = pull.buyer,
+        token::token_program = token_2022_program
+    )]
+    pub buyer_reward_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub authority: Signer<'info>,
+    /// CHECK: Equality proof account
+    #[account(
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub equality_proof_account: AccountInfo<'info>,
+    /// CHECK: Ciphertext validity proof account
+    #[account(
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub ciphertext_validity_proof_account: AccountInfo<'info>,
+
+    /// CHECK: Range proof account
+    #[account(
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub range_proof_account: AccountInfo<'info>,
+
+    /// CHECK: Zero ciphertext proof account for the remaining balance
+    #[account(
+        owner = zk_elgamal_proof_program.key()
+    )]
+    pub zero_balance_proof_account: AccountInfo<'info>,
+    pub zk_elgamal_proof_program: Program<'info, ZkElgamalProof>,
+    pub token_2022_program: Program<'info, Token2022>,
+}
+
+impl<'info> VerifyPullTransferInstruction
+    for Context<'_, '_, '_, 'info, VerifyPullTransfer<'info>>
+{
+    fn verify_transfer_proofs(&self, params: &VerifyPullTransferParams) -> Result<()> {
+        let authority = self.accounts.authority.key();
+
+        let equality_proof_data = self.accounts.equality_proof_account.data.borrow();
+        let equality_proof = pod_from_bytes::<
+            ProofContextState<CiphertextCommitmentEqualityProofContext>,
+        >(&equality_proof_data)?;
+
+        require!(
+            equality_proof.proof_type
+                == PodProofType::from(ProofType::CiphertextCommitmentEquality),
+            GachaError::InvalidProofType
+        );
+        require!(
+            equality_proof.context_state_authority == authority,
+            GachaError::InvalidContextAuthority
+        );
+
+        let ciphertext_validity_proof_data = self
+            .accounts
+            .ciphertext_validity_proof_account
+            .data
+            .borrow();
+        let ciphertext_validity_proof = pod_from_bytes::<
+            ProofContextState<BatchedGroupedCiphertext3HandlesValidityProofContext>,
+        >(&ciphertext_validity_proof_data)?;
+
+        require!(
+            ciphertext_validity_proof.proof_type
+                == PodProofType::from(ProofType::BatchedGroupedCiphertext3HandlesValidity),
+            GachaError::InvalidProofType
+        );
+        require!(
+            ciphertext_validity_proof.context_state_authority == authority,
+            GachaError::InvalidContextAuthority
+        );
+
+        let range_proof_data = self.accounts.range_proof_account.data.borrow();
+        let range_proof =
+            pod_from_bytes::<ProofContextState<BatchedRangeProofContext>>(&range_proof_data)?;
+
+        require!(
+            range_proof.proof_type == PodProofType::from(ProofType::BatchedRangeProofU128),
+            GachaError::InvalidProofType
+        );
+        require!(
+            range_proof.context_state_authority == authority,
+            GachaError::InvalidContextAuthority
+        );
+
+        // The proofs have to describe a single transfer
+        let proof_context = TransferProofContext::verify_and_extract(
+            &equality_proof.proof_context,
+            &ciphertext_validity_proof.proof_context,
+            &range_proof.proof_context,
+        )
+        .map_err(|_| GachaError::InvalidTransferProofs)?;
+
+        // Source is the reward vault, destination is the buyer reward account
+        let reward_vault_data = self.accounts.reward_vault.try_borrow_data()?;
+        let reward_vault_state =
+            StateWithExtensions::<Token2022Account>::unpack(&reward_vault_data)?;
+        let reward_vault_account =
+            reward_vault_state.get_extension::<ConfidentialTransferAccount>()?;
+
+        require!(
+            proof_context.transfer_pubkeys.source == reward_vault_account.elgamal_pubkey,
+            GachaError::InvalidElgamalPubkey
+        );
+
+        let buyer_reward_account_info = self.accounts.buyer_reward_account.to_account_info();
+        let buyer_reward_account_data = buyer_reward_account_info.try_borrow_data()?;
+        let buyer_reward_account_state =
+            StateWithExtensions::<Token2022Account>::unpack(&buyer_reward_account_data)?;
+        let buyer_confidential_transfer_account =
+            buyer_reward_account_state.get_extension::<ConfidentialTransferAccount>()?;
+
+        require!(
+            proof_context.transfer_pubkeys.destination
+                == buyer_confidential_transfer_account.elgamal_pubkey,
+            GachaError::InvalidElgamalPubkey
+        );
+
+        let reward_mint_data = self.accounts.reward_mint.try_borrow_data()?;
+        let reward_mint_state = StateWithExtensions::<Mint2022>::unpack(&reward_mint_data)?;
+        let confidential_transfer_mint =
+            reward_mint_state.get_extension::<ConfidentialTransferMint>()?;
+
+        require!(
+            confidential_transfer_mint
+                .auditor_elgamal_pubkey
+                .equals(&proof_context.transfer_pubkeys.auditor),
+            GachaError::InvalidElgamalPubkey
+        );
+
+        // Auditor ciphertexts have to match the ones in the validity proof
+        let auditor_ciphertext_lo = proof_context
+            .ciphertext_lo
+            .try_extract_ciphertext(2)
+            .map_err(|_| GachaError::InvalidTransferProofs)?;
+        let auditor_ciphertext_hi = proof_context
+            .ciphertext_hi
+            .try_extract_ciphertext(2)
+            .map_err(|_| GachaError::InvalidTransferProofs)?;
+
+        require!(
+            auditor_ciphertext_lo
+                == parse_ciphertext(&params.transfer_amount_auditor_ciphertext_lo)?
+                && auditor_ciphertext_hi
+                    == parse_ciphertext(&params.transfer_amount_auditor_ciphertext_hi)?,
+            GachaError::AuditorCiphertextMismatch
+        );
+
+        // The new vault balance is the current one minus the transfer amount
+        let source_ciphertext_lo = proof_context
+            .ciphertext_lo
+            .try_extract_ciphertext(0)
+            .map_err(|_| GachaError::InvalidTransferProofs)?;
+        let source_ciphertext_hi = proof_context
+            .ciphertext_hi
+            .try_extract_ciphertext(0)
+            .map_err(|_| GachaError::InvalidTransferProofs)?;
+
+        let new_available_balance = subtract_with_lo_hi(
+            &reward_vault_account.available_balance,
+            &source_ciphertext_lo,
+            &source_ciphertext_hi,
+        )
+        .ok_or(GachaError::CiphertextArithmeticFailed)?;
+
+        require!(
+            new_available_balance == proof_context.new_source_ciphertext,
+            GachaError::InvalidTransferProofs
+        );
+
+        // The whole verified reward has to leave the vault
+        let zero_balance_proof_data = self.accounts.zero_balance_proof_account.data.borrow();
+        let zero_balance_proof = pod_from_bytes::<ProofContextState<ZeroCiphertextProofContext>>(
+            &zero_balance_proof_data,
+        )?;
+
+        require!(
+            zero_balance_proof.proof_type == PodProofType::from(ProofType::ZeroCiphertext),
+            GachaError::InvalidProofType
+        );
+        require!(
+            zero_balance_proof.proof_context.pubkey == reward_vault_account.elgamal_pubkey,
+            GachaError::InvalidElgamalPubkey
+        );
+        require!(
+            zero_balance_proof.proof_context.ciphertext == new_available_balance,
+            GachaError::PartialRewardWithdraw
+        );
+
+        Ok(())
+    }
+}
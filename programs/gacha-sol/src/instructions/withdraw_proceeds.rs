@@ -0,0 +1,196 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::{
+    error::GachaError,
+    event::ProceedsWithdrawn,
+    state::{FeeSchedule, GameConfig, PaymentMode, WithdrawProceedsParams},
+};
+
+use super::WithdrawProceedsInstruction;
+
+pub fn withdraw_proceeds<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawProceeds<'info>>,
+    params: WithdrawProceedsParams,
+) -> Result<()> {
+    require!(params.amount > 0, GachaError::InvalidZeroWithdrawAmount);
+
+    // Only purchases of opened pulls can leave the vault
+    require!(
+        params.amount <= ctx.accounts.game_config.withdrawable_amount()?,
+        GachaError::InsufficientProceeds
+    );
+
+    // With a fee schedule the proceeds are split between its recipients, so purchases
+    // still in escrow are refunded in full
+    let payouts = match ctx.accounts.game_config.has_fee_schedule() {
+        true => {
+            let fee_schedule = ctx
+                .accounts
+                .fee_schedule
+                .as_ref()
+                .ok_or(GachaError::MissingFeeSchedule)?;
+            require!(
+                ctx.accounts.destination.is_none()
+                    && ctx.remaining_accounts.len() == fee_schedule.recipients.len(),
+                GachaError::InvalidFeeRecipients
+            );
+            require!(
+                fee_schedule
+                    .recipients
+                    .iter()
+                    .zip(ctx.remaining_accounts.iter())
+                    .all(|(recipient, account)| recipient.token_account == account.key()),
+                GachaError::InvalidFeeRecipients
+            );
+
+            let amounts = fee_schedule.split(params.amount);
+            ctx.transfer_fees(&amounts)?;
+
+            fee_schedule
+                .recipients
+                .iter()
+                .map(|recipient| recipient.token_account)
+                .zip(amounts)
+                .collect()
+        }
+        false => {
+            let destination = match ctx.accounts.game_config.payment_mode {
+                PaymentMode::Token => {
+                    ctx.transfer_proceeds(params.amount)?;
+                    ctx.accounts
+                        .destination
+                        .as_ref()
+                        .map(|destination| destination.key())
+                }
+                PaymentMode::NativeSol => {
+                    ctx.transfer_proceeds_native(params.amount)?;
+                    ctx.accounts
+                        .native_destination
+                        .as_ref()
+                        .map(|native_destination| native_destination.key())
+                }
+            }
+            .ok_or(GachaError::InvalidPaymentAccounts)?;
+
+            vec![(destination, params.amount)]
+        }
+    };
+
+    let game_config = &mut ctx.accounts.game_config;
+    game_config.withdrawn_amount += params.amount;
+
+    // Emit an event per destination
+    for (destination, amount) in payouts {
+        emit!(ProceedsWithdrawn {
+            game_config: game_config.key(),
+            destination,
+            amount
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProceeds<'info> {
+    #[account(mut, has_one = authority, has_one = game_vault)]
+    pub game_config: Account<'info, GameConfig>,
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub game_vault: Account<'info, TokenAccount>,
+    /// Required when the game is paid in tokens, unless it has a fee schedule
+    #[account(mut,
+        token::mint = game_config.purchase_mint,
+    )]
+    pub destination: Option<Account<'info, TokenAccount>>,
+    /// Required when the game has a fee schedule, the recipient token accounts
+    /// are passed as remaining accounts in the schedule order
+    #[account(address = game_config.fee_schedule)]
+    pub fee_schedule: Option<Box<Account<'info, FeeSchedule>>>,
+    /// Required when the game is paid in native SOL
+    #[account(mut,
+        seeds = [b"treasury", game_config.key().as_ref()],
+        bump = game_config.treasury_bump
+    )]
+    pub treasury: Option<SystemAccount<'info>>,
+    /// Wallet receiving native SOL proceeds
+    #[account(mut)]
+    pub native_destination: Option<SystemAccount<'info>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> WithdrawProceedsInstruction for Context<'_, '_, '_, 'info, WithdrawProceeds<'info>> {
+    fn transfer_proceeds(&self, amount: u64) -> Result<()> {
+        let destination = self
+            .accounts
+            .destination
+            .as_ref()
+            .ok_or(GachaError::InvalidPaymentAccounts)?;
+
+        let signer_seeds = &self.accounts.game_config.get_signer_seeds();
+        let signer = &[&signer_seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: self.accounts.game_vault.to_account_info(),
+            to: destination.to_account_info(),
+            authority: self.accounts.game_config.to_account_info(),
+        };
+        let token_program = self.accounts.token_program.to_account_info().clone();
+        let cpi_context = CpiContext::new_with_signer(token_program, cpi_accounts, signer);
+
+        token::transfer(cpi_context, amount)?;
+
+        Ok(())
+    }
+
+    fn transfer_fees(&self, amounts: &[u64]) -> Result<()> {
+        let signer_seeds = &self.accounts.game_config.get_signer_seeds();
+        let signer = &[&signer_seeds[..]];
+
+        for (recipient_account, amount) in self.remaining_accounts.iter().zip(amounts.iter()) {
+            if *amount == 0 {
+                continue;
+            }
+
+            let cpi_accounts = Transfer {
+                from: self.accounts.game_vault.to_account_info(),
+                to: recipient_account.clone(),
+                authority: self.accounts.game_config.to_account_info(),
+            };
+            let token_program = self.accounts.token_program.to_account_info().clone();
+            let cpi_context = CpiContext::new_with_signer(token_program, cpi_accounts, signer);
+
+            token::transfer(cpi_context, *amount)?;
+        }
+
+        Ok(())
+    }
+
+    fn transfer_proceeds_native(&self, amount: u64) -> Result<()> {
+        let (Some(treasury), Some(native_destination)) =
+            (&self.accounts.treasury, &self.accounts.native_destination)
+        else {
+            return err!(GachaError::InvalidPaymentAccounts);
+        };
+
+        let game_config_key = self.accounts.game_config.key();
+        let signer_seeds = &self
+            .accounts
+            .game_config
+            .get_treasury_signer_seeds(&game_config_key);
+        let signer = &[&signer_seeds[..]];
+
+        let cpi_accounts = system_program::Transfer {
+            from: treasury.to_account_info(),
+            to: native_destination.to_account_info(),
+        };
+        let system_program = self.accounts.system_program.to_account_info();
+        let cpi_context = CpiContext::new_with_signer(system_program, cpi_accounts, signer);
+
+        system_program::transfer(cpi_context, amount)?;
+
+        Ok(())
+    }
+}
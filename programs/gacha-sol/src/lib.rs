@@ -27,6 +27,47 @@ pub mod gacha_sol {
         instructions::initialize_game_config(ctx, params)
     }
 
+    pub fn update_game_config<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateGameConfig<'info>>,
+        params: UpdateGameConfigParams,
+    ) -> Result<()> {
+        instructions::update_game_config(ctx, params)
+    }
+
+    pub fn propose_authority<'info>(
+        ctx: Context<'_, '_, '_, 'info, ProposeAuthority<'info>>,
+    ) -> Result<()> {
+        instructions::propose_authority(ctx)
+    }
+
+    pub fn accept_authority<'info>(
+        ctx: Context<'_, '_, '_, 'info, AcceptAuthority<'info>>,
+    ) -> Result<()> {
+        instructions::accept_authority(ctx)
+    }
+
+    pub fn pause_game<'info>(ctx: Context<'_, '_, '_, 'info, PauseGame<'info>>) -> Result<()> {
+        instructions::pause_game(ctx)
+    }
+
+    pub fn resume_game<'info>(ctx: Context<'_, '_, '_, 'info, ResumeGame<'info>>) -> Result<()> {
+        instructions::resume_game(ctx)
+    }
+
+    pub fn commit_prize_table<'info>(
+        ctx: Context<'_, '_, '_, 'info, CommitPrizeTable<'info>>,
+        params: CommitPrizeTableParams,
+    ) -> Result<()> {
+        instructions::commit_prize_table(ctx, params)
+    }
+
+    pub fn set_fee_schedule<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetFeeSchedule<'info>>,
+        params: SetFeeScheduleParams,
+    ) -> Result<()> {
+        instructions::set_fee_schedule(ctx, params)
+    }
+
     pub fn create_pull<'info>(
         ctx: Context<'_, '_, '_, 'info, CreatePull<'info>>,
         params: CreatePullParams,
@@ -34,6 +75,13 @@ pub mod gacha_sol {
         instructions::create_pull(ctx, params)
     }
 
+    pub fn create_pulls<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePulls<'info>>,
+        params: CreatePullsParams,
+    ) -> Result<()> {
+        instructions::create_pulls(ctx, params)
+    }
+
     pub fn apply_pull_pending_balance<'info>(
         ctx: Context<'_, '_, '_, 'info, ApplyPullPendingBalance<'info>>,
         params: ApplyPullPendingBalanceParams,
@@ -41,8 +89,11 @@ pub mod gacha_sol {
         instructions::apply_pull_pending_balance(ctx, params)
     }
 
-    pub fn verify_pull<'info>(ctx: Context<'_, '_, '_, 'info, VerifyPull<'info>>) -> Result<()> {
-        instructions::verify_pull(ctx)
+    pub fn verify_pull<'info>(
+        ctx: Context<'_, '_, '_, 'info, VerifyPull<'info>>,
+        params: VerifyPullParams,
+    ) -> Result<()> {
+        instructions::verify_pull(ctx, params)
     }
 
     pub fn buy_pull<'info>(
@@ -52,10 +103,94 @@ pub mod gacha_sol {
         instructions::buy_pull(ctx, params)
     }
 
+    pub fn buy_pulls<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyPulls<'info>>,
+        params: BuyPullsParams,
+    ) -> Result<()> {
+        instructions::buy_pulls(ctx, params)
+    }
+
+    pub fn reveal_pull<'info>(
+        ctx: Context<'_, '_, '_, 'info, RevealPull<'info>>,
+        params: RevealPullParams,
+    ) -> Result<()> {
+        instructions::reveal_pull(ctx, params)
+    }
+
     pub fn open_pull<'info>(
         ctx: Context<'_, '_, '_, 'info, OpenPull<'info>>,
         params: OpenPullParams,
     ) -> Result<()> {
         instructions::open_pull(ctx, params)
     }
+
+    pub fn publish_withdraw_proofs<'info>(
+        ctx: Context<'_, '_, '_, 'info, PublishWithdrawProofs<'info>>,
+        params: PublishWithdrawProofsParams,
+    ) -> Result<()> {
+        instructions::publish_withdraw_proofs(ctx, params)
+    }
+
+    pub fn verify_pull_transfer<'info>(
+        ctx: Context<'_, '_, '_, 'info, VerifyPullTransfer<'info>>,
+        params: VerifyPullTransferParams,
+    ) -> Result<()> {
+        instructions::verify_pull_transfer(ctx, params)
+    }
+
+    pub fn claim_pull<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimPull<'info>>,
+        params: ClaimPullParams,
+    ) -> Result<()> {
+        instructions::claim_pull(ctx, params)
+    }
+
+    pub fn claim_pull_confidential<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimPullConfidential<'info>>,
+        params: ClaimPullConfidentialParams,
+    ) -> Result<()> {
+        instructions::claim_pull_confidential(ctx, params)
+    }
+
+    pub fn close_pull<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClosePull<'info>>,
+        params: ClosePullParams,
+    ) -> Result<()> {
+        instructions::close_pull(ctx, params)
+    }
+
+    pub fn refund_pull<'info>(
+        ctx: Context<'_, '_, '_, 'info, RefundPull<'info>>,
+        params: RefundPullParams,
+    ) -> Result<()> {
+        instructions::refund_pull(ctx, params)
+    }
+
+    pub fn withdraw_proceeds<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawProceeds<'info>>,
+        params: WithdrawProceedsParams,
+    ) -> Result<()> {
+        instructions::withdraw_proceeds(ctx, params)
+    }
+
+    pub fn reclaim_pull<'info>(
+        ctx: Context<'_, '_, '_, 'info, ReclaimPull<'info>>,
+        params: ReclaimPullParams,
+    ) -> Result<()> {
+        instructions::reclaim_pull(ctx, params)
+    }
+
+    pub fn pay_referral_fee<'info>(
+        ctx: Context<'_, '_, '_, 'info, PayReferralFee<'info>>,
+        params: PayReferralFeeParams,
+    ) -> Result<()> {
+        instructions::pay_referral_fee(ctx, params)
+    }
+
+    pub fn cancel_ticket<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelTicket<'info>>,
+        params: CancelTicketParams,
+    ) -> Result<()> {
+        instructions::cancel_ticket(ctx, params)
+    }
 }
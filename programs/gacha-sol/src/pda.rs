@@ -6,17 +6,42 @@ fn find_program_address(seed: &[&[u8]]) -> Pubkey {
     Pubkey::find_program_address(seed, &ID).0
 }
 
-pub fn get_game_config_seed<'a>() -> [&'a [u8]; 1] {
-    [b"game_config"]
+pub fn get_game_config_pubkey(game_id: u64) -> Pubkey {
+    let game_id_seed = game_id.to_le_bytes();
+    find_program_address(&[b"game_config", game_id_seed.as_ref()])
 }
 
-pub fn get_game_config_pubkey() -> Pubkey {
-    find_program_address(&get_game_config_seed())
+pub fn get_game_vault_pubkey(game_config: Pubkey, purchase_mint: Pubkey) -> Pubkey {
+    find_program_address(&[b"game_vault", game_config.as_ref(), purchase_mint.as_ref()])
 }
 
-pub fn get_pull_pubkey(pull_id: u64) -> Pubkey {
+pub fn get_pull_pool_pubkey(game_config: Pubkey) -> Pubkey {
+    find_program_address(&[b"pull_pool", game_config.as_ref()])
+}
+
+pub fn get_prize_table_pubkey(game_config: Pubkey) -> Pubkey {
+    find_program_address(&[b"prize_table", game_config.as_ref()])
+}
+
+pub fn get_fee_schedule_pubkey(game_config: Pubkey) -> Pubkey {
+    find_program_address(&[b"fee_schedule", game_config.as_ref()])
+}
+
+pub fn get_treasury_pubkey(game_config: Pubkey) -> Pubkey {
+    find_program_address(&[b"treasury", game_config.as_ref()])
+}
+
+pub fn get_pull_ticket_pubkey(game_config: Pubkey, buyer: Pubkey) -> Pubkey {
+    find_program_address(&[b"pull_ticket", game_config.as_ref(), buyer.as_ref()])
+}
+
+pub fn get_player_state_pubkey(game_config: Pubkey, buyer: Pubkey) -> Pubkey {
+    find_program_address(&[b"player_state", game_config.as_ref(), buyer.as_ref()])
+}
+
+pub fn get_pull_pubkey(game_config: Pubkey, pull_id: u64) -> Pubkey {
     let pull_id_seed = pull_id.to_le_bytes();
-    find_program_address(&[b"pull", pull_id_seed.as_ref()])
+    find_program_address(&[b"pull", game_config.as_ref(), pull_id_seed.as_ref()])
 }
 
 pub fn get_reward_vault_pubkey(pull: Pubkey) -> Pubkey {
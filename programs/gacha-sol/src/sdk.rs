@@ -1,30 +1,77 @@
 use anchor_lang::{
     prelude::*,
-    solana_program::{instruction::Instruction, system_program},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        system_program,
+        sysvar::{self, slot_hashes},
+    },
     InstructionData,
 };
 use anchor_spl::{token, token_2022};
 
 use crate::{
     accounts, instruction,
-    pda::{get_game_config_pubkey, get_pull_pubkey, get_reward_vault_pubkey},
+    pda::{
+        get_fee_schedule_pubkey, get_game_config_pubkey, get_game_vault_pubkey,
+        get_player_state_pubkey, get_prize_table_pubkey, get_pull_pool_pubkey, get_pull_pubkey,
+        get_pull_ticket_pubkey, get_reward_vault_pubkey, get_treasury_pubkey,
+    },
     state::{
-        ApplyPullPendingBalanceParams, BuyPullParams, CreatePullParams, OpenPullParams,
-        AE_CIPHERTEXT_MAX_BASE64_LEN, ELGAMAL_PUBKEY_MAX_BASE64_LEN,
+        AllowlistProof, ApplyPullPendingBalanceParams, BuyPullParams, BuyPullsParams,
+        CancelTicketParams, ClaimPullConfidentialParams, ClaimPullParams, ClosePullParams,
+        CommitPrizeTableParams, CreatePullParams, CreatePullsParams, FeeRecipient, GameConfig,
+        NewPull, OpenPullParams, PayReferralFeeParams, PaymentMode, PlayerState, PrizeTier,
+        PublishWithdrawProofsParams, ReclaimPullParams, RefundPullParams, RevealPullParams,
+        SetFeeScheduleParams, UpdateGameConfigParams, VerifyPullParams, VerifyPullTransferParams,
+        WithdrawProceedsParams, AE_CIPHERTEXT_MAX_BASE64_LEN, ELGAMAL_PUBKEY_MAX_BASE64_LEN,
     },
     utils::{rent::Rent, zk_elgamal_proof_program::ZkElgamalProof},
     ID,
 };
 
+/// Maximum number of pulls created by one create pulls instruction, keeping the
+/// transaction within the size and compute limits
+pub const CREATE_PULLS_BATCH_SIZE: usize = 4;
+
 impl accounts::InitializeGameConfig {
     pub fn populate(
+        game_id: u64,
         authority: Pubkey,
         purchase_mint: Pubkey,
         reward_mint: Pubkey,
-        game_vault: Pubkey,
         payer: Pubkey,
+        payment_mode: PaymentMode,
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let pull_pool = get_pull_pool_pubkey(game_config);
+        let game_vault = get_game_vault_pubkey(game_config, purchase_mint);
+        let native_sol = payment_mode == PaymentMode::NativeSol;
+
+        Self {
+            game_config,
+            pull_pool,
+            authority,
+            purchase_mint,
+            reward_mint,
+            game_vault,
+            treasury: native_sol.then(|| get_treasury_pubkey(game_config)),
+            payer,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        }
+    }
+}
+
+impl accounts::UpdateGameConfig {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        purchase_mint: Option<Pubkey>,
+        reward_mint: Option<Pubkey>,
     ) -> Self {
-        let game_config = get_game_config_pubkey();
+        let game_config = get_game_config_pubkey(game_id);
+        let game_vault =
+            purchase_mint.map(|purchase_mint| get_game_vault_pubkey(game_config, purchase_mint));
 
         Self {
             game_config,
@@ -32,6 +79,66 @@ impl accounts::InitializeGameConfig {
             purchase_mint,
             reward_mint,
             game_vault,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        }
+    }
+}
+
+impl accounts::ProposeAuthority {
+    pub fn populate(game_id: u64, authority: Pubkey, new_authority: Pubkey) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+
+        Self {
+            game_config,
+            authority,
+            new_authority,
+        }
+    }
+}
+
+impl accounts::AcceptAuthority {
+    pub fn populate(game_id: u64, pending_authority: Pubkey) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+
+        Self {
+            game_config,
+            pending_authority,
+        }
+    }
+}
+
+impl accounts::PauseGame {
+    pub fn populate(game_id: u64, authority: Pubkey) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+
+        Self {
+            game_config,
+            authority,
+        }
+    }
+}
+
+impl accounts::ResumeGame {
+    pub fn populate(game_id: u64, authority: Pubkey) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+
+        Self {
+            game_config,
+            authority,
+        }
+    }
+}
+
+impl accounts::CommitPrizeTable {
+    pub fn populate(game_id: u64, authority: Pubkey, payer: Pubkey) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let prize_table = get_prize_table_pubkey(game_config);
+
+        Self {
+            game_config,
+            prize_table,
+            authority,
             payer,
             system_program: system_program::ID,
         }
@@ -40,14 +147,16 @@ impl accounts::InitializeGameConfig {
 
 impl accounts::CreatePull {
     pub fn populate(
+        game_id: u64,
         authority: Pubkey,
         reward_mint: Pubkey,
+        prize_table: Option<Pubkey>,
         payer: Pubkey,
-        pubkey_validity_proof_data: Pubkey,
+        pubkey_validity_proof_data: Option<Pubkey>,
         pull_id: u64,
     ) -> Self {
-        let game_config = get_game_config_pubkey();
-        let pull = get_pull_pubkey(pull_id);
+        let game_config = get_game_config_pubkey(game_id);
+        let pull = get_pull_pubkey(game_config, pull_id);
         let reward_vault = get_reward_vault_pubkey(pull);
 
         Self {
@@ -55,7 +164,35 @@ impl accounts::CreatePull {
             game_config,
             reward_vault,
             reward_mint,
+            prize_table,
+            pubkey_validity_proof_data,
+            instructions_sysvar: get_instructions_sysvar(&[pubkey_validity_proof_data]),
+            authority,
+            payer,
+            system_program: system_program::ID,
+            token_program: token_2022::ID,
+            rent: Rent::id(),
+        }
+    }
+}
+
+impl accounts::CreatePulls {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        reward_mint: Pubkey,
+        prize_table: Option<Pubkey>,
+        payer: Pubkey,
+        pubkey_validity_proof_data: Option<Pubkey>,
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+
+        Self {
+            game_config,
+            reward_mint,
+            prize_table,
             pubkey_validity_proof_data,
+            instructions_sysvar: get_instructions_sysvar(&[pubkey_validity_proof_data]),
             authority,
             payer,
             system_program: system_program::ID,
@@ -66,9 +203,9 @@ impl accounts::CreatePull {
 }
 
 impl accounts::ApplyPullPendingBalance {
-    pub fn populate(authority: Pubkey, pull_id: u64) -> Self {
-        let game_config = get_game_config_pubkey();
-        let pull = get_pull_pubkey(pull_id);
+    pub fn populate(game_id: u64, authority: Pubkey, pull_id: u64) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let pull = get_pull_pubkey(game_config, pull_id);
         let reward_vault = get_reward_vault_pubkey(pull);
 
         Self {
@@ -83,72 +220,226 @@ impl accounts::ApplyPullPendingBalance {
 
 impl accounts::VerifyPull {
     pub fn populate(
+        game_id: u64,
         authority: Pubkey,
-        zero_ciphertext_proof_context: Pubkey,
+        zero_ciphertext_proof_context: Option<Pubkey>,
+        context_state_destination: Option<Pubkey>,
         pull_id: u64,
     ) -> Self {
-        let game_config = get_game_config_pubkey();
-        let pull = get_pull_pubkey(pull_id);
+        Self::populate_prize_range(
+            game_id,
+            authority,
+            zero_ciphertext_proof_context,
+            context_state_destination,
+            None,
+            pull_id,
+        )
+    }
+
+    /// Verify a pull of a game with a prize table, `amount_range_proofs` holds the min amount
+    /// equality, max amount equality and range proof context accounts
+    pub fn populate_prize_range(
+        game_id: u64,
+        authority: Pubkey,
+        zero_ciphertext_proof_context: Option<Pubkey>,
+        context_state_destination: Option<Pubkey>,
+        amount_range_proofs: Option<[Pubkey; 3]>,
+        pull_id: u64,
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let pull = get_pull_pubkey(game_config, pull_id);
         let reward_vault = get_reward_vault_pubkey(pull);
 
+        let pull_pool = get_pull_pool_pubkey(game_config);
+
         Self {
             game_config,
+            pull_pool,
             pull,
             reward_vault,
             authority,
             zero_ciphertext_proof_context,
+            prize_table: amount_range_proofs.map(|_| get_prize_table_pubkey(game_config)),
+            min_amount_equality_proof: amount_range_proofs.map(|proofs| proofs[0]),
+            max_amount_equality_proof: amount_range_proofs.map(|proofs| proofs[1]),
+            amount_range_proof: amount_range_proofs.map(|proofs| proofs[2]),
+            context_state_destination,
+            instructions_sysvar: get_instructions_sysvar(&[zero_ciphertext_proof_context]),
             zk_elgamal_proof_program: ZkElgamalProof::id(),
             token_program: token_2022::ID,
         }
     }
 }
 
+impl accounts::SetFeeSchedule {
+    pub fn populate(game_id: u64, authority: Pubkey, payer: Pubkey) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let fee_schedule = get_fee_schedule_pubkey(game_config);
+
+        Self {
+            game_config,
+            fee_schedule,
+            authority,
+            payer,
+            system_program: system_program::ID,
+        }
+    }
+}
+
 impl accounts::BuyPull {
     pub fn populate(
+        game_id: u64,
         buyer: Pubkey,
         buyer_purchase_account: Pubkey,
-        game_vault: Pubkey,
         purchase_mint: Pubkey,
-        pull_id: u64,
+        referrer_purchase_account: Option<Pubkey>,
+        payment_mode: PaymentMode,
     ) -> Self {
-        let game_config = get_game_config_pubkey();
-        let pull = get_pull_pubkey(pull_id);
+        let game_config = get_game_config_pubkey(game_id);
+        let game_vault = get_game_vault_pubkey(game_config, purchase_mint);
+        let pull_pool = get_pull_pool_pubkey(game_config);
+        let pull_ticket = get_pull_ticket_pubkey(game_config, buyer);
+        let player_state = get_player_state_pubkey(game_config, buyer);
+        let native_sol = payment_mode == PaymentMode::NativeSol;
 
         Self {
             game_config,
-            pull,
+            pull_pool,
+            pull_ticket,
+            player_state,
             buyer,
-            buyer_purchase_account,
+            buyer_purchase_account: (!native_sol).then_some(buyer_purchase_account),
+            referrer_purchase_account,
+            game_vault,
+            purchase_mint,
+            treasury: native_sol.then(|| get_treasury_pubkey(game_config)),
+            token_program: token::ID,
+            system_program: system_program::ID,
+        }
+    }
+}
+
+impl accounts::BuyPulls {
+    pub fn populate(
+        game_id: u64,
+        buyer: Pubkey,
+        buyer_purchase_account: Pubkey,
+        purchase_mint: Pubkey,
+        payment_mode: PaymentMode,
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let game_vault = get_game_vault_pubkey(game_config, purchase_mint);
+        let pull_pool = get_pull_pool_pubkey(game_config);
+        let pull_ticket = get_pull_ticket_pubkey(game_config, buyer);
+        let player_state = get_player_state_pubkey(game_config, buyer);
+        let native_sol = payment_mode == PaymentMode::NativeSol;
+
+        Self {
+            game_config,
+            pull_pool,
+            pull_ticket,
+            player_state,
+            buyer,
+            buyer_purchase_account: (!native_sol).then_some(buyer_purchase_account),
             game_vault,
             purchase_mint,
+            treasury: native_sol.then(|| get_treasury_pubkey(game_config)),
+            token_program: token::ID,
+            system_program: system_program::ID,
+        }
+    }
+}
+
+impl accounts::RevealPull {
+    /// `authority` signs the reveal of a ticket whose commit slot hash expired
+    pub fn populate(game_id: u64, buyer: Pubkey, pull_id: u64, authority: Option<Pubkey>) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let pull_pool = get_pull_pool_pubkey(game_config);
+        let pull_ticket = get_pull_ticket_pubkey(game_config, buyer);
+        let player_state = get_player_state_pubkey(game_config, buyer);
+        let pull = get_pull_pubkey(game_config, pull_id);
+
+        Self {
+            game_config,
+            pull_pool,
+            pull_ticket,
+            player_state,
+            pull,
+            buyer,
+            slot_hashes: slot_hashes::ID,
+            authority,
+        }
+    }
+}
+
+impl accounts::CancelTicket {
+    pub fn populate(
+        game_id: u64,
+        buyer: Pubkey,
+        buyer_purchase_account: Pubkey,
+        purchase_mint: Pubkey,
+        payment_mode: PaymentMode,
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let game_vault = get_game_vault_pubkey(game_config, purchase_mint);
+        let pull_pool = get_pull_pool_pubkey(game_config);
+        let pull_ticket = get_pull_ticket_pubkey(game_config, buyer);
+        let player_state = get_player_state_pubkey(game_config, buyer);
+        let native_sol = payment_mode == PaymentMode::NativeSol;
+
+        Self {
+            game_config,
+            pull_pool,
+            pull_ticket,
+            player_state,
+            buyer,
+            buyer_purchase_account: (!native_sol).then_some(buyer_purchase_account),
+            game_vault,
+            treasury: native_sol.then(|| get_treasury_pubkey(game_config)),
+            slot_hashes: slot_hashes::ID,
             token_program: token::ID,
+            system_program: system_program::ID,
         }
     }
 }
 
 impl accounts::OpenPull {
     pub fn populate(
+        game_id: u64,
         buyer: Pubkey,
         buyer_reward_account: Pubkey,
         reward_mint: Pubkey,
-        equality_proof_account: Pubkey,
-        range_proof_account: Pubkey,
+        prize_table: Option<Pubkey>,
+        equality_proof_account: Option<Pubkey>,
+        range_proof_account: Option<Pubkey>,
+        zero_balance_proof_account: Option<Pubkey>,
+        context_state_destination: Option<Pubkey>,
         authority: Pubkey,
         pull_id: u64,
     ) -> Self {
-        let game_config = get_game_config_pubkey();
-        let pull = get_pull_pubkey(pull_id);
+        let game_config = get_game_config_pubkey(game_id);
+        let pull = get_pull_pubkey(game_config, pull_id);
         let reward_vault = get_reward_vault_pubkey(pull);
+        let player_state = get_player_state_pubkey(game_config, buyer);
 
         Self {
             pull,
             game_config,
             buyer,
+            player_state,
             reward_vault,
             buyer_reward_account,
             reward_mint,
+            prize_table,
             equality_proof_account,
             range_proof_account,
+            zero_balance_proof_account,
+            context_state_destination,
+            instructions_sysvar: get_instructions_sysvar(&[
+                equality_proof_account,
+                range_proof_account,
+                zero_balance_proof_account,
+            ]),
             authority,
             zk_elgamal_proof_program: ZkElgamalProof::id(),
             token_program: token::ID,
@@ -157,173 +448,1416 @@ impl accounts::OpenPull {
     }
 }
 
-impl instruction::InitializeGameConfig {
+impl accounts::PublishWithdrawProofs {
     pub fn populate(
+        game_id: u64,
         authority: Pubkey,
-        purchase_mint: Pubkey,
-        reward_mint: Pubkey,
-        game_vault: Pubkey,
-        payer: Pubkey,
-        pull_price: u64,
-    ) -> Instruction {
-        let initialize_game_config_accounts = accounts::InitializeGameConfig::populate(
-            authority,
-            purchase_mint,
-            reward_mint,
-            game_vault,
-            payer,
-        )
-        .to_account_metas(None);
+        equality_proof_account: Pubkey,
+        range_proof_account: Pubkey,
+        zero_balance_proof_account: Pubkey,
+        pull_id: u64,
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let pull = get_pull_pubkey(game_config, pull_id);
+        let reward_vault = get_reward_vault_pubkey(pull);
 
-        Instruction {
-            program_id: ID,
-            accounts: initialize_game_config_accounts,
-            data: instruction::InitializeGameConfig {
-                params: crate::InitializeGameConfigParams { pull_price },
-            }
-            .data(),
+        Self {
+            game_config,
+            pull,
+            reward_vault,
+            equality_proof_account,
+            range_proof_account,
+            zero_balance_proof_account,
+            authority,
+            zk_elgamal_proof_program: ZkElgamalProof::id(),
         }
     }
 }
 
-impl instruction::CreatePull {
+impl accounts::VerifyPullTransfer {
     pub fn populate(
+        game_id: u64,
         authority: Pubkey,
+        buyer_reward_account: Pubkey,
         reward_mint: Pubkey,
-        payer: Pubkey,
-        pubkey_validity_proof_data: Pubkey,
+        equality_proof_account: Pubkey,
+        ciphertext_validity_proof_account: Pubkey,
+        range_proof_account: Pubkey,
+        zero_balance_proof_account: Pubkey,
         pull_id: u64,
-        encrypted_amount: [u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
-        decryptable_zero_balance_base64: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
-    ) -> Instruction {
-        let create_pull_accounts = accounts::CreatePull::populate(
-            authority,
-            reward_mint,
-            payer,
-            pubkey_validity_proof_data,
-            pull_id,
-        )
-        .to_account_metas(None);
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let pull = get_pull_pubkey(game_config, pull_id);
+        let reward_vault = get_reward_vault_pubkey(pull);
 
-        Instruction {
-            program_id: ID,
-            accounts: create_pull_accounts,
-            data: instruction::CreatePull {
-                params: CreatePullParams {
-                    pull_id,
-                    encrypted_amount,
-                    decryptable_zero_balance_base64,
-                },
-            }
-            .data(),
+        Self {
+            game_config,
+            pull,
+            reward_vault,
+            reward_mint,
+            buyer_reward_account,
+            authority,
+            equality_proof_account,
+            ciphertext_validity_proof_account,
+            range_proof_account,
+            zero_balance_proof_account,
+            zk_elgamal_proof_program: ZkElgamalProof::id(),
+            token_2022_program: token_2022::ID,
         }
     }
 }
 
-impl instruction::ApplyPullPendingBalance {
+impl accounts::ClaimPull {
     pub fn populate(
-        authority: Pubkey,
+        game_id: u64,
+        buyer: Pubkey,
+        buyer_reward_account: Pubkey,
+        reward_mint: Pubkey,
+        prize_table: Option<Pubkey>,
+        equality_proof_account: Pubkey,
+        range_proof_account: Pubkey,
+        zero_balance_proof_account: Pubkey,
         pull_id: u64,
-        new_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
-    ) -> Instruction {
-        let apply_pull_pending_balance_accounts =
-            accounts::ApplyPullPendingBalance::populate(authority, pull_id).to_account_metas(None);
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let pull = get_pull_pubkey(game_config, pull_id);
+        let reward_vault = get_reward_vault_pubkey(pull);
+        let player_state = get_player_state_pubkey(game_config, buyer);
 
-        Instruction {
-            program_id: ID,
-            accounts: apply_pull_pending_balance_accounts,
-            data: instruction::ApplyPullPendingBalance {
-                params: ApplyPullPendingBalanceParams {
-                    new_decryptable_available_balance,
-                },
-            }
-            .data(),
+        Self {
+            pull,
+            game_config,
+            buyer,
+            player_state,
+            reward_vault,
+            buyer_reward_account,
+            reward_mint,
+            prize_table,
+            equality_proof_account,
+            range_proof_account,
+            zero_balance_proof_account,
+            zk_elgamal_proof_program: ZkElgamalProof::id(),
+            token_2022_program: token_2022::ID,
         }
     }
 }
 
-impl instruction::VerifyPull {
+impl accounts::ClaimPullConfidential {
     pub fn populate(
-        authority: Pubkey,
-        zero_ciphertext_proof_context: Pubkey,
+        game_id: u64,
+        buyer: Pubkey,
+        buyer_reward_account: Pubkey,
+        reward_mint: Pubkey,
+        equality_proof_account: Pubkey,
+        ciphertext_validity_proof_account: Pubkey,
+        range_proof_account: Pubkey,
+        pull_id: u64,
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let pull = get_pull_pubkey(game_config, pull_id);
+        let reward_vault = get_reward_vault_pubkey(pull);
+        let player_state = get_player_state_pubkey(game_config, buyer);
+
+        Self {
+            pull,
+            game_config,
+            buyer,
+            player_state,
+            reward_vault,
+            buyer_reward_account,
+            reward_mint,
+            equality_proof_account,
+            ciphertext_validity_proof_account,
+            range_proof_account,
+            zk_elgamal_proof_program: ZkElgamalProof::id(),
+            token_2022_program: token_2022::ID,
+        }
+    }
+}
+
+impl accounts::ClosePull {
+    /// `published_withdraw_proofs` holds the equality and range proof accounts published for
+    /// the pull, closed along with it
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        zero_balance_proof_account: Pubkey,
+        published_withdraw_proofs: Option<[Pubkey; 2]>,
+        rent_receiver: Pubkey,
+        pull_id: u64,
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let pull = get_pull_pubkey(game_config, pull_id);
+        let reward_vault = get_reward_vault_pubkey(pull);
+
+        Self {
+            game_config,
+            pull,
+            reward_vault,
+            zero_balance_proof_account,
+            equality_proof_account: published_withdraw_proofs.map(|proofs| proofs[0]),
+            range_proof_account: published_withdraw_proofs.map(|proofs| proofs[1]),
+            authority,
+            rent_receiver,
+            zk_elgamal_proof_program: ZkElgamalProof::id(),
+            token_2022_program: token_2022::ID,
+        }
+    }
+}
+
+impl accounts::RefundPull {
+    pub fn populate(
+        game_id: u64,
+        buyer: Pubkey,
+        buyer_purchase_account: Pubkey,
+        purchase_mint: Pubkey,
+        pull_id: u64,
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let pull = get_pull_pubkey(game_config, pull_id);
+        let game_vault = get_game_vault_pubkey(game_config, purchase_mint);
+
+        Self {
+            game_config,
+            pull,
+            buyer,
+            buyer_purchase_account: Some(buyer_purchase_account),
+            game_vault,
+            treasury: None,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        }
+    }
+
+    /// Refund of a game paid in native SOL, from the treasury to the buyer
+    pub fn populate_native(
+        game_id: u64,
+        buyer: Pubkey,
+        purchase_mint: Pubkey,
+        pull_id: u64,
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let pull = get_pull_pubkey(game_config, pull_id);
+        let game_vault = get_game_vault_pubkey(game_config, purchase_mint);
+        let treasury = get_treasury_pubkey(game_config);
+
+        Self {
+            game_config,
+            pull,
+            buyer,
+            buyer_purchase_account: None,
+            game_vault,
+            treasury: Some(treasury),
+            token_program: token::ID,
+            system_program: system_program::ID,
+        }
+    }
+}
+
+impl accounts::WithdrawProceeds {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        purchase_mint: Pubkey,
+        destination: Pubkey,
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let game_vault = get_game_vault_pubkey(game_config, purchase_mint);
+
+        Self {
+            game_config,
+            authority,
+            game_vault,
+            destination: Some(destination),
+            fee_schedule: None,
+            treasury: None,
+            native_destination: None,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        }
+    }
+
+    /// Withdrawal of a game paid in native SOL, from the treasury to the destination wallet
+    pub fn populate_native(
+        game_id: u64,
+        authority: Pubkey,
+        purchase_mint: Pubkey,
+        destination: Pubkey,
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let game_vault = get_game_vault_pubkey(game_config, purchase_mint);
+        let treasury = get_treasury_pubkey(game_config);
+
+        Self {
+            game_config,
+            authority,
+            game_vault,
+            destination: None,
+            fee_schedule: None,
+            treasury: Some(treasury),
+            native_destination: Some(destination),
+            token_program: token::ID,
+            system_program: system_program::ID,
+        }
+    }
+
+    /// Withdrawal of a game with a fee schedule, split between the schedule recipients
+    pub fn populate_fee_schedule(game_id: u64, authority: Pubkey, purchase_mint: Pubkey) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let game_vault = get_game_vault_pubkey(game_config, purchase_mint);
+        let fee_schedule = get_fee_schedule_pubkey(game_config);
+
+        Self {
+            game_config,
+            authority,
+            game_vault,
+            destination: None,
+            fee_schedule: Some(fee_schedule),
+            treasury: None,
+            native_destination: None,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        }
+    }
+}
+
+impl accounts::ReclaimPull {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        destination: Pubkey,
+        reward_mint: Pubkey,
+        equality_proof_account: Pubkey,
+        range_proof_account: Pubkey,
+        zero_balance_proof_account: Pubkey,
+        pull_id: u64,
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let pull = get_pull_pubkey(game_config, pull_id);
+        let reward_vault = get_reward_vault_pubkey(pull);
+
+        Self {
+            game_config,
+            pull,
+            reward_vault,
+            destination,
+            reward_mint,
+            equality_proof_account,
+            range_proof_account,
+            zero_balance_proof_account,
+            authority,
+            zk_elgamal_proof_program: ZkElgamalProof::id(),
+            token_2022_program: token_2022::ID,
+        }
+    }
+}
+
+impl accounts::PayReferralFee {
+    pub fn populate(
+        game_id: u64,
+        purchase_mint: Pubkey,
+        referrer_purchase_account: Pubkey,
+        pull_id: u64,
+    ) -> Self {
+        let game_config = get_game_config_pubkey(game_id);
+        let pull = get_pull_pubkey(game_config, pull_id);
+        let game_vault = get_game_vault_pubkey(game_config, purchase_mint);
+
+        Self {
+            game_config,
+            pull,
+            referrer_purchase_account,
+            game_vault,
+            token_program: token::ID,
+        }
+    }
+}
+
+impl instruction::InitializeGameConfig {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        purchase_mint: Pubkey,
+        reward_mint: Pubkey,
+        payer: Pubkey,
+        pull_price: u64,
+        refund_timeout: i64,
+        sale_start: i64,
+        sale_end: i64,
+        payment_mode: PaymentMode,
+    ) -> Instruction {
+        let initialize_game_config_accounts = accounts::InitializeGameConfig::populate(
+            game_id,
+            authority,
+            purchase_mint,
+            reward_mint,
+            payer,
+            payment_mode,
+        )
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: initialize_game_config_accounts,
+            data: instruction::InitializeGameConfig {
+                params: crate::InitializeGameConfigParams {
+                    game_id,
+                    pull_price,
+                    refund_timeout,
+                    sale_start,
+                    sale_end,
+                    payment_mode,
+                },
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::UpdateGameConfig {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        purchase_mint: Option<Pubkey>,
+        reward_mint: Option<Pubkey>,
+        params: UpdateGameConfigParams,
+    ) -> Instruction {
+        let update_game_config_accounts =
+            accounts::UpdateGameConfig::populate(game_id, authority, purchase_mint, reward_mint)
+                .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: update_game_config_accounts,
+            data: instruction::UpdateGameConfig { params }.data(),
+        }
+    }
+}
+
+impl instruction::ProposeAuthority {
+    pub fn populate(game_id: u64, authority: Pubkey, new_authority: Pubkey) -> Instruction {
+        let propose_authority_accounts =
+            accounts::ProposeAuthority::populate(game_id, authority, new_authority)
+                .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: propose_authority_accounts,
+            data: instruction::ProposeAuthority {}.data(),
+        }
+    }
+}
+
+impl instruction::AcceptAuthority {
+    pub fn populate(game_id: u64, pending_authority: Pubkey) -> Instruction {
+        let accept_authority_accounts =
+            accounts::AcceptAuthority::populate(game_id, pending_authority).to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: accept_authority_accounts,
+            data: instruction::AcceptAuthority {}.data(),
+        }
+    }
+}
+
+impl instruction::PauseGame {
+    pub fn populate(game_id: u64, authority: Pubkey) -> Instruction {
+        let pause_game_accounts =
+            accounts::PauseGame::populate(game_id, authority).to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: pause_game_accounts,
+            data: instruction::PauseGame {}.data(),
+        }
+    }
+}
+
+impl instruction::ResumeGame {
+    pub fn populate(game_id: u64, authority: Pubkey) -> Instruction {
+        let resume_game_accounts =
+            accounts::ResumeGame::populate(game_id, authority).to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: resume_game_accounts,
+            data: instruction::ResumeGame {}.data(),
+        }
+    }
+}
+
+impl instruction::CommitPrizeTable {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        payer: Pubkey,
+        tiers: Vec<PrizeTier>,
+    ) -> Instruction {
+        let commit_prize_table_accounts =
+            accounts::CommitPrizeTable::populate(game_id, authority, payer).to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: commit_prize_table_accounts,
+            data: instruction::CommitPrizeTable {
+                params: CommitPrizeTableParams { tiers },
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::CreatePull {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        reward_mint: Pubkey,
+        prize_table: Option<Pubkey>,
+        payer: Pubkey,
+        pubkey_validity_proof_data: Option<Pubkey>,
+        pull_id: u64,
+        encrypted_amount: [u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
+        tier: u8,
+        decryptable_zero_balance_base64: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+    ) -> Instruction {
+        let create_pull_accounts = accounts::CreatePull::populate(
+            game_id,
+            authority,
+            reward_mint,
+            prize_table,
+            payer,
+            pubkey_validity_proof_data,
+            pull_id,
+        )
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: create_pull_accounts,
+            data: instruction::CreatePull {
+                params: CreatePullParams {
+                    pull_id,
+                    encrypted_amount,
+                    tier,
+                    decryptable_zero_balance_base64,
+                    proof_instruction_offset: get_proof_instruction_offsets([
+                        pubkey_validity_proof_data,
+                    ])[0],
+                },
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::CreatePulls {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        reward_mint: Pubkey,
+        prize_table: Option<Pubkey>,
+        payer: Pubkey,
+        pubkey_validity_proof_data: Option<Pubkey>,
+        first_pull_id: u64,
+        pulls: Vec<NewPull>,
+        decryptable_zero_balance_base64: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+    ) -> Instruction {
+        let game_config = get_game_config_pubkey(game_id);
+
+        let mut create_pulls_accounts = accounts::CreatePulls::populate(
+            game_id,
+            authority,
+            reward_mint,
+            prize_table,
+            payer,
+            pubkey_validity_proof_data,
+        )
+        .to_account_metas(None);
+
+        for pull_id in first_pull_id..first_pull_id + pulls.len() as u64 {
+            let pull = get_pull_pubkey(game_config, pull_id);
+            let reward_vault = get_reward_vault_pubkey(pull);
+
+            create_pulls_accounts.push(AccountMeta::new(pull, false));
+            create_pulls_accounts.push(AccountMeta::new(reward_vault, false));
+        }
+
+        Instruction {
+            program_id: ID,
+            accounts: create_pulls_accounts,
+            data: instruction::CreatePulls {
+                params: CreatePullsParams {
+                    first_pull_id,
+                    pulls,
+                    decryptable_zero_balance_base64,
+                    proof_instruction_offset: get_proof_instruction_offsets([
+                        pubkey_validity_proof_data,
+                    ])[0],
+                },
+            }
+            .data(),
+        }
+    }
+
+    /// One create pulls instruction per transaction, each covering at most
+    /// `CREATE_PULLS_BATCH_SIZE` pulls, to be sent in order
+    pub fn populate_batches(
+        game_id: u64,
+        authority: Pubkey,
+        reward_mint: Pubkey,
+        prize_table: Option<Pubkey>,
+        payer: Pubkey,
+        pubkey_validity_proof_data: Option<Pubkey>,
+        first_pull_id: u64,
+        pulls: &[NewPull],
+        decryptable_zero_balance_base64: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+    ) -> Vec<Instruction> {
+        pulls
+            .chunks(CREATE_PULLS_BATCH_SIZE)
+            .enumerate()
+            .map(|(index, pulls)| {
+                Self::populate(
+                    game_id,
+                    authority,
+                    reward_mint,
+                    prize_table,
+                    payer,
+                    pubkey_validity_proof_data,
+                    first_pull_id + (index * CREATE_PULLS_BATCH_SIZE) as u64,
+                    pulls.to_vec(),
+                    decryptable_zero_balance_base64,
+                )
+            })
+            .collect()
+    }
+}
+
+impl instruction::ApplyPullPendingBalance {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        pull_id: u64,
+        new_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+    ) -> Instruction {
+        let apply_pull_pending_balance_accounts =
+            accounts::ApplyPullPendingBalance::populate(game_id, authority, pull_id)
+                .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: apply_pull_pending_balance_accounts,
+            data: instruction::ApplyPullPendingBalance {
+                params: ApplyPullPendingBalanceParams {
+                    new_decryptable_available_balance,
+                },
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::VerifyPull {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        zero_ciphertext_proof_context: Option<Pubkey>,
+        context_state_destination: Option<Pubkey>,
+        pull_id: u64,
+    ) -> Instruction {
+        Self::populate_prize_range(
+            game_id,
+            authority,
+            zero_ciphertext_proof_context,
+            context_state_destination,
+            None,
+            pull_id,
+        )
+    }
+
+    pub fn populate_prize_range(
+        game_id: u64,
+        authority: Pubkey,
+        zero_ciphertext_proof_context: Option<Pubkey>,
+        context_state_destination: Option<Pubkey>,
+        amount_range_proofs: Option<[Pubkey; 3]>,
+        pull_id: u64,
+    ) -> Instruction {
+        let verify_pull_accounts = accounts::VerifyPull::populate_prize_range(
+            game_id,
+            authority,
+            zero_ciphertext_proof_context,
+            context_state_destination,
+            amount_range_proofs,
+            pull_id,
+        )
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: verify_pull_accounts,
+            data: instruction::VerifyPull {
+                params: VerifyPullParams {
+                    zero_ciphertext_proof_instruction_offset: get_proof_instruction_offsets([
+                        zero_ciphertext_proof_context,
+                    ])[0],
+                },
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::SetFeeSchedule {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        payer: Pubkey,
+        recipients: Vec<FeeRecipient>,
+    ) -> Instruction {
+        let set_fee_schedule_accounts =
+            accounts::SetFeeSchedule::populate(game_id, authority, payer).to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: set_fee_schedule_accounts,
+            data: instruction::SetFeeSchedule {
+                params: SetFeeScheduleParams { recipients },
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::BuyPull {
+    pub fn populate(
+        game_id: u64,
+        buyer: Pubkey,
+        buyer_purchase_account: Pubkey,
+        purchase_mint: Pubkey,
+        referrer_purchase_account: Option<Pubkey>,
+        payment_mode: PaymentMode,
+        allowlist: Option<AllowlistProof>,
+    ) -> Instruction {
+        // Games paid in native SOL transfer from the buyer to the treasury,
+        // `buyer_purchase_account` is then left out
+        let buy_pull_accounts = accounts::BuyPull::populate(
+            game_id,
+            buyer,
+            buyer_purchase_account,
+            purchase_mint,
+            referrer_purchase_account,
+            payment_mode,
+        )
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: buy_pull_accounts,
+            data: instruction::BuyPull {
+                params: BuyPullParams { allowlist },
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::BuyPulls {
+    pub fn populate(
+        game_id: u64,
+        buyer: Pubkey,
+        buyer_purchase_account: Pubkey,
+        purchase_mint: Pubkey,
+        payment_mode: PaymentMode,
+        bundle_size: u8,
+        allowlist: Option<AllowlistProof>,
+    ) -> Instruction {
+        // Games paid in native SOL transfer from the buyer to the treasury,
+        // `buyer_purchase_account` is then left out
+        let buy_pulls_accounts = accounts::BuyPulls::populate(
+            game_id,
+            buyer,
+            buyer_purchase_account,
+            purchase_mint,
+            payment_mode,
+        )
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: buy_pulls_accounts,
+            data: instruction::BuyPulls {
+                params: BuyPullsParams {
+                    bundle_size,
+                    allowlist,
+                },
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::RevealPull {
+    pub fn populate(game_id: u64, buyer: Pubkey, pull_id: u64) -> Instruction {
+        Self::populate_bundle(game_id, buyer, &[pull_id], None)
+    }
+
+    /// Reveal a bundle ticket, `pull_ids` are the selected pulls in draw order.
+    /// `authority` signs the reveal of a ticket whose commit slot hash expired.
+    pub fn populate_bundle(
+        game_id: u64,
+        buyer: Pubkey,
+        pull_ids: &[u64],
+        authority: Option<Pubkey>,
+    ) -> Instruction {
+        let game_config = get_game_config_pubkey(game_id);
+        let mut reveal_pull_accounts =
+            accounts::RevealPull::populate(game_id, buyer, pull_ids[0], authority)
+                .to_account_metas(None);
+
+        for pull_id in &pull_ids[1..] {
+            let pull = get_pull_pubkey(game_config, *pull_id);
+            reveal_pull_accounts.push(AccountMeta::new(pull, false));
+        }
+
+        Instruction {
+            program_id: ID,
+            accounts: reveal_pull_accounts,
+            data: instruction::RevealPull {
+                params: RevealPullParams {},
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::CancelTicket {
+    pub fn populate(
+        game_id: u64,
+        buyer: Pubkey,
+        buyer_purchase_account: Pubkey,
+        purchase_mint: Pubkey,
+        payment_mode: PaymentMode,
+    ) -> Instruction {
+        // Games paid in native SOL are refunded from the treasury to the buyer,
+        // `buyer_purchase_account` is then left out
+        let cancel_ticket_accounts = accounts::CancelTicket::populate(
+            game_id,
+            buyer,
+            buyer_purchase_account,
+            purchase_mint,
+            payment_mode,
+        )
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: cancel_ticket_accounts,
+            data: instruction::CancelTicket {
+                params: CancelTicketParams {},
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::OpenPull {
+    pub fn populate(
+        game_id: u64,
+        buyer: Pubkey,
+        buyer_reward_account: Pubkey,
+        reward_mint: Pubkey,
+        prize_table: Option<Pubkey>,
+        equality_proof_account: Option<Pubkey>,
+        range_proof_account: Option<Pubkey>,
+        zero_balance_proof_account: Option<Pubkey>,
+        context_state_destination: Option<Pubkey>,
+        authority: Pubkey,
+        pull_id: u64,
+        amount: u64,
+        decimals: u8,
+        new_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+    ) -> Instruction {
+        let open_pull_accounts = accounts::OpenPull::populate(
+            game_id,
+            buyer,
+            buyer_reward_account,
+            reward_mint,
+            prize_table,
+            equality_proof_account,
+            range_proof_account,
+            zero_balance_proof_account,
+            context_state_destination,
+            authority,
+            pull_id,
+        )
+        .to_account_metas(None);
+
+        let [equality_proof_instruction_offset, range_proof_instruction_offset, zero_balance_proof_instruction_offset] =
+            get_proof_instruction_offsets([
+                equality_proof_account,
+                range_proof_account,
+                zero_balance_proof_account,
+            ]);
+
+        Instruction {
+            program_id: ID,
+            accounts: open_pull_accounts,
+            data: instruction::OpenPull {
+                params: OpenPullParams {
+                    pull_id,
+                    amount,
+                    decimals,
+                    new_decryptable_available_balance,
+                    equality_proof_instruction_offset,
+                    range_proof_instruction_offset,
+                    zero_balance_proof_instruction_offset,
+                },
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::PublishWithdrawProofs {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        equality_proof_account: Pubkey,
+        range_proof_account: Pubkey,
+        zero_balance_proof_account: Pubkey,
         pull_id: u64,
+        amount: u64,
+        new_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
     ) -> Instruction {
-        let verify_pull_accounts =
-            accounts::VerifyPull::populate(authority, zero_ciphertext_proof_context, pull_id)
-                .to_account_metas(None);
+        let publish_withdraw_proofs_accounts = accounts::PublishWithdrawProofs::populate(
+            game_id,
+            authority,
+            equality_proof_account,
+            range_proof_account,
+            zero_balance_proof_account,
+            pull_id,
+        )
+        .to_account_metas(None);
 
         Instruction {
             program_id: ID,
-            accounts: verify_pull_accounts,
-            data: instruction::VerifyPull {}.data(),
+            accounts: publish_withdraw_proofs_accounts,
+            data: instruction::PublishWithdrawProofs {
+                params: PublishWithdrawProofsParams {
+                    pull_id,
+                    amount,
+                    new_decryptable_available_balance,
+                },
+            }
+            .data(),
         }
     }
 }
 
-impl instruction::BuyPull {
+impl instruction::VerifyPullTransfer {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        buyer_reward_account: Pubkey,
+        reward_mint: Pubkey,
+        equality_proof_account: Pubkey,
+        ciphertext_validity_proof_account: Pubkey,
+        range_proof_account: Pubkey,
+        zero_balance_proof_account: Pubkey,
+        pull_id: u64,
+        transfer_amount_auditor_ciphertext_lo: [u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
+        transfer_amount_auditor_ciphertext_hi: [u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
+        final_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+    ) -> Instruction {
+        let verify_pull_transfer_accounts = accounts::VerifyPullTransfer::populate(
+            game_id,
+            authority,
+            buyer_reward_account,
+            reward_mint,
+            equality_proof_account,
+            ciphertext_validity_proof_account,
+            range_proof_account,
+            zero_balance_proof_account,
+            pull_id,
+        )
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: verify_pull_transfer_accounts,
+            data: instruction::VerifyPullTransfer {
+                params: VerifyPullTransferParams {
+                    pull_id,
+                    transfer_amount_auditor_ciphertext_lo,
+                    transfer_amount_auditor_ciphertext_hi,
+                    final_decryptable_available_balance,
+                },
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::ClaimPull {
     pub fn populate(
+        game_id: u64,
         buyer: Pubkey,
-        buyer_purchase_account: Pubkey,
-        game_vault: Pubkey,
-        purchase_mint: Pubkey,
+        buyer_reward_account: Pubkey,
+        reward_mint: Pubkey,
+        prize_table: Option<Pubkey>,
+        equality_proof_account: Pubkey,
+        range_proof_account: Pubkey,
+        zero_balance_proof_account: Pubkey,
         pull_id: u64,
     ) -> Instruction {
-        let buy_pull_accounts = accounts::BuyPull::populate(
+        let claim_pull_accounts = accounts::ClaimPull::populate(
+            game_id,
             buyer,
-            buyer_purchase_account,
-            game_vault,
-            purchase_mint,
+            buyer_reward_account,
+            reward_mint,
+            prize_table,
+            equality_proof_account,
+            range_proof_account,
+            zero_balance_proof_account,
             pull_id,
         )
         .to_account_metas(None);
 
         Instruction {
             program_id: ID,
-            accounts: buy_pull_accounts,
-            data: instruction::BuyPull {
-                params: BuyPullParams { pull_id },
+            accounts: claim_pull_accounts,
+            data: instruction::ClaimPull {
+                params: ClaimPullParams { pull_id },
             }
             .data(),
         }
     }
 }
 
-impl instruction::OpenPull {
+impl instruction::ClaimPullConfidential {
     pub fn populate(
+        game_id: u64,
         buyer: Pubkey,
         buyer_reward_account: Pubkey,
         reward_mint: Pubkey,
         equality_proof_account: Pubkey,
+        ciphertext_validity_proof_account: Pubkey,
         range_proof_account: Pubkey,
-        authority: Pubkey,
         pull_id: u64,
-        amount: u64,
-        decimals: u8,
-        new_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
     ) -> Instruction {
-        let open_pull_accounts = accounts::OpenPull::populate(
+        let claim_pull_confidential_accounts = accounts::ClaimPullConfidential::populate(
+            game_id,
             buyer,
             buyer_reward_account,
             reward_mint,
             equality_proof_account,
+            ciphertext_validity_proof_account,
             range_proof_account,
+            pull_id,
+        )
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: claim_pull_confidential_accounts,
+            data: instruction::ClaimPullConfidential {
+                params: ClaimPullConfidentialParams { pull_id },
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::ClosePull {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        zero_balance_proof_account: Pubkey,
+        published_withdraw_proofs: Option<[Pubkey; 2]>,
+        rent_receiver: Pubkey,
+        pull_id: u64,
+    ) -> Instruction {
+        let close_pull_accounts = accounts::ClosePull::populate(
+            game_id,
             authority,
+            zero_balance_proof_account,
+            published_withdraw_proofs,
+            rent_receiver,
             pull_id,
         )
         .to_account_metas(None);
 
         Instruction {
             program_id: ID,
-            accounts: open_pull_accounts,
-            data: instruction::OpenPull {
-                params: OpenPullParams {
-                    pull_id,
-                    amount,
-                    decimals,
-                    new_decryptable_available_balance,
-                },
+            accounts: close_pull_accounts,
+            data: instruction::ClosePull {
+                params: ClosePullParams { pull_id },
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::RefundPull {
+    pub fn populate(
+        game_id: u64,
+        buyer: Pubkey,
+        buyer_purchase_account: Pubkey,
+        purchase_mint: Pubkey,
+        pull_id: u64,
+    ) -> Instruction {
+        let refund_pull_accounts = accounts::RefundPull::populate(
+            game_id,
+            buyer,
+            buyer_purchase_account,
+            purchase_mint,
+            pull_id,
+        )
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: refund_pull_accounts,
+            data: instruction::RefundPull {
+                params: RefundPullParams { pull_id },
+            }
+            .data(),
+        }
+    }
+
+    pub fn populate_native(
+        game_id: u64,
+        buyer: Pubkey,
+        purchase_mint: Pubkey,
+        pull_id: u64,
+    ) -> Instruction {
+        let refund_pull_accounts =
+            accounts::RefundPull::populate_native(game_id, buyer, purchase_mint, pull_id)
+                .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: refund_pull_accounts,
+            data: instruction::RefundPull {
+                params: RefundPullParams { pull_id },
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::WithdrawProceeds {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        purchase_mint: Pubkey,
+        destination: Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        let withdraw_proceeds_accounts =
+            accounts::WithdrawProceeds::populate(game_id, authority, purchase_mint, destination)
+                .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: withdraw_proceeds_accounts,
+            data: instruction::WithdrawProceeds {
+                params: WithdrawProceedsParams { amount },
+            }
+            .data(),
+        }
+    }
+
+    pub fn populate_native(
+        game_id: u64,
+        authority: Pubkey,
+        purchase_mint: Pubkey,
+        destination: Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        let withdraw_proceeds_accounts = accounts::WithdrawProceeds::populate_native(
+            game_id,
+            authority,
+            purchase_mint,
+            destination,
+        )
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: withdraw_proceeds_accounts,
+            data: instruction::WithdrawProceeds {
+                params: WithdrawProceedsParams { amount },
+            }
+            .data(),
+        }
+    }
+
+    pub fn populate_fee_schedule(
+        game_id: u64,
+        authority: Pubkey,
+        purchase_mint: Pubkey,
+        fee_recipients: &[Pubkey],
+        amount: u64,
+    ) -> Instruction {
+        // Fee recipient token accounts in the fee schedule order
+        let mut withdraw_proceeds_accounts =
+            accounts::WithdrawProceeds::populate_fee_schedule(game_id, authority, purchase_mint)
+                .to_account_metas(None);
+
+        for fee_recipient in fee_recipients {
+            withdraw_proceeds_accounts.push(AccountMeta::new(*fee_recipient, false));
+        }
+
+        Instruction {
+            program_id: ID,
+            accounts: withdraw_proceeds_accounts,
+            data: instruction::WithdrawProceeds {
+                params: WithdrawProceedsParams { amount },
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::ReclaimPull {
+    pub fn populate(
+        game_id: u64,
+        authority: Pubkey,
+        destination: Pubkey,
+        reward_mint: Pubkey,
+        equality_proof_account: Pubkey,
+        range_proof_account: Pubkey,
+        zero_balance_proof_account: Pubkey,
+        pull_id: u64,
+    ) -> Instruction {
+        let reclaim_pull_accounts = accounts::ReclaimPull::populate(
+            game_id,
+            authority,
+            destination,
+            reward_mint,
+            equality_proof_account,
+            range_proof_account,
+            zero_balance_proof_account,
+            pull_id,
+        )
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: reclaim_pull_accounts,
+            data: instruction::ReclaimPull {
+                params: ReclaimPullParams { pull_id },
+            }
+            .data(),
+        }
+    }
+}
+
+impl instruction::PayReferralFee {
+    pub fn populate(
+        game_id: u64,
+        purchase_mint: Pubkey,
+        referrer_purchase_account: Pubkey,
+        pull_id: u64,
+    ) -> Instruction {
+        let pay_referral_fee_accounts = accounts::PayReferralFee::populate(
+            game_id,
+            purchase_mint,
+            referrer_purchase_account,
+            pull_id,
+        )
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: ID,
+            accounts: pay_referral_fee_accounts,
+            data: instruction::PayReferralFee {
+                params: PayReferralFeeParams { pull_id },
             }
             .data(),
         }
     }
 }
+
+/// Off-chain allowlist tooling, left out of the program build
+#[cfg(not(target_os = "solana"))]
+mod allowlist {
+    use anchor_lang::prelude::*;
+
+    use crate::{
+        state::AllowlistProof,
+        utils::merkle::{get_allowlist_leaf, hash_nodes},
+    };
+
+    /// Presale allowlist merkle tree, its root is set as the game config `merkle_root`
+    #[derive(Debug, Clone)]
+    pub struct AllowlistTree {
+        entries: Vec<(Pubkey, u32)>,
+        layers: Vec<Vec<[u8; 32]>>,
+    }
+
+    /// Invalid row of an allowlist CSV, lines are numbered from 1
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum AllowlistCsvError {
+        InvalidWallet(usize),
+        InvalidAllocation(usize),
+    }
+
+    impl std::fmt::Display for AllowlistCsvError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::InvalidWallet(line) => write!(f, "invalid wallet on line {}", line),
+                Self::InvalidAllocation(line) => write!(f, "invalid allocation on line {}", line),
+            }
+        }
+    }
+
+    impl std::error::Error for AllowlistCsvError {}
+
+    impl AllowlistTree {
+        /// Build the tree from wallets and their presale allocation, 0 for no allocation
+        pub fn new(entries: Vec<(Pubkey, u32)>) -> Self {
+            let mut layers = vec![entries
+                .iter()
+                .map(|(wallet, allocation)| get_allowlist_leaf(wallet, *allocation))
+                .collect::<Vec<_>>()];
+
+            while layers.last().unwrap().len() > 1 {
+                let next_layer = layers
+                    .last()
+                    .unwrap()
+                    .chunks(2)
+                    .map(|pair| match pair {
+                        [left, right] => hash_nodes(left, right),
+                        // An odd node is promoted as is
+                        [node] => *node,
+                        _ => unreachable!(),
+                    })
+                    .collect();
+                layers.push(next_layer);
+            }
+
+            Self { entries, layers }
+        }
+
+        /// Build the tree from `wallet[,allocation]` rows. Empty lines, lines starting with `#`
+        /// and a `wallet` header as the first remaining row are skipped.
+        pub fn from_csv(csv: &str) -> std::result::Result<Self, AllowlistCsvError> {
+            let mut entries = Vec::new();
+            let mut first_row = true;
+
+            for (index, line) in csv.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+
+                let mut columns = line.split(',').map(str::trim);
+                let wallet = columns.next().unwrap_or_default();
+                if std::mem::take(&mut first_row) && wallet.eq_ignore_ascii_case("wallet") {
+                    continue;
+                }
+
+                let wallet = wallet
+                    .parse::<Pubkey>()
+                    .map_err(|_| AllowlistCsvError::InvalidWallet(index + 1))?;
+                let allocation = match columns.next() {
+                    Some(allocation) if !allocation.is_empty() => allocation
+                        .parse::<u32>()
+                        .map_err(|_| AllowlistCsvError::InvalidAllocation(index + 1))?,
+                    _ => 0,
+                };
+
+                entries.push((wallet, allocation));
+            }
+
+            Ok(Self::new(entries))
+        }
+
+        /// Merkle root, zeroed for an empty allowlist
+        pub fn root(&self) -> [u8; 32] {
+            self.layers
+                .last()
+                .and_then(|layer| layer.first())
+                .copied()
+                .unwrap_or_default()
+        }
+
+        /// Proof passed to buy_pull by an allowlisted wallet
+        pub fn get_proof(&self, wallet: &Pubkey) -> Option<AllowlistProof> {
+            let position = self.entries.iter().position(|(entry, _)| entry == wallet)?;
+            let mut index = position;
+            let mut proof = Vec::new();
+
+            for layer in &self.layers[..self.layers.len() - 1] {
+                if let Some(sibling) = layer.get(index ^ 1) {
+                    proof.push(*sibling);
+                }
+                index /= 2;
+            }
+
+            Some(AllowlistProof {
+                allocation: self.entries[position].1,
+                proof,
+            })
+        }
+    }
+}
+
+#[cfg(not(target_os = "solana"))]
+pub use allowlist::{AllowlistCsvError, AllowlistTree};
+
+/// Pulls bought by a wallet against the per-wallet limit of a game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalletPurchases {
+    pub bought: u64,
+    /// `None` when the game has no per-wallet limit
+    pub limit: Option<u32>,
+    pub remaining: Option<u64>,
+}
+
+/// Read the purchase counter of a wallet, `player_state` is the account at
+/// `get_player_state_pubkey` and `None` until the wallet buys its first pull
+pub fn get_wallet_purchases(
+    game_config: &GameConfig,
+    player_state: Option<&PlayerState>,
+) -> WalletPurchases {
+    let limit = (game_config.max_pulls_per_wallet > 0).then_some(game_config.max_pulls_per_wallet);
+
+    match player_state {
+        Some(player_state) => WalletPurchases {
+            bought: player_state.pulls_bought,
+            limit,
+            remaining: player_state.remaining_purchases(game_config),
+        },
+        None => WalletPurchases {
+            bought: 0,
+            limit,
+            remaining: limit.map(u64::from),
+        },
+    }
+}
+
+/// Instructions sysvar is passed when any proof is verified in a sibling instruction
+fn get_instructions_sysvar(proof_accounts: &[Option<Pubkey>]) -> Option<Pubkey> {
+    proof_accounts
+        .iter()
+        .any(Option::is_none)
+        .then_some(sysvar::instructions::ID)
+}
+
+/// Proofs without a context state account are expected in the instructions right after,
+/// in the order given
+fn get_proof_instruction_offsets<const N: usize>(proof_accounts: [Option<Pubkey>; N]) -> [i8; N] {
+    let mut next_offset = 0;
+
+    proof_accounts.map(|proof_account| match proof_account {
+        Some(_) => 0,
+        None => {
+            next_offset += 1;
+            next_offset
+        }
+    })
+}
@@ -1,6 +1,15 @@
 use anchor_lang::prelude::*;
 
-#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
 pub struct BuyPullParams {
-    pub pull_id: u64,
+    /// Allowlist proof of the buyer, required during the presale
+    pub allowlist: Option<AllowlistProof>,
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct AllowlistProof {
+    /// Pulls the wallet may buy during the presale, 0 for no allocation
+    pub allocation: u32,
+    /// Sibling nodes from the buyer leaf up to the merkle root
+    pub proof: Vec<[u8; 32]>,
 }
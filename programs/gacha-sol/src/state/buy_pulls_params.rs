@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+use super::AllowlistProof;
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct BuyPullsParams {
+    /// Number of pulls of the bundle to buy
+    pub bundle_size: u8,
+    /// Allowlist proof of the buyer, required during the presale
+    pub allowlist: Option<AllowlistProof>,
+}
@@ -0,0 +1,6 @@
+use anchor_lang::prelude::*;
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct ClaimPullParams {
+    pub pull_id: u64,
+}
@@ -0,0 +1,9 @@
+use anchor_lang::prelude::*;
+
+use super::PrizeTier;
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct CommitPrizeTableParams {
+    /// Tiers indexed by pull tier, at most `MAX_PRIZE_TIERS`
+    pub tiers: Vec<PrizeTier>,
+}
@@ -6,3 +6,21 @@ pub const AE_CIPHERTEXT_MAX_BASE64_LEN: usize = 48;
 
 /// Maximum length of a base64 encoded ElGamal public key
 pub const ELGAMAL_PUBKEY_MAX_BASE64_LEN: usize = 88;
+
+/// Number of prize tiers a pull can belong to
+pub const MAX_PRIZE_TIERS: usize = 8;
+
+/// Number of bundle slots on a game config
+pub const MAX_PULL_BUNDLES: usize = 4;
+
+/// Maximum number of pulls in a bundle, all of them are revealed in one transaction
+pub const MAX_BUNDLE_SIZE: u8 = 10;
+
+/// Maximum number of recipients of a fee schedule
+pub const MAX_FEE_RECIPIENTS: usize = 5;
+
+/// Maximum share of a purchase paid to a referrer, in basis points
+pub const MAX_REFERRAL_BPS: u16 = 2_000;
+
+/// Maximum number of verified pulls waiting in a pull pool
+pub const PULL_POOL_CAPACITY: usize = 1000;
@@ -6,5 +6,9 @@ use super::{AE_CIPHERTEXT_MAX_BASE64_LEN, ELGAMAL_PUBKEY_MAX_BASE64_LEN};
 pub struct CreatePullParams {
     pub pull_id: u64,
     pub encrypted_amount: [u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
+    /// Prize tier, below `MAX_PRIZE_TIERS`
+    pub tier: u8,
     pub decryptable_zero_balance_base64: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+    /// Offset of the pubkey validity proof instruction, zero to read the context state account
+    pub proof_instruction_offset: i8,
 }
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+use super::{AE_CIPHERTEXT_MAX_BASE64_LEN, ELGAMAL_PUBKEY_MAX_BASE64_LEN};
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct NewPull {
+    pub encrypted_amount: [u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
+    /// Prize tier, below `MAX_PRIZE_TIERS`
+    pub tier: u8,
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct CreatePullsParams {
+    /// Id of the first pull, the following pulls take the next ids
+    pub first_pull_id: u64,
+    /// Pulls to create, in pull id order
+    pub pulls: Vec<NewPull>,
+    /// Shared by every reward vault, which are all configured with the same ElGamal pubkey
+    pub decryptable_zero_balance_base64: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+    /// Offset of the pubkey validity proof instruction, zero to read the context state account
+    pub proof_instruction_offset: i8,
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+use crate::error::GachaError;
+
+use super::{Size, MAX_FEE_RECIPIENTS};
+
+/// Split of the proceeds between recipients, paid out by withdraw_proceeds.
+///
+/// Proceeds are split when they are withdrawn rather than at purchase: a purchase stays in
+/// escrow until its pull is claimed and is refunded in full otherwise, so only released
+/// proceeds are ever split. Recipients are fixed by the schedule, the authority only decides
+/// when and how much of the released proceeds are paid out.
+#[account]
+pub struct FeeSchedule {
+    pub game_config: Pubkey,
+    pub recipients: Vec<FeeRecipient>,
+    pub bump: u8,
+}
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct FeeRecipient {
+    /// Purchase token account receiving the share
+    pub token_account: Pubkey,
+    /// Share of the proceeds, in basis points
+    pub bps: u16,
+}
+
+impl Size for FeeSchedule {
+    const SIZE: usize = 8       // discriminator
+        + 32                    // game_config
+        + 4 + (32 + 2) * MAX_FEE_RECIPIENTS // recipients
+        + 1                     // bump
+        ;
+}
+
+impl FeeSchedule {
+    /// Recipients have distinct token accounts and shares summing to 10000 bps
+    pub fn verify_recipients(recipients: &[FeeRecipient]) -> Result<()> {
+        require!(
+            !recipients.is_empty() && recipients.len() <= MAX_FEE_RECIPIENTS,
+            GachaError::InvalidFeeSchedule
+        );
+        require!(
+            recipients.iter().all(|recipient| recipient.bps > 0),
+            GachaError::InvalidFeeSchedule
+        );
+        require!(
+            recipients
+                .iter()
+                .map(|recipient| recipient.bps as u32)
+                .sum::<u32>()
+                == 10_000,
+            GachaError::InvalidFeeSchedule
+        );
+        require!(
+            recipients.iter().enumerate().all(|(index, recipient)| {
+                recipients[..index]
+                    .iter()
+                    .all(|other| other.token_account != recipient.token_account)
+            }),
+            GachaError::InvalidFeeSchedule
+        );
+
+        Ok(())
+    }
+
+    /// Amount of each recipient, the first recipient receives the rounding remainder
+    pub fn split(&self, amount: u64) -> Vec<u64> {
+        let mut amounts: Vec<u64> = self
+            .recipients
+            .iter()
+            .map(|recipient| (amount as u128 * recipient.bps as u128 / 10_000) as u64)
+            .collect();
+
+        let remainder = amount - amounts.iter().sum::<u64>();
+        if let Some(first) = amounts.first_mut() {
+            *first += remainder;
+        }
+
+        amounts
+    }
+}
@@ -1,24 +1,240 @@
 use anchor_lang::prelude::*;
 
-use super::Size;
+use crate::{
+    error::GachaError,
+    utils::merkle::{get_allowlist_leaf, verify_proof},
+};
+
+use super::{AllowlistProof, Size, MAX_PRIZE_TIERS, MAX_PULL_BUNDLES};
 
 #[account]
 pub struct GameConfig {
+    pub game_id: u64,
     pub authority: Pubkey,
+    pub pending_authority: Pubkey,
     pub purchase_mint: Pubkey,
     pub reward_mint: Pubkey,
     pub game_vault: Pubkey,
     pub pull_price: u64,
     pub last_pull_id: u64,
+    pub paused: bool,
+    pub refund_timeout: i64,
+    pub escrowed_amount: u64,
+    pub released_amount: u64,
+    pub withdrawn_amount: u64,
+    /// Referral fees of claimed pulls not yet paid from the game vault
+    pub outstanding_referral_fees: u64,
+    /// Pulls of each prize tier not yet assigned to a buyer
+    pub unsold_pulls_per_tier: [u32; MAX_PRIZE_TIERS],
+    /// Committed prize table, default when the game has none
+    pub prize_table: Pubkey,
+    /// Multi-pull bundles on sale, unused slots have a zero size
+    pub bundles: [PullBundle; MAX_PULL_BUNDLES],
+    /// Pulls without a pity tier pull after which the next one is guaranteed, 0 disables pity
+    pub pity_threshold: u32,
+    /// Prize tier guaranteed once the pity threshold is hit
+    pub pity_tier: u8,
+    /// Unix timestamp pulls go on sale, 0 when there is no start
+    pub sale_start: i64,
+    /// Unix timestamp the sale ends, 0 when there is no end
+    pub sale_end: i64,
+    /// Pulls a single wallet may buy, 0 for no limit
+    pub max_pulls_per_wallet: u32,
+    /// Root of the presale allowlist merkle tree
+    pub merkle_root: [u8; 32],
+    /// Unix timestamp only allowlisted wallets may buy from
+    pub presale_start: i64,
+    /// Unix timestamp the presale ends, 0 when there is no presale
+    pub presale_end: i64,
+    /// Share of a purchase paid to the referrer, in basis points
+    pub referral_bps: u16,
+    /// Fee schedule splitting purchase proceeds, default when the game has none
+    pub fee_schedule: Pubkey,
+    /// Whether pulls are paid with `purchase_mint` tokens or native SOL
+    pub payment_mode: PaymentMode,
+    /// Bump of the treasury holding native SOL purchases
+    pub treasury_bump: u8,
+    pub game_id_bytes: [u8; 8],
+    pub bump: u8,
+}
+
+#[derive(Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct PullBundle {
+    /// Number of pulls in the bundle
+    pub size: u8,
+    /// Price of the whole bundle
+    pub price: u64,
+}
+
+#[derive(Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub enum PaymentMode {
+    /// Purchases are transferred to the game vault in `purchase_mint` tokens
+    #[default]
+    Token,
+    /// Purchases are transferred to the treasury in lamports
+    NativeSol,
 }
 
 impl Size for GameConfig {
     const SIZE: usize = 8       // discriminator
+        + 8                     // game_id
         + 32                    // authority
+        + 32                    // pending_authority
         + 32                    // purchase_mint
         + 32                    // reward_mint
         + 32                    // game_vault
         + 8                    // pull_price
         + 8                    // last_pull_id
+        + 1                     // paused
+        + 8                     // refund_timeout
+        + 8                     // escrowed_amount
+        + 8                     // released_amount
+        + 8                     // withdrawn_amount
+        + 8                     // outstanding_referral_fees
+        + 4 * MAX_PRIZE_TIERS   // unsold_pulls_per_tier
+        + 32                    // prize_table
+        + (1 + 8) * MAX_PULL_BUNDLES // bundles
+        + 4                     // pity_threshold
+        + 1                     // pity_tier
+        + 8                     // sale_start
+        + 8                     // sale_end
+        + 4                     // max_pulls_per_wallet
+        + 32                    // merkle_root
+        + 8                     // presale_start
+        + 8                     // presale_end
+        + 2                     // referral_bps
+        + 32                    // fee_schedule
+        + 1                     // payment_mode
+        + 1                     // treasury_bump
+        + 8                     // game_id_bytes
+        + 1                     // bump
         ;
 }
+
+impl GameConfig {
+    /// Proceeds of opened pulls not yet withdrawn
+    pub fn withdrawable_amount(&self) -> Result<u64> {
+        self.released_amount
+            .checked_sub(self.withdrawn_amount)
+            .ok_or(GachaError::MathOverflow.into())
+    }
+
+    /// Whether pulls have to be checked against a committed prize table
+    pub fn has_prize_table(&self) -> bool {
+        self.prize_table != Pubkey::default()
+    }
+
+    /// Count a newly created pull as unsold in its tier
+    pub fn record_created_pull(&mut self, tier: u8) -> Result<()> {
+        let unsold_pulls = self
+            .unsold_pulls_per_tier
+            .get_mut(tier as usize)
+            .ok_or(GachaError::InvalidPrizeTier)?;
+        *unsold_pulls += 1;
+
+        Ok(())
+    }
+
+    /// Count a pull assigned to a buyer out of the unsold pulls of its tier
+    pub fn record_sold_pull(&mut self, tier: u8) {
+        self.unsold_pulls_per_tier[tier as usize] -= 1;
+    }
+
+    /// Whether pulls of `tier` count for the pity guarantee
+    pub fn is_pity_tier(&self, tier: u8) -> bool {
+        self.pity_threshold > 0 && self.pity_tier == tier
+    }
+
+    /// Whether purchase proceeds are split by a fee schedule
+    pub fn has_fee_schedule(&self) -> bool {
+        self.fee_schedule != Pubkey::default()
+    }
+
+    /// Bundle on sale with the given number of pulls
+    pub fn get_bundle(&self, size: u8) -> Option<&PullBundle> {
+        self.bundles
+            .iter()
+            .find(|bundle| bundle.size > 0 && bundle.size == size)
+    }
+
+    /// Whether `sale_end` is open-ended or after `sale_start`
+    pub fn is_valid_sale_window(sale_start: i64, sale_end: i64) -> bool {
+        sale_start >= 0 && (sale_end == 0 || sale_end > sale_start)
+    }
+
+    /// Fail unless `now` is within the sale window
+    pub fn verify_sale_window(&self, now: i64) -> Result<()> {
+        require!(now >= self.sale_start, GachaError::SaleNotStarted);
+        require!(
+            self.sale_end == 0 || now < self.sale_end,
+            GachaError::SaleEnded
+        );
+
+        Ok(())
+    }
+
+    /// Whether `presale_end` disables the presale or is after `presale_start`
+    pub fn is_valid_presale_window(presale_start: i64, presale_end: i64) -> bool {
+        presale_end == 0 || (presale_start >= 0 && presale_end > presale_start)
+    }
+
+    /// Whether only allowlisted wallets may buy at `now`
+    pub fn is_presale(&self, now: i64) -> bool {
+        self.presale_end > 0 && now >= self.presale_start && now < self.presale_end
+    }
+
+    /// Fail unless the buyer may buy at `now`. During the presale the buyer has to be in the
+    /// allowlist, and `pulls_bought` after the purchase within its allocation.
+    pub fn verify_purchase(
+        &self,
+        now: i64,
+        buyer: &Pubkey,
+        allowlist: Option<&AllowlistProof>,
+        pulls_bought: u64,
+    ) -> Result<()> {
+        if !self.is_presale(now) {
+            return self.verify_sale_window(now);
+        }
+
+        let allowlist = allowlist.ok_or(GachaError::NotAllowlisted)?;
+        require!(
+            verify_proof(
+                &self.merkle_root,
+                get_allowlist_leaf(buyer, allowlist.allocation),
+                &allowlist.proof
+            ),
+            GachaError::NotAllowlisted
+        );
+        require!(
+            allowlist.allocation == 0 || pulls_bought <= allowlist.allocation as u64,
+            GachaError::PresaleAllocationExceeded
+        );
+
+        Ok(())
+    }
+
+    /// Share of `amount` paid to a referrer
+    pub fn get_referral_fee(&self, amount: u64) -> u64 {
+        (amount as u128 * self.referral_bps as u128 / 10_000) as u64
+    }
+
+    /// Signer seeds of the treasury, `game_config` being the key of this account
+    pub fn get_treasury_signer_seeds<'a, 'b: 'a>(
+        &'b self,
+        game_config: &'b Pubkey,
+    ) -> [&'a [u8]; 3] {
+        [
+            b"treasury",
+            game_config.as_ref(),
+            std::slice::from_ref(&self.treasury_bump),
+        ]
+    }
+
+    pub fn get_signer_seeds<'a, 'b: 'a>(&'b self) -> [&'a [u8]; 3] {
+        [
+            b"game_config",
+            &self.game_id_bytes,
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
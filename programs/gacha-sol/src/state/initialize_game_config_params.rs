@@ -1,6 +1,17 @@
 use anchor_lang::prelude::*;
 
+use super::PaymentMode;
+
 #[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
 pub struct InitializeGameConfigParams {
+    pub game_id: u64,
     pub pull_price: u64,
+    /// Seconds after purchase before a buyer may refund an unopened pull
+    pub refund_timeout: i64,
+    /// Unix timestamp pulls go on sale, 0 to start right away
+    pub sale_start: i64,
+    /// Unix timestamp the sale ends, 0 for no end
+    pub sale_end: i64,
+    /// Whether pulls are paid with `purchase_mint` tokens or native SOL
+    pub payment_mode: PaymentMode,
 }
@@ -1,21 +1,63 @@
 mod apply_pull_pending_balance_params;
 mod buy_pull_params;
+mod buy_pulls_params;
+mod cancel_ticket_params;
+mod claim_pull_confidential_params;
+mod claim_pull_params;
+mod close_pull_params;
+mod commit_prize_table_params;
 mod constants;
 mod create_pull_params;
+mod create_pulls_params;
+mod fee_schedule;
 mod game_config;
 mod initialize_game_config_params;
 mod open_pull_params;
+mod pay_referral_fee_params;
+mod player_state;
+mod prize_table;
+mod publish_withdraw_proofs_params;
 mod pull;
+mod pull_pool;
+mod pull_ticket;
+mod reclaim_pull_params;
+mod refund_pull_params;
+mod reveal_pull_params;
+mod set_fee_schedule_params;
 mod traits;
+mod update_game_config_params;
 mod verify_pull_params;
+mod verify_pull_transfer_params;
+mod withdraw_proceeds_params;
 
 pub use apply_pull_pending_balance_params::*;
 pub use buy_pull_params::*;
+pub use buy_pulls_params::*;
+pub use cancel_ticket_params::*;
+pub use claim_pull_confidential_params::*;
+pub use claim_pull_params::*;
+pub use close_pull_params::*;
+pub use commit_prize_table_params::*;
 pub use constants::*;
 pub use create_pull_params::*;
+pub use create_pulls_params::*;
+pub use fee_schedule::*;
 pub use game_config::*;
 pub use initialize_game_config_params::*;
 pub use open_pull_params::*;
+pub use pay_referral_fee_params::*;
+pub use player_state::*;
+pub use prize_table::*;
+pub use publish_withdraw_proofs_params::*;
 pub use pull::*;
+pub use pull_pool::*;
+pub use pull_ticket::*;
+pub use reclaim_pull_params::*;
+pub use refund_pull_params::*;
+pub use reveal_pull_params::*;
+pub use set_fee_schedule_params::*;
 pub use traits::*;
+pub use update_game_config_params::*;
 pub use verify_pull_params::*;
+pub use verify_pull_transfer_params::*;
+pub use withdraw_proceeds_params::*;
@@ -8,4 +8,8 @@ pub struct OpenPullParams {
     pub amount: u64,
     pub decimals: u8,
     pub new_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+    /// Proof instruction offsets, zero to read the context state account
+    pub equality_proof_instruction_offset: i8,
+    pub range_proof_instruction_offset: i8,
+    pub zero_balance_proof_instruction_offset: i8,
 }
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+use crate::error::GachaError;
+
+use super::{GameConfig, Size};
+
+/// Pull history of a buyer in a game, drives the pity guarantee
+#[account]
+pub struct PlayerState {
+    pub game_config: Pubkey,
+    pub buyer: Pubkey,
+    pub pulls_bought: u64,
+    pub pulls_opened: u64,
+    /// Pulls assigned since the last one from the pity tier
+    pub pulls_since_rare: u32,
+    pub bump: u8,
+}
+
+impl Size for PlayerState {
+    const SIZE: usize = 8       // discriminator
+        + 32                    // game_config
+        + 32                    // buyer
+        + 8                     // pulls_bought
+        + 8                     // pulls_opened
+        + 4                     // pulls_since_rare
+        + 1                     // bump
+        ;
+}
+
+impl PlayerState {
+    /// Pulls the buyer may still buy, `None` when the game has no per-wallet limit
+    pub fn remaining_purchases(&self, game_config: &GameConfig) -> Option<u64> {
+        (game_config.max_pulls_per_wallet > 0)
+            .then(|| (game_config.max_pulls_per_wallet as u64).saturating_sub(self.pulls_bought))
+    }
+
+    /// Count bought pulls against the per-wallet limit
+    pub fn record_purchase(&mut self, pull_count: u64, game_config: &GameConfig) -> Result<()> {
+        if let Some(remaining) = self.remaining_purchases(game_config) {
+            require!(pull_count <= remaining, GachaError::PurchaseLimitExceeded);
+        }
+
+        self.pulls_bought += pull_count;
+
+        Ok(())
+    }
+
+    /// Whether the next assigned pull has to come from the pity tier
+    pub fn pity_guaranteed(&self, game_config: &GameConfig) -> bool {
+        game_config.pity_threshold > 0 && self.pulls_since_rare >= game_config.pity_threshold
+    }
+
+    /// Draws among the next `draws` that fall back on the pity guarantee when no other draw
+    /// hits the pity tier, the most pity tier pulls these draws can require
+    pub fn guaranteed_pity_draws(&self, game_config: &GameConfig, draws: u8) -> u64 {
+        if game_config.pity_threshold == 0 {
+            return 0;
+        }
+
+        let mut pulls_since_rare = self.pulls_since_rare;
+        let mut guaranteed_draws = 0;
+        for _ in 0..draws {
+            match pulls_since_rare >= game_config.pity_threshold {
+                true => {
+                    guaranteed_draws += 1;
+                    pulls_since_rare = 0;
+                }
+                false => pulls_since_rare = pulls_since_rare.saturating_add(1),
+            }
+        }
+
+        guaranteed_draws
+    }
+
+    /// Count an assigned pull towards the pity threshold
+    pub fn record_pull(&mut self, pity: bool) {
+        self.pulls_since_rare = match pity {
+            true => 0,
+            false => self.pulls_since_rare.saturating_add(1),
+        };
+    }
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::error::GachaError;
+
+use super::{Size, MAX_PRIZE_TIERS};
+
+/// Drop rates of a game, committed by the authority before any pull is created
+#[account]
+pub struct PrizeTable {
+    pub game_config: Pubkey,
+    pub tiers: Vec<PrizeTier>,
+    /// Pulls created so far in each tier
+    pub created_per_tier: [u32; MAX_PRIZE_TIERS],
+    pub bump: u8,
+}
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct PrizeTier {
+    /// Number of pulls in the tier
+    pub count: u32,
+    /// Smallest reward of a pull in the tier
+    pub min_amount: u64,
+    /// Largest reward of a pull in the tier
+    pub max_amount: u64,
+}
+
+impl Size for PrizeTable {
+    const SIZE: usize = 8       // discriminator
+        + 32                    // game_config
+        + 4 + (4 + 8 + 8) * MAX_PRIZE_TIERS // tiers
+        + 4 * MAX_PRIZE_TIERS   // created_per_tier
+        + 1                     // bump
+        ;
+}
+
+impl PrizeTable {
+    /// Count a newly created pull against the count of its tier
+    pub fn record_pull(&mut self, tier: u8) -> Result<()> {
+        let prize_tier = self
+            .tiers
+            .get(tier as usize)
+            .ok_or(GachaError::InvalidPrizeTier)?;
+        let created = &mut self.created_per_tier[tier as usize];
+        require!(*created < prize_tier.count, GachaError::PrizeTierFull);
+        *created += 1;
+
+        Ok(())
+    }
+
+    /// Smallest and largest reward of a pull in `tier`
+    pub fn amount_range(&self, tier: u8) -> Result<(u64, u64)> {
+        let prize_tier = self
+            .tiers
+            .get(tier as usize)
+            .ok_or(GachaError::InvalidPrizeTier)?;
+
+        Ok((prize_tier.min_amount, prize_tier.max_amount))
+    }
+
+    /// Check a revealed reward against the range of its tier
+    pub fn verify_amount(&self, tier: u8, amount: u64) -> Result<()> {
+        let (min_amount, max_amount) = self.amount_range(tier)?;
+
+        require!(
+            (min_amount..=max_amount).contains(&amount),
+            GachaError::RewardOutOfPrizeRange
+        );
+
+        Ok(())
+    }
+}
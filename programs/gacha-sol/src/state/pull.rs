@@ -1,16 +1,42 @@
 use anchor_lang::prelude::*;
 
-use super::{Size, ELGAMAL_PUBKEY_MAX_BASE64_LEN};
+use super::{Size, AE_CIPHERTEXT_MAX_BASE64_LEN, ELGAMAL_PUBKEY_MAX_BASE64_LEN};
 
 #[account]
 pub struct Pull {
     pub id: u64,
+    pub game_config: Pubkey,
     pub reward_vault: Pubkey,
     pub encrypted_amount: [u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
+    /// Prize tier, public from creation so the unsold count of each tier can be tracked
+    pub tier: u8,
     pub buyer: Pubkey,
     pub verified: bool,
     pub claimed: bool,
     pub revealed_amount: u64,
+    pub purchase_price: u64,
+    /// Owner of the referrer token account, default without a referrer
+    pub referrer: Pubkey,
+    /// Part of `purchase_price` owed to the referrer once the pull is claimed
+    pub referral_fee: u64,
+    pub referral_fee_paid: bool,
+    pub purchased_at: i64,
+    pub refunded: bool,
+    /// Reward of a refunded pull withdrawn back by the authority
+    pub reclaimed: bool,
+    pub withdraw_proofs_published: bool,
+    pub withdraw_amount: u64,
+    pub withdraw_equality_proof: Pubkey,
+    pub withdraw_range_proof: Pubkey,
+    pub withdraw_zero_balance_proof: Pubkey,
+    pub withdraw_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+    pub transfer_proofs_verified: bool,
+    pub transfer_amount_auditor_ciphertext_lo: [u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
+    pub transfer_amount_auditor_ciphertext_hi: [u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
+    pub transfer_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+    pub transfer_equality_proof: Pubkey,
+    pub transfer_ciphertext_validity_proof: Pubkey,
+    pub transfer_range_proof: Pubkey,
     pub pull_id_bytes: [u8; 8],
     pub bump: u8,
 }
@@ -18,21 +44,98 @@ pub struct Pull {
 impl Size for Pull {
     const SIZE: usize = 8       // discriminator
         + 8                     // id
+        + 32                    // game_config
         + 32                    // reward_vault
         + ELGAMAL_PUBKEY_MAX_BASE64_LEN                    // encrypted_amount
+        + 1                     // tier
         + 32                    // buyer
         + 1                     // verified
         + 1                     // claimed
         + 8                     // revealed_amount
+        + 8                     // purchase_price
+        + 32                    // referrer
+        + 8                     // referral_fee
+        + 1                     // referral_fee_paid
+        + 8                     // purchased_at
+        + 1                     // refunded
+        + 1                     // reclaimed
+        + 1                     // withdraw_proofs_published
+        + 8                     // withdraw_amount
+        + 32                    // withdraw_equality_proof
+        + 32                    // withdraw_range_proof
+        + 32                    // withdraw_zero_balance_proof
+        + AE_CIPHERTEXT_MAX_BASE64_LEN                    // withdraw_decryptable_available_balance
+        + 1                     // transfer_proofs_verified
+        + ELGAMAL_PUBKEY_MAX_BASE64_LEN                    // transfer_amount_auditor_ciphertext_lo
+        + ELGAMAL_PUBKEY_MAX_BASE64_LEN                    // transfer_amount_auditor_ciphertext_hi
+        + AE_CIPHERTEXT_MAX_BASE64_LEN                    // transfer_decryptable_available_balance
+        + 32                    // transfer_equality_proof
+        + 32                    // transfer_ciphertext_validity_proof
+        + 32                    // transfer_range_proof
         + 8                     // pull_id_bytes
         + 1                     // bump
         ;
 }
 
 impl Pull {
-    pub fn get_signer_seeds<'a, 'b: 'a>(&'b self) -> [&'a [u8]; 3] {
+    /// A freshly created pull, waiting for its reward vault to be funded and verified
+    pub fn new(
+        id: u64,
+        game_config: Pubkey,
+        reward_vault: Pubkey,
+        encrypted_amount: [u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
+        tier: u8,
+        bump: u8,
+    ) -> Self {
+        Self {
+            id,
+            game_config,
+            reward_vault,
+            encrypted_amount,
+            tier,
+            buyer: Pubkey::default(),
+            verified: false,
+            claimed: false,
+            revealed_amount: 0,
+            purchase_price: 0,
+            referrer: Pubkey::default(),
+            referral_fee: 0,
+            referral_fee_paid: false,
+            purchased_at: 0,
+            refunded: false,
+            reclaimed: false,
+            withdraw_proofs_published: false,
+            withdraw_amount: 0,
+            withdraw_equality_proof: Pubkey::default(),
+            withdraw_range_proof: Pubkey::default(),
+            withdraw_zero_balance_proof: Pubkey::default(),
+            withdraw_decryptable_available_balance: [0; AE_CIPHERTEXT_MAX_BASE64_LEN],
+            transfer_proofs_verified: false,
+            transfer_amount_auditor_ciphertext_lo: [0; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
+            transfer_amount_auditor_ciphertext_hi: [0; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
+            transfer_decryptable_available_balance: [0; AE_CIPHERTEXT_MAX_BASE64_LEN],
+            transfer_equality_proof: Pubkey::default(),
+            transfer_ciphertext_validity_proof: Pubkey::default(),
+            transfer_range_proof: Pubkey::default(),
+            pull_id_bytes: id.to_le_bytes(),
+            bump,
+        }
+    }
+
+    /// Part of `purchase_price` released for withdrawal once the pull is claimed
+    pub fn released_price(&self) -> u64 {
+        self.purchase_price - self.referral_fee
+    }
+
+    /// Referral fee of a claimed pull still waiting to be paid
+    pub fn has_unpaid_referral_fee(&self) -> bool {
+        self.referral_fee > 0 && !self.referral_fee_paid
+    }
+
+    pub fn get_signer_seeds<'a, 'b: 'a>(&'b self) -> [&'a [u8]; 4] {
         [
             b"pull",
+            self.game_config.as_ref(),
             &self.pull_id_bytes,
             std::slice::from_ref(&self.bump),
         ]
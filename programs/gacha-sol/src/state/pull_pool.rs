@@ -0,0 +1,118 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+use super::{Size, PULL_POOL_CAPACITY};
+
+/// Verified pulls that have not been assigned to a buyer yet
+#[account]
+pub struct PullPool {
+    pub game_config: Pubkey,
+    pub pending_tickets: u64,
+    /// Pity tier pulls held back for the guaranteed draws of pending tickets
+    pub pending_pity_draws: u64,
+    pub pull_ids: Vec<u64>,
+    /// Whether each pull in `pull_ids` belongs to the pity tier
+    pub pull_pity: Vec<bool>,
+    pub bump: u8,
+}
+
+impl Size for PullPool {
+    const SIZE: usize = 8       // discriminator
+        + 32                    // game_config
+        + 8                     // pending_tickets
+        + 8                     // pending_pity_draws
+        + 4 + 8 * PULL_POOL_CAPACITY // pull_ids
+        + 4 + PULL_POOL_CAPACITY // pull_pity
+        + 1                     // bump
+        ;
+}
+
+impl PullPool {
+    /// Number of pulls that can still be reserved by a new ticket
+    pub fn available(&self) -> u64 {
+        (self.pull_ids.len() as u64).saturating_sub(self.pending_tickets)
+    }
+
+    /// Number of pity tier pulls that can still be reserved by a new ticket
+    pub fn available_pity(&self) -> u64 {
+        self.pity_count().saturating_sub(self.pending_pity_draws)
+    }
+
+    /// Number of pity tier pulls in the pool
+    pub fn pity_count(&self) -> u64 {
+        self.pull_pity.iter().filter(|pity| **pity).count() as u64
+    }
+
+    /// Add a verified pull to the pool
+    pub fn add_pull(&mut self, pull_id: u64, pity: bool) {
+        self.pull_ids.push(pull_id);
+        self.pull_pity.push(pity);
+    }
+
+    /// Index into `pull_ids` picked by the given randomness
+    pub fn select_index(&self, randomness: &[u8; 32]) -> Option<usize> {
+        if self.pull_ids.is_empty() {
+            return None;
+        }
+
+        Some((Self::seed(randomness) % self.pull_ids.len() as u64) as usize)
+    }
+
+    /// Index into `pull_ids` picked by the given randomness among the pulls with the given
+    /// pity flag
+    pub fn select_index_by_pity(&self, randomness: &[u8; 32], pity: bool) -> Option<usize> {
+        // Count the candidates first, then scan for the picked one, without allocating
+        let candidate_count = self
+            .pull_pity
+            .iter()
+            .filter(|pull_pity| **pull_pity == pity)
+            .count();
+
+        if candidate_count == 0 {
+            return None;
+        }
+
+        let nth = (Self::seed(randomness) % candidate_count as u64) as usize;
+        self.pull_pity
+            .iter()
+            .enumerate()
+            .filter(|(_, pull_pity)| **pull_pity == pity)
+            .nth(nth)
+            .map(|(index, _)| index)
+    }
+
+    /// Remove and return the pull id and pity flag picked by the `draw`-th draw of a ticket.
+    /// A guaranteed draw only picks a pity tier pull, any other draw leaves the
+    /// `pity_reserved` pity tier pulls in the pool.
+    pub fn take_pull(
+        &mut self,
+        randomness: &[u8; 32],
+        draw: u8,
+        pity_guaranteed: bool,
+        pity_reserved: u64,
+    ) -> Option<(u64, bool)> {
+        let randomness = match draw {
+            0 => *randomness,
+            _ => hashv(&[randomness, &[draw]]).to_bytes(),
+        };
+        // Any pull can be drawn unless every pity tier pull in the pool is reserved
+        let pity_count = self.pity_count();
+        let any_pull = pity_count == 0 || pity_count > pity_reserved;
+        let index = match (pity_guaranteed, any_pull) {
+            (true, _) => self.select_index_by_pity(&randomness, true)?,
+            (false, true) => self.select_index(&randomness)?,
+            (false, false) => self.select_index_by_pity(&randomness, false)?,
+        };
+
+        Some((
+            self.pull_ids.swap_remove(index),
+            self.pull_pity.swap_remove(index),
+        ))
+    }
+
+    fn seed(randomness: &[u8; 32]) -> u64 {
+        let mut seed = [0u8; 8];
+        seed.copy_from_slice(&randomness[..8]);
+
+        u64::from_le_bytes(seed)
+    }
+}
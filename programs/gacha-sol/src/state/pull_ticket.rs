@@ -0,0 +1,46 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+use super::Size;
+
+/// A paid purchase waiting for its pull to be randomly assigned
+#[account]
+pub struct PullTicket {
+    pub game_config: Pubkey,
+    pub buyer: Pubkey,
+    pub commit_slot: u64,
+    /// Price paid for all the pulls of the ticket
+    pub purchase_price: u64,
+    pub purchased_at: i64,
+    /// Number of pulls assigned by reveal_pull, more than one for bundles
+    pub pull_count: u8,
+    /// Pity tier pulls reserved in the pool for the guaranteed draws of the ticket
+    pub pity_draws: u8,
+    /// Owner of the referrer token account, default without a referrer
+    pub referrer: Pubkey,
+    /// Part of `purchase_price` paid to the referrer once the pull is claimed,
+    /// the whole price is escrowed so a refund returns all of it
+    pub referral_fee: u64,
+    pub bump: u8,
+}
+
+impl Size for PullTicket {
+    const SIZE: usize = 8       // discriminator
+        + 32                    // game_config
+        + 32                    // buyer
+        + 8                     // commit_slot
+        + 8                     // purchase_price
+        + 8                     // purchased_at
+        + 1                     // pull_count
+        + 1                     // pity_draws
+        + 32                    // referrer
+        + 8                     // referral_fee
+        + 1                     // bump
+        ;
+}
+
+impl PullTicket {
+    /// Mix the hash of the commit slot, which is unknown when buying, with the ticket
+    pub fn compute_randomness(ticket: &Pubkey, slot_hash: &[u8; 32]) -> [u8; 32] {
+        hashv(&[slot_hash, ticket.as_ref()]).to_bytes()
+    }
+}
@@ -0,0 +1,9 @@
+use anchor_lang::prelude::*;
+
+use super::FeeRecipient;
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct SetFeeScheduleParams {
+    /// At most `MAX_FEE_RECIPIENTS`, with shares summing to 10000 bps
+    pub recipients: Vec<FeeRecipient>,
+}
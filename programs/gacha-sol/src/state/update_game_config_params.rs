@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use super::{PullBundle, MAX_PULL_BUNDLES};
+
+#[derive(Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct UpdateGameConfigParams {
+    pub pull_price: Option<u64>,
+    pub refund_timeout: Option<i64>,
+    /// Replaces every bundle slot
+    pub bundles: Option<[PullBundle; MAX_PULL_BUNDLES]>,
+    /// 0 disables the pity guarantee
+    pub pity_threshold: Option<u32>,
+    pub pity_tier: Option<u8>,
+    /// 0 to start right away
+    pub sale_start: Option<i64>,
+    /// 0 for no end
+    pub sale_end: Option<i64>,
+    /// 0 removes the limit
+    pub max_pulls_per_wallet: Option<u32>,
+    /// Root of the presale allowlist merkle tree
+    pub merkle_root: Option<[u8; 32]>,
+    pub presale_start: Option<i64>,
+    /// 0 disables the presale
+    pub presale_end: Option<i64>,
+    /// Capped at MAX_REFERRAL_BPS
+    pub referral_bps: Option<u16>,
+}
@@ -0,0 +1,29 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, program::invoke_signed},
+};
+
+use crate::error::GachaError;
+
+/// Invoke `instruction`, looking up each of its account metas in `account_infos`
+pub fn invoke_signed_with_accounts<'info>(
+    instruction: &Instruction,
+    account_infos: &[&AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let accounts = instruction
+        .accounts
+        .iter()
+        .map(|meta| {
+            account_infos
+                .iter()
+                .find(|account_info| *account_info.key == meta.pubkey)
+                .map(|account_info| (*account_info).clone())
+                .ok_or_else(|| GachaError::InvalidAccount.into())
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    invoke_signed(instruction, &accounts, signer_seeds)?;
+
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+/// Leaf of the presale allowlist for a wallet and its allocation
+pub fn get_allowlist_leaf(wallet: &Pubkey, allocation: u32) -> [u8; 32] {
+    hashv(&[&[0], wallet.as_ref(), &allocation.to_le_bytes()]).to_bytes()
+}
+
+/// Parent of two nodes, sorted so proofs do not depend on the leaf position
+pub fn hash_nodes(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+
+    hashv(&[&[1], left, right]).to_bytes()
+}
+
+/// Whether `proof` links `leaf` to `root`
+pub fn verify_proof(root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_nodes(&node, sibling))
+        == *root
+}
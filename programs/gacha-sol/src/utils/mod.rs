@@ -1,3 +1,9 @@
+pub mod account;
+pub mod cpi;
+pub mod merkle;
+pub mod payment;
 pub mod rent;
+pub mod reward_vault;
+pub mod slot_hashes;
 pub mod token_2022;
 pub mod zk_elgamal_proof_program;
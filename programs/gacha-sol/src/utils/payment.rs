@@ -0,0 +1,107 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::{self, Transfer};
+
+use crate::{
+    error::GachaError,
+    state::{GameConfig, PaymentMode},
+};
+
+/// Accounts moving a purchase between the buyer and the escrow of the game,
+/// the game vault for token games and the treasury for native SOL games
+pub struct PaymentAccounts<'a, 'info> {
+    pub game_config: &'a Account<'info, GameConfig>,
+    pub buyer: &'a AccountInfo<'info>,
+    pub buyer_purchase_account: Option<&'a AccountInfo<'info>>,
+    pub game_vault: &'a AccountInfo<'info>,
+    pub treasury: Option<&'a AccountInfo<'info>>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl<'info> PaymentAccounts<'_, 'info> {
+    /// Token games are paid from the buyer token account, native SOL games from the buyer wallet
+    pub fn verify(&self) -> Result<()> {
+        let valid = match self.game_config.payment_mode {
+            PaymentMode::Token => self.buyer_purchase_account.is_some() && self.treasury.is_none(),
+            PaymentMode::NativeSol => {
+                self.buyer_purchase_account.is_none() && self.treasury.is_some()
+            }
+        };
+        require!(valid, GachaError::InvalidPaymentAccounts);
+
+        Ok(())
+    }
+
+    /// Move `amount` from the buyer into escrow
+    pub fn pay(&self, amount: u64) -> Result<()> {
+        match self.game_config.payment_mode {
+            PaymentMode::Token => {
+                let buyer_purchase_account = self
+                    .buyer_purchase_account
+                    .ok_or(GachaError::InvalidPaymentAccounts)?;
+
+                let cpi_accounts = Transfer {
+                    from: buyer_purchase_account.clone(),
+                    to: self.game_vault.clone(),
+                    authority: self.buyer.clone(),
+                };
+                let cpi_context = CpiContext::new(self.token_program.clone(), cpi_accounts);
+
+                token::transfer(cpi_context, amount)
+            }
+            PaymentMode::NativeSol => {
+                let treasury = self.treasury.ok_or(GachaError::InvalidPaymentAccounts)?;
+
+                let cpi_accounts = system_program::Transfer {
+                    from: self.buyer.clone(),
+                    to: treasury.clone(),
+                };
+                let cpi_context = CpiContext::new(self.system_program.clone(), cpi_accounts);
+
+                system_program::transfer(cpi_context, amount)
+            }
+        }
+    }
+
+    /// Return `amount` from escrow to the buyer
+    pub fn refund(&self, amount: u64) -> Result<()> {
+        let game_config = self.game_config;
+
+        match game_config.payment_mode {
+            PaymentMode::Token => {
+                let buyer_purchase_account = self
+                    .buyer_purchase_account
+                    .ok_or(GachaError::InvalidPaymentAccounts)?;
+
+                let signer_seeds = &game_config.get_signer_seeds();
+                let signer = &[&signer_seeds[..]];
+
+                let cpi_accounts = Transfer {
+                    from: self.game_vault.clone(),
+                    to: buyer_purchase_account.clone(),
+                    authority: game_config.to_account_info(),
+                };
+                let cpi_context =
+                    CpiContext::new_with_signer(self.token_program.clone(), cpi_accounts, signer);
+
+                token::transfer(cpi_context, amount)
+            }
+            PaymentMode::NativeSol => {
+                let treasury = self.treasury.ok_or(GachaError::InvalidPaymentAccounts)?;
+
+                let game_config_key = game_config.key();
+                let signer_seeds = &game_config.get_treasury_signer_seeds(&game_config_key);
+                let signer = &[&signer_seeds[..]];
+
+                let cpi_accounts = system_program::Transfer {
+                    from: treasury.clone(),
+                    to: self.buyer.clone(),
+                };
+                let cpi_context =
+                    CpiContext::new_with_signer(self.system_program.clone(), cpi_accounts, signer);
+
+                system_program::transfer(cpi_context, amount)
+            }
+        }
+    }
+}
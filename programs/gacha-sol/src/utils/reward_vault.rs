@@ -0,0 +1,227 @@
+use std::str::FromStr as _;
+
+use anchor_lang::{prelude::*, solana_program::program::invoke};
+use anchor_spl::token_2022::{self, TransferChecked};
+use spl_token_2022::{
+    extension::{
+        confidential_transfer::instruction::{
+            inner_configure_account, withdraw, PubkeyValidityProofData,
+        },
+        ExtensionType,
+    },
+    instruction::initialize_account3,
+    solana_zk_sdk::encryption::pod::auth_encryption::PodAeCiphertext,
+    state::Account as TokenAccount,
+};
+use spl_token_confidential_transfer_proof_extraction::instruction::ProofLocation;
+
+use crate::{
+    error::GachaError,
+    state::{Pull, AE_CIPHERTEXT_MAX_BASE64_LEN},
+    utils::{
+        account::create_pda_account, cpi::invoke_signed_with_accounts,
+        zk_elgamal_proof_program::get_proof_location,
+    },
+};
+
+/// Accounts shared by the instructions creating pull reward vaults
+pub struct RewardVaultAccounts<'a, 'info> {
+    pub reward_mint: &'a AccountInfo<'info>,
+    pub payer: &'a AccountInfo<'info>,
+    pub pubkey_validity_proof_account: Option<&'a AccountInfo<'info>>,
+    pub instructions_sysvar: Option<&'a AccountInfo<'info>>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub rent: &'a Rent,
+}
+
+impl<'info> RewardVaultAccounts<'_, 'info> {
+    /// Create the reward vault PDA of a pull as a Token-2022 account owned by the pull,
+    /// `reward_vault_bump` being the bump of the vault address
+    pub fn create_reward_vault(
+        &self,
+        pull_account: &AccountInfo<'info>,
+        reward_vault: &AccountInfo<'info>,
+        reward_vault_bump: u8,
+    ) -> Result<()> {
+        let required_space = ExtensionType::try_calculate_account_len::<TokenAccount>(&[
+            ExtensionType::ConfidentialTransferAccount,
+        ])?;
+
+        let pull_pubkey = pull_account.key();
+        let reward_vault_pubkey = reward_vault.key();
+        let token_program_id = self.token_program.key();
+
+        let reward_vault_seeds = &[b"reward_vault", pull_pubkey.as_ref(), &[reward_vault_bump]];
+
+        create_pda_account(
+            self.payer,
+            reward_vault,
+            self.system_program,
+            self.rent,
+            required_space,
+            &token_program_id,
+            &[&reward_vault_seeds[..]],
+        )?;
+
+        let init_account_ix = initialize_account3(
+            &token_program_id,
+            &reward_vault_pubkey,
+            self.reward_mint.key,
+            &pull_pubkey,
+        )?;
+
+        invoke(
+            &init_account_ix,
+            &[
+                reward_vault.clone(),
+                self.reward_mint.clone(),
+                pull_account.clone(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Configure the reward vault for confidential transfers, signed by the pull
+    pub fn configure_reward_vault(
+        &self,
+        pull: &Pull,
+        pull_account: &AccountInfo<'info>,
+        reward_vault: &AccountInfo<'info>,
+        decryptable_zero_balance_base64: &[u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+        proof_data: Option<&PubkeyValidityProofData>,
+        proof_instruction_offset: i8,
+    ) -> Result<()> {
+        let signer_seeds = &pull.get_signer_seeds();
+        let signer = &[&signer_seeds[..]];
+
+        let decryptable_zero_balance = PodAeCiphertext::from_str(
+            std::str::from_utf8(decryptable_zero_balance_base64)
+                .map_err(|_| GachaError::DecryptableBalanceConversionFailed)?,
+        )
+        .map_err(|_| GachaError::DecryptableBalanceConversionFailed)?;
+
+        let proof_data_location = get_proof_location(
+            self.pubkey_validity_proof_account,
+            proof_data,
+            proof_instruction_offset,
+        )?;
+
+        let configure_account_ix = inner_configure_account(
+            self.token_program.key,
+            &reward_vault.key(),
+            self.reward_mint.key,
+            &decryptable_zero_balance,
+            65536, // maximum_pending_balance_credit_counter
+            &pull_account.key(),
+            &[],
+            proof_data_location,
+        )?;
+
+        let account_infos = [
+            Some(reward_vault),
+            Some(self.reward_mint),
+            Some(pull_account),
+            self.pubkey_validity_proof_account,
+            self.instructions_sysvar,
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        invoke_signed_with_accounts(&configure_account_ix, &account_infos, signer)
+            .map_err(|_| GachaError::ConfigureTokenAccountFailed)?;
+
+        Ok(())
+    }
+}
+
+/// Accounts moving the reward out of a pull reward vault with the withdraw proofs
+/// published for the pull
+pub struct RewardWithdrawAccounts<'a, 'info> {
+    pub pull: &'a Account<'info, Pull>,
+    pub reward_vault: &'a AccountInfo<'info>,
+    pub reward_mint: &'a AccountInfo<'info>,
+    pub equality_proof_account: &'a AccountInfo<'info>,
+    pub range_proof_account: &'a AccountInfo<'info>,
+    pub zk_elgamal_proof_program: &'a AccountInfo<'info>,
+    pub token_2022_program: &'a AccountInfo<'info>,
+}
+
+impl<'info> RewardWithdrawAccounts<'_, 'info> {
+    /// Withdraw `amount` from the confidential available balance of the reward vault
+    pub fn withdraw_reward(
+        &self,
+        amount: u64,
+        decimals: u8,
+        new_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+    ) -> Result<()> {
+        let signer_seeds = &self.pull.get_signer_seeds();
+        let signer = &[&signer_seeds[..]];
+
+        let new_decryptable_available_balance = PodAeCiphertext::from_str(
+            std::str::from_utf8(&new_decryptable_available_balance)
+                .map_err(|_| GachaError::DecryptableBalanceConversionFailed)?,
+        )
+        .map_err(|_| GachaError::DecryptableBalanceConversionFailed)?;
+
+        let equality_proof_data_location =
+            ProofLocation::ContextStateAccount(self.equality_proof_account.key);
+
+        let range_proof_data_location =
+            ProofLocation::ContextStateAccount(self.range_proof_account.key);
+
+        let withdraw_instructions = withdraw(
+            self.token_2022_program.key,
+            self.reward_vault.key,
+            self.reward_mint.key,
+            amount,
+            decimals,
+            &new_decryptable_available_balance,
+            &self.pull.key(),
+            &[],
+            equality_proof_data_location,
+            range_proof_data_location,
+        )?;
+
+        let account_infos = [
+            self.reward_vault,
+            self.reward_mint,
+            self.equality_proof_account,
+            self.range_proof_account,
+            self.zk_elgamal_proof_program,
+            self.pull.as_ref(),
+        ];
+
+        for ix in withdraw_instructions {
+            invoke_signed_with_accounts(&ix, &account_infos, signer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Transfer the withdrawn reward to `destination`
+    pub fn transfer_reward(
+        &self,
+        destination: &AccountInfo<'info>,
+        amount: u64,
+        decimals: u8,
+    ) -> Result<()> {
+        let signer_seeds = &self.pull.get_signer_seeds();
+        let signer = &[&signer_seeds[..]];
+
+        let cpi_accounts = TransferChecked {
+            from: self.reward_vault.clone(),
+            to: destination.clone(),
+            authority: self.pull.to_account_info(),
+            mint: self.reward_mint.clone(),
+        };
+        let cpi_context =
+            CpiContext::new_with_signer(self.token_2022_program.clone(), cpi_accounts, signer);
+
+        token_2022::transfer_checked(cpi_context, amount, decimals)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::error::GachaError;
+
+const SLOT_HASH_ENTRY_LEN: usize = 8 + 32;
+
+/// Look up the hash of `slot` in the raw SlotHashes sysvar account.
+///
+/// The sysvar is too large to deserialize on chain, so entries (sorted by
+/// descending slot) are binary searched in place.
+pub fn get_slot_hash(slot_hashes: &AccountInfo, slot: u64) -> Result<Option<[u8; 32]>> {
+    let data = slot_hashes.try_borrow_data()?;
+
+    let len_bytes: [u8; 8] = data
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(GachaError::InvalidAccount)?;
+    let len = u64::from_le_bytes(len_bytes) as usize;
+
+    let entry = |index: usize| -> Option<(u64, &[u8])> {
+        let start = 8 + index * SLOT_HASH_ENTRY_LEN;
+        let bytes = data.get(start..start + SLOT_HASH_ENTRY_LEN)?;
+        let entry_slot = u64::from_le_bytes(bytes[..8].try_into().ok()?);
+        Some((entry_slot, &bytes[8..]))
+    };
+
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let (entry_slot, hash) = entry(mid).ok_or(GachaError::InvalidAccount)?;
+
+        if entry_slot == slot {
+            let mut slot_hash = [0u8; 32];
+            slot_hash.copy_from_slice(hash);
+            return Ok(Some(slot_hash));
+        }
+
+        if entry_slot > slot {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    Ok(None)
+}
+
+/// Hash of the most recent slot in the raw SlotHashes sysvar account
+pub fn get_latest_slot_hash(slot_hashes: &AccountInfo) -> Result<[u8; 32]> {
+    let data = slot_hashes.try_borrow_data()?;
+
+    // Entries are sorted by descending slot, the first one follows the u64 length
+    let hash = match data.get(..8) {
+        Some(len_bytes) if len_bytes != [0u8; 8] => data.get(8 + 8..8 + SLOT_HASH_ENTRY_LEN),
+        _ => None,
+    }
+    .ok_or(GachaError::InvalidAccount)?;
+
+    let mut slot_hash = [0u8; 32];
+    slot_hash.copy_from_slice(hash);
+
+    Ok(slot_hash)
+}
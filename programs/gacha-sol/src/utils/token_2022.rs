@@ -1,5 +1,21 @@
-use anchor_lang::prelude::Pubkey;
-use spl_token_2022::ID;
+use std::str::FromStr;
+
+use anchor_lang::prelude::*;
+use spl_token_2022::{
+    extension::{
+        confidential_transfer::{
+            instruction::ZeroCiphertextProofContext, ConfidentialTransferAccount,
+            ConfidentialTransferMint,
+        },
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    solana_zk_sdk::encryption::pod::elgamal::PodElGamalCiphertext,
+    state::{Account as Token2022Account, Mint as Mint2022},
+    ID,
+};
+use spl_token_confidential_transfer_ciphertext_arithmetic::subtract_from;
+
+use crate::{error::GachaError, state::ELGAMAL_PUBKEY_MAX_BASE64_LEN};
 
 #[derive(Clone)]
 pub struct Token2022;
@@ -9,3 +25,57 @@ impl anchor_lang::Id for Token2022 {
         ID
     }
 }
+
+/// Ensure the mint is a Token-2022 mint with the Confidential Transfer extension
+pub fn verify_confidential_transfer_mint(mint: &AccountInfo) -> Result<()> {
+    let mint_data = mint.data.borrow();
+    let mint_state = StateWithExtensions::<Mint2022>::unpack(&mint_data)?;
+
+    require!(
+        mint_state
+            .get_extension::<ConfidentialTransferMint>()
+            .is_ok(),
+        GachaError::InvalidRewardMint
+    );
+
+    Ok(())
+}
+
+/// Ensure withdrawing `amount` leaves the token account with a zero available balance
+pub fn verify_full_withdraw(
+    token_account: &AccountInfo,
+    zero_ciphertext_proof_context: &ZeroCiphertextProofContext,
+    amount: u64,
+) -> Result<()> {
+    let token_account_data = token_account.try_borrow_data()?;
+    let state = StateWithExtensions::<Token2022Account>::unpack(&token_account_data)?;
+    let confidential_transfer_account = state.get_extension::<ConfidentialTransferAccount>()?;
+
+    require!(
+        zero_ciphertext_proof_context.pubkey == confidential_transfer_account.elgamal_pubkey,
+        GachaError::InvalidElgamalPubkey
+    );
+
+    let remaining_balance = subtract_from(&confidential_transfer_account.available_balance, amount)
+        .ok_or(GachaError::CiphertextArithmeticFailed)?;
+
+    require!(
+        remaining_balance == zero_ciphertext_proof_context.ciphertext,
+        GachaError::PartialRewardWithdraw
+    );
+
+    Ok(())
+}
+
+/// Parse a base64 ElGamal ciphertext stored on a pull
+pub fn parse_ciphertext(
+    ciphertext: &[u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
+) -> Result<PodElGamalCiphertext> {
+    let ciphertext = PodElGamalCiphertext::from_str(
+        std::str::from_utf8(ciphertext)
+            .map_err(|_| GachaError::CipherTextBalanceConversionFailed)?,
+    )
+    .map_err(|_| GachaError::CipherTextBalanceConversionFailed)?;
+
+    Ok(ciphertext)
+}
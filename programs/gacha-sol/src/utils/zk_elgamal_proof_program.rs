@@ -1,5 +1,25 @@
-use anchor_lang::prelude::Pubkey;
-use spl_token_2022::solana_zk_sdk::zk_elgamal_proof_program::ID;
+use std::num::NonZeroI8;
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke_signed, sysvar::instructions::get_instruction_relative},
+};
+use bytemuck::Pod;
+use spl_pod::bytemuck::pod_from_bytes;
+use spl_token_2022::{
+    extension::confidential_transfer::instruction::{
+        pod::PodProofType, ProofContextState, ZkProofData,
+    },
+    solana_zk_sdk::zk_elgamal_proof_program::{
+        instruction::{close_context_state, ContextStateInfo, ProofInstruction},
+        ID,
+    },
+};
+use spl_token_confidential_transfer_proof_extraction::instruction::{
+    zk_proof_type_to_instruction, ProofData, ProofLocation,
+};
+
+use crate::error::GachaError;
 
 #[derive(Clone, PartialEq, Eq)]
 pub struct ZkElgamalProof;
@@ -9,3 +29,115 @@ impl anchor_lang::Id for ZkElgamalProof {
         ID
     }
 }
+
+/// Read the proof data of the zk proof instruction at `proof_instruction_offset`
+/// from the current instruction, `None` when the proof lives in a context state account
+pub fn get_instruction_proof_data<T: Pod + ZkProofData<U>, U: Pod>(
+    instructions_sysvar: Option<&AccountInfo>,
+    proof_instruction_offset: i8,
+) -> Result<Option<T>> {
+    if proof_instruction_offset == 0 {
+        return Ok(None);
+    }
+
+    let instructions_sysvar = instructions_sysvar.ok_or(GachaError::MissingProofLocation)?;
+    let instruction =
+        get_instruction_relative(proof_instruction_offset.into(), instructions_sysvar)?;
+    let expected_instruction = zk_proof_type_to_instruction(T::PROOF_TYPE)?;
+
+    require!(
+        instruction.program_id == ID
+            && ProofInstruction::instruction_type(&instruction.data) == Some(expected_instruction),
+        GachaError::InvalidProofType
+    );
+
+    let proof_data = ProofInstruction::proof_data::<T, U>(&instruction.data)
+        .ok_or(GachaError::ProofDataConversionError)?;
+
+    Ok(Some(*proof_data))
+}
+
+/// Read the proof context of a zk proof, from the proof instruction at
+/// `proof_instruction_offset` or from its context state account when the offset is zero
+pub fn get_proof_context<T: Pod + ZkProofData<U>, U: Pod>(
+    context_state_account: Option<&AccountInfo>,
+    instructions_sysvar: Option<&AccountInfo>,
+    proof_instruction_offset: i8,
+    context_state_authority: Option<&Pubkey>,
+) -> Result<U> {
+    if let Some(proof_data) =
+        get_instruction_proof_data::<T, U>(instructions_sysvar, proof_instruction_offset)?
+    {
+        return Ok(*proof_data.context_data());
+    }
+
+    let context_state_account = context_state_account.ok_or(GachaError::MissingProofLocation)?;
+    let context_state_account_data = context_state_account.data.borrow();
+    let context_state = pod_from_bytes::<ProofContextState<U>>(&context_state_account_data)?;
+
+    require!(
+        context_state.proof_type == PodProofType::from(T::PROOF_TYPE),
+        GachaError::InvalidProofType
+    );
+    if let Some(context_state_authority) = context_state_authority {
+        require!(
+            context_state.context_state_authority == *context_state_authority,
+            GachaError::InvalidContextAuthority
+        );
+    }
+
+    Ok(context_state.proof_context)
+}
+
+/// Locate a zk proof for a Token-2022 confidential transfer CPI
+pub fn get_proof_location<'a, T>(
+    context_state_account: Option<&'a AccountInfo>,
+    proof_data: Option<&'a T>,
+    proof_instruction_offset: i8,
+) -> Result<ProofLocation<'a, T>> {
+    let proof_location = match NonZeroI8::new(proof_instruction_offset) {
+        Some(proof_instruction_offset) => ProofLocation::InstructionOffset(
+            proof_instruction_offset,
+            ProofData::InstructionData(proof_data.ok_or(GachaError::MissingProofLocation)?),
+        ),
+        None => ProofLocation::ContextStateAccount(
+            context_state_account
+                .ok_or(GachaError::MissingProofLocation)?
+                .key,
+        ),
+    };
+
+    Ok(proof_location)
+}
+
+/// Close a zk proof context state account, returning its rent to `destination`.
+/// `signer_seeds` sign for a program address context state authority.
+pub fn close_context_state_account<'info>(
+    context_state_account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    context_state_authority: &AccountInfo<'info>,
+    zk_elgamal_proof_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let close_instruction = close_context_state(
+        ContextStateInfo {
+            context_state_account: context_state_account.key,
+            context_state_authority: context_state_authority.key,
+        },
+        destination.key,
+    );
+
+    invoke_signed(
+        &close_instruction,
+        &[
+            context_state_account.clone(),
+            destination.clone(),
+            context_state_authority.clone(),
+            zk_elgamal_proof_program.clone(),
+        ],
+        signer_seeds,
+    )
+    .map_err(|_| GachaError::CloseContextStateFailed)?;
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use gacha_sol::instruction;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+
+use crate::test_utils::gacha_sol_test_environment::GachaSolTestEnvironment;
+
+#[tokio::test]
+async fn test_accept_authority() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let authority_pubkey = env.authority.pubkey();
+    let new_authority = Keypair::new();
+    let new_authority_pubkey = new_authority.pubkey();
+
+    let ix = instruction::ProposeAuthority::populate(
+        env.game_id,
+        authority_pubkey,
+        new_authority_pubkey,
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    // === only the proposed authority can accept
+
+    let ix = instruction::AcceptAuthority::populate(env.game_id, authority_pubkey);
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    let ix = instruction::AcceptAuthority::populate(env.game_id, new_authority_pubkey);
+
+    let tx = env
+        .process_instruction(ix, &vec![&new_authority], Some(&env.payer))
+        .await?;
+
+    println!("accept authority tx: {}", tx);
+
+    let game_config = env.get_game_config().await?;
+
+    assert_eq!(game_config.authority, new_authority_pubkey);
+    assert_eq!(game_config.pending_authority, Pubkey::default());
+
+    Ok(())
+}
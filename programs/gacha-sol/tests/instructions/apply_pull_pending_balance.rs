@@ -170,6 +170,7 @@ async fn test_apply_pull_pending_balance() -> Result<()> {
     };
 
     let ix = instruction::ApplyPullPendingBalance::populate(
+        env.game_id,
         authority_pubkey,
         pull_id,
         decryptable_new_decryptable_available_balance_array,
@@ -1,11 +1,17 @@
 use anchor_spl::token::{spl_token::state::Account, TokenAccount};
 use anyhow::Result;
-use gacha_sol::instruction;
-use solana_sdk::{signature::Keypair, signer::Signer};
+use gacha_sol::{
+    instruction,
+    sdk::{get_wallet_purchases, AllowlistTree, WalletPurchases},
+    state::{PaymentMode, UpdateGameConfigParams, MAX_REFERRAL_BPS},
+};
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair, signer::Signer,
+};
 use spl_token_2022::ui_amount_to_amount;
 
 use crate::test_utils::{
-    gacha_sol_test_environment::GachaSolTestEnvironment,
+    gacha_sol_test_environment::{GachaSolTestEnvironment, REFUND_TIMEOUT},
     proof_account::{ProofAccount, SignerProofAccount},
 };
 
@@ -107,11 +113,13 @@ async fn test_buy_pull() -> Result<()> {
         .await?;
 
     let ix: solana_sdk::instruction::Instruction = instruction::BuyPull::populate(
+        env.game_id,
         buyer_pubkey,
         buyer_purchase_token_account,
-        env.game_vault_pubkey(),
         purchase_mint_pubkey,
-        pull_id,
+        None,
+        PaymentMode::Token,
+        None,
     );
 
     let tx = env
@@ -120,9 +128,20 @@ async fn test_buy_pull() -> Result<()> {
 
     println!("buy pull tx: {}", tx);
 
+    let pull_ticket = env.get_pull_ticket(&buyer_pubkey).await?;
+
+    assert_eq!(pull_ticket.game_config, env.game_config_pubkey());
+    assert_eq!(pull_ticket.buyer, buyer_pubkey);
+
+    let pull_pool = env.get_pull_pool().await?;
+
+    assert_eq!(pull_pool.pending_tickets, 1);
+    assert_eq!(pull_pool.pull_ids, vec![pull_id]);
+
+    // Buyer does not own a pull until the ticket is revealed
     let pull = env.get_pull(pull_id).await?;
 
-    assert_eq!(pull.buyer, buyer_pubkey);
+    assert_eq!(pull.buyer, Pubkey::default());
 
     let buyer_balance = {
         let mut test_fixtures = env.test_fixtures.lock().unwrap();
@@ -148,5 +167,628 @@ async fn test_buy_pull() -> Result<()> {
 
     println!("game_vault_balance: {}", game_vault_balance); // 100_000_000
 
+    assert_eq!(game_vault_balance, pull_price);
+    assert_eq!(env.get_game_config().await?.escrowed_amount, pull_price);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_buy_pull_sale_window() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    for _ in 0..2 {
+        env.create_verified_pull(SignerProofAccount::new(), expected_amount)
+            .await?;
+    }
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+
+    let sale_start = 4_000_000_000;
+    let sale_end = sale_start + 3_600;
+
+    // === sale end must be after sale start
+
+    let ix = instruction::UpdateGameConfig::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        None,
+        None,
+        UpdateGameConfigParams {
+            sale_start: Some(sale_end),
+            sale_end: Some(sale_start),
+            ..Default::default()
+        },
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    let ix = instruction::UpdateGameConfig::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        None,
+        None,
+        UpdateGameConfigParams {
+            sale_start: Some(sale_start),
+            sale_end: Some(sale_end),
+            ..Default::default()
+        },
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    let game_config = env.get_game_config().await?;
+
+    assert_eq!(game_config.sale_start, sale_start);
+    assert_eq!(game_config.sale_end, sale_end);
+
+    // === buy before the sale starts is rejected
+
+    let result = env.buy_pull(&buyer, &buyer_purchase_token_account).await;
+
+    assert!(result.is_err());
+
+    // === buy within the sale window
+
+    env.warp_to_timestamp(sale_start).await?;
+
+    let pull_id = env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+
+    assert_eq!(env.get_pull(pull_id).await?.buyer, buyer_pubkey);
+
+    // === buy once the sale ended is rejected
+
+    env.warp_to_timestamp(sale_end).await?;
+
+    let result = env.buy_pull(&buyer, &buyer_purchase_token_account).await;
+
+    assert!(result.is_err());
+    assert_eq!(env.get_pull_pool().await?.pull_ids.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_buy_pull_purchase_limit() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    for _ in 0..3 {
+        env.create_verified_pull(SignerProofAccount::new(), expected_amount)
+            .await?;
+    }
+
+    let max_pulls_per_wallet = 2;
+    let ix = instruction::UpdateGameConfig::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        None,
+        None,
+        UpdateGameConfigParams {
+            max_pulls_per_wallet: Some(max_pulls_per_wallet),
+            ..Default::default()
+        },
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+
+    let game_config = env.get_game_config().await?;
+
+    assert_eq!(
+        get_wallet_purchases(&game_config, None),
+        WalletPurchases {
+            bought: 0,
+            limit: Some(max_pulls_per_wallet),
+            remaining: Some(2),
+        }
+    );
+
+    // === buy up to the limit
+
+    for _ in 0..max_pulls_per_wallet {
+        env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+    }
+
+    let player_state = env.get_player_state(&buyer_pubkey).await?;
+
+    assert_eq!(
+        get_wallet_purchases(&game_config, Some(&player_state)),
+        WalletPurchases {
+            bought: 2,
+            limit: Some(max_pulls_per_wallet),
+            remaining: Some(0),
+        }
+    );
+
+    // === buy over the limit is rejected
+
+    let result = env.buy_pull(&buyer, &buyer_purchase_token_account).await;
+
+    assert!(result.is_err());
+    assert_eq!(env.get_pull_pool().await?.pull_ids.len(), 1);
+
+    // Other wallets keep their own counter
+    let other_buyer = Keypair::new();
+    let other_buyer_purchase_token_account = env
+        .create_ata(&purchase_mint_pubkey, &other_buyer.pubkey())
+        .await?;
+
+    env.mint_purchase_token(&other_buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+    env.buy_pull(&other_buyer, &other_buyer_purchase_token_account)
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_buy_pull_presale() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    for _ in 0..3 {
+        env.create_verified_pull(SignerProofAccount::new(), expected_amount)
+            .await?;
+    }
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let allowlisted_buyer = Keypair::new();
+    let other_buyer = Keypair::new();
+
+    let mut buyer_purchase_token_accounts = Vec::new();
+    for buyer in [&allowlisted_buyer, &other_buyer] {
+        let token_account = env
+            .create_ata(&purchase_mint_pubkey, &buyer.pubkey())
+            .await?;
+        env.mint_purchase_token(&token_account, 200_000_000_000)
+            .await?;
+        buyer_purchase_token_accounts.push(token_account);
+    }
+
+    // === allowlist built from a CSV, the allowlisted buyer may buy a single presale pull
+
+    let csv = format!(
+        "# presale round 1\nwallet,allocation\n{},1\n{}\n",
+        allowlisted_buyer.pubkey(),
+        Pubkey::new_unique()
+    );
+    let allowlist = AllowlistTree::from_csv(&csv)?;
+
+    let presale_end = 4_000_000_000;
+    let ix = instruction::UpdateGameConfig::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        None,
+        None,
+        UpdateGameConfigParams {
+            sale_start: Some(presale_end),
+            merkle_root: Some(allowlist.root()),
+            presale_start: Some(0),
+            presale_end: Some(presale_end),
+            ..Default::default()
+        },
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    let game_config = env.get_game_config().await?;
+
+    assert_eq!(game_config.merkle_root, allowlist.root());
+    assert_eq!(game_config.presale_end, presale_end);
+
+    // === wallets outside the allowlist are rejected during the presale
+
+    let result = env
+        .buy_pull(&other_buyer, &buyer_purchase_token_accounts[1])
+        .await;
+
+    assert!(result.is_err());
+
+    let other_proof =
+        AllowlistTree::new(vec![(other_buyer.pubkey(), 0)]).get_proof(&other_buyer.pubkey());
+
+    assert!(allowlist.get_proof(&other_buyer.pubkey()).is_none());
+
+    let result = env
+        .buy_pull_with_allowlist(&other_buyer, &buyer_purchase_token_accounts[1], other_proof)
+        .await;
+
+    assert!(result.is_err());
+
+    // === allowlisted wallet buys within its allocation
+
+    let proof = allowlist.get_proof(&allowlisted_buyer.pubkey());
+
+    assert_eq!(proof.as_ref().map(|proof| proof.allocation), Some(1));
+
+    let pull_id = env
+        .buy_pull_with_allowlist(
+            &allowlisted_buyer,
+            &buyer_purchase_token_accounts[0],
+            proof.clone(),
+        )
+        .await?;
+
+    assert_eq!(
+        env.get_pull(pull_id).await?.buyer,
+        allowlisted_buyer.pubkey()
+    );
+
+    let result = env
+        .buy_pull_with_allowlist(&allowlisted_buyer, &buyer_purchase_token_accounts[0], proof)
+        .await;
+
+    assert!(result.is_err());
+
+    // === public sale opens when the presale ends
+
+    env.warp_to_timestamp(presale_end).await?;
+
+    env.buy_pull(&other_buyer, &buyer_purchase_token_accounts[1])
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_buy_pull_referral() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let pull_proof_account = SignerProofAccount::new();
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    env.create_verified_pull(pull_proof_account.clone(), expected_amount)
+        .await?;
+
+    // === referral share is capped
+
+    let update_referral_bps = |referral_bps: u16| {
+        instruction::UpdateGameConfig::populate(
+            env.game_id,
+            env.authority.pubkey(),
+            None,
+            None,
+            UpdateGameConfigParams {
+                referral_bps: Some(referral_bps),
+                ..Default::default()
+            },
+        )
+    };
+
+    let result = env
+        .process_instruction(
+            update_referral_bps(MAX_REFERRAL_BPS + 1),
+            &vec![&env.authority],
+            Some(&env.payer),
+        )
+        .await;
+
+    assert!(result.is_err());
+
+    let referral_bps = 1_000;
+    env.process_instruction(
+        update_referral_bps(referral_bps),
+        &vec![&env.authority],
+        Some(&env.payer),
+    )
+    .await?;
+
+    assert_eq!(env.get_game_config().await?.referral_bps, referral_bps);
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+    let referrer_pubkey = Pubkey::new_unique();
+    let referrer_purchase_token_account = env
+        .create_ata(&purchase_mint_pubkey, &referrer_pubkey)
+        .await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+
+    // === buyer cannot refer itself
+
+    let ix = instruction::BuyPull::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_purchase_token_account,
+        purchase_mint_pubkey,
+        Some(buyer_purchase_token_account),
+        PaymentMode::Token,
+        None,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === the whole price is escrowed, the referral fee is owed to the referrer
+
+    let ix = instruction::BuyPull::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_purchase_token_account,
+        purchase_mint_pubkey,
+        Some(referrer_purchase_token_account),
+        PaymentMode::Token,
+        None,
+    );
+
+    env.process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await?;
+
+    let referral_fee = 10_000_000;
+    let pull_ticket = env.get_pull_ticket(&buyer_pubkey).await?;
+
+    assert_eq!(pull_ticket.purchase_price, pull_price);
+    assert_eq!(pull_ticket.referrer, referrer_pubkey);
+    assert_eq!(pull_ticket.referral_fee, referral_fee);
+
+    let pull_id = env.reveal_pull(&buyer_pubkey).await?;
+    let pull = env.get_pull(pull_id).await?;
+
+    assert_eq!(pull.purchase_price, pull_price);
+    assert_eq!(pull.referrer, referrer_pubkey);
+    assert_eq!(pull.referral_fee, referral_fee);
+    assert_eq!(
+        env.get_token_balance(&referrer_purchase_token_account)
+            .await?,
+        0
+    );
+    assert_eq!(
+        env.get_token_balance(&env.game_vault_pubkey()).await?,
+        pull_price
+    );
+    assert_eq!(env.get_game_config().await?.escrowed_amount, pull_price);
+
+    // === referral fee of an unclaimed pull cannot be paid
+
+    let pay_referral_fee_ix = || {
+        instruction::PayReferralFee::populate(
+            env.game_id,
+            purchase_mint_pubkey,
+            referrer_purchase_token_account,
+            pull_id,
+        )
+    };
+
+    let result = env
+        .process_instruction(pay_referral_fee_ix(), &vec![], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === claiming releases the price without the referral fee
+
+    env.open_pull(pull_id, pull_proof_account, &buyer_pubkey, expected_amount)
+        .await?;
+
+    let game_config = env.get_game_config().await?;
+    assert_eq!(game_config.escrowed_amount, 0);
+    assert_eq!(
+        game_config.withdrawable_amount()?,
+        pull_price - referral_fee
+    );
+    assert_eq!(game_config.outstanding_referral_fees, referral_fee);
+
+    // === purchase mint cannot be switched while the referral fee is unpaid
+
+    let authority_pubkey = env.authority.pubkey();
+    let destination = env
+        .create_ata(&purchase_mint_pubkey, &authority_pubkey)
+        .await?;
+
+    let ix = instruction::WithdrawProceeds::populate(
+        env.game_id,
+        authority_pubkey,
+        purchase_mint_pubkey,
+        destination,
+        pull_price - referral_fee,
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    let new_purchase_mint_pubkey = {
+        let mut test_fixtures = env.test_fixtures.lock().unwrap();
+        test_fixtures
+            .create_mint(&Keypair::new().pubkey(), env.decimals)
+            .await?
+    };
+    let update_purchase_mint_ix = || {
+        instruction::UpdateGameConfig::populate(
+            env.game_id,
+            authority_pubkey,
+            Some(new_purchase_mint_pubkey),
+            None,
+            UpdateGameConfigParams::default(),
+        )
+    };
+
+    let result = env
+        .process_instruction(
+            update_purchase_mint_ix(),
+            &vec![&env.authority],
+            Some(&env.payer),
+        )
+        .await;
+
+    assert!(result.is_err());
+
+    // === anyone can pay the referral fee, once
+
+    env.process_instruction(pay_referral_fee_ix(), &vec![], Some(&env.payer))
+        .await?;
+
+    assert!(env.get_pull(pull_id).await?.referral_fee_paid);
+    assert_eq!(
+        env.get_token_balance(&referrer_purchase_token_account)
+            .await?,
+        referral_fee
+    );
+    assert_eq!(env.get_token_balance(&env.game_vault_pubkey()).await?, 0);
+    assert_eq!(env.get_game_config().await?.outstanding_referral_fees, 0);
+
+    let result = env
+        .process_instruction(pay_referral_fee_ix(), &vec![], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === settled vault allows the purchase mint switch
+
+    env.process_instruction(
+        update_purchase_mint_ix(),
+        &vec![&env.authority],
+        Some(&env.payer),
+    )
+    .await?;
+
+    assert_eq!(
+        env.get_game_config().await?.purchase_mint,
+        new_purchase_mint_pubkey
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_buy_pull_native_sol() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config_with_payment_mode(pull_price, PaymentMode::NativeSol)
+        .await?;
+
+    let treasury_pubkey = env.treasury_pubkey();
+    let treasury_rent = Rent::default().minimum_balance(0);
+
+    assert_eq!(
+        env.get_game_config().await?.payment_mode,
+        PaymentMode::NativeSol
+    );
+    assert_eq!(env.get_lamports(&treasury_pubkey).await?, treasury_rent);
+
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    env.create_verified_pull(SignerProofAccount::new(), expected_amount)
+        .await?;
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+
+    env.transfer_lamports(&buyer_pubkey, LAMPORTS_PER_SOL)
+        .await?;
+    env.mint_purchase_token(&buyer_purchase_token_account, pull_price)
+        .await?;
+
+    // === paying with tokens is rejected
+
+    let ix = instruction::BuyPull::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_purchase_token_account,
+        purchase_mint_pubkey,
+        None,
+        PaymentMode::Token,
+        None,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === pay in native SOL, escrowed in the treasury
+
+    let ix = instruction::BuyPull::populate(
+        env.game_id,
+        buyer_pubkey,
+        Pubkey::default(),
+        purchase_mint_pubkey,
+        None,
+        PaymentMode::NativeSol,
+        None,
+    );
+
+    env.process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await?;
+
+    let pull_id = env.reveal_pull(&buyer_pubkey).await?;
+    let pull = env.get_pull(pull_id).await?;
+
+    assert_eq!(pull.purchase_price, pull_price);
+    assert_eq!(
+        env.get_lamports(&treasury_pubkey).await?,
+        treasury_rent + pull_price
+    );
+    assert_eq!(
+        env.get_token_balance(&buyer_purchase_token_account).await?,
+        pull_price
+    );
+    assert_eq!(env.get_game_config().await?.escrowed_amount, pull_price);
+
+    // === unopened pull is refunded from the treasury
+
+    env.warp_to_timestamp(pull.purchased_at + REFUND_TIMEOUT)
+        .await?;
+
+    let buyer_lamports = env.get_lamports(&buyer_pubkey).await?;
+    let ix = instruction::RefundPull::populate_native(
+        env.game_id,
+        buyer_pubkey,
+        purchase_mint_pubkey,
+        pull_id,
+    );
+
+    env.process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await?;
+
+    assert!(env.get_pull(pull_id).await?.refunded);
+    assert_eq!(
+        env.get_lamports(&buyer_pubkey).await?,
+        buyer_lamports + pull_price
+    );
+    assert_eq!(env.get_lamports(&treasury_pubkey).await?, treasury_rent);
+    assert_eq!(env.get_game_config().await?.escrowed_amount, 0);
+
     Ok(())
 }
@@ -0,0 +1,278 @@
+use anyhow::Result;
+use gacha_sol::{
+    instruction,
+    state::{PaymentMode, PullBundle, UpdateGameConfigParams, MAX_BUNDLE_SIZE, MAX_PULL_BUNDLES},
+};
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair, signer::Signer,
+};
+use spl_token_2022::ui_amount_to_amount;
+
+use crate::test_utils::{
+    gacha_sol_test_environment::GachaSolTestEnvironment, proof_account::SignerProofAccount,
+};
+
+#[tokio::test]
+async fn test_buy_pulls() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    for _ in 0..3 {
+        env.create_verified_pull(SignerProofAccount::new(), expected_amount)
+            .await?;
+    }
+
+    // === bundles need at least two pulls
+
+    let mut bundles = [PullBundle::default(); MAX_PULL_BUNDLES];
+    bundles[0] = PullBundle {
+        size: 1,
+        price: pull_price,
+    };
+
+    let ix = instruction::UpdateGameConfig::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        None,
+        None,
+        UpdateGameConfigParams {
+            bundles: Some(bundles),
+            ..Default::default()
+        },
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === bundles can't exceed the pulls revealed in one transaction
+
+    bundles[0] = PullBundle {
+        size: MAX_BUNDLE_SIZE + 1,
+        price: pull_price,
+    };
+
+    let ix = instruction::UpdateGameConfig::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        None,
+        None,
+        UpdateGameConfigParams {
+            bundles: Some(bundles),
+            ..Default::default()
+        },
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === put a discounted 2-pull bundle on sale
+
+    let bundle_price = 150_000_001;
+    bundles[0] = PullBundle {
+        size: 2,
+        price: bundle_price,
+    };
+
+    let ix = instruction::UpdateGameConfig::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        None,
+        None,
+        UpdateGameConfigParams {
+            bundles: Some(bundles),
+            ..Default::default()
+        },
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    assert_eq!(env.get_game_config().await?.bundles, bundles);
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+
+    // === bundle size not on sale is rejected
+
+    let ix = instruction::BuyPulls::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_purchase_token_account,
+        purchase_mint_pubkey,
+        PaymentMode::Token,
+        3,
+        None,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === buy the bundle
+
+    let pull_ids = env
+        .buy_pulls(&buyer, &buyer_purchase_token_account, 2)
+        .await?;
+
+    assert_eq!(pull_ids.len(), 2);
+    assert_ne!(pull_ids[0], pull_ids[1]);
+
+    let first_pull = env.get_pull(pull_ids[0]).await?;
+    let second_pull = env.get_pull(pull_ids[1]).await?;
+
+    assert_eq!(first_pull.buyer, buyer_pubkey);
+    assert_eq!(second_pull.buyer, buyer_pubkey);
+    assert_eq!(first_pull.purchase_price, 75_000_001);
+    assert_eq!(second_pull.purchase_price, 75_000_000);
+
+    let pull_pool = env.get_pull_pool().await?;
+
+    assert_eq!(pull_pool.pending_tickets, 0);
+    assert_eq!(pull_pool.pull_ids.len(), 1);
+
+    let game_config = env.get_game_config().await?;
+
+    assert_eq!(game_config.escrowed_amount, bundle_price);
+    assert_eq!(
+        env.get_token_balance(&env.game_vault_pubkey()).await?,
+        bundle_price
+    );
+
+    // Ticket is closed once revealed
+    assert!(env
+        .get_lamports(&env.pull_ticket_pubkey(&buyer_pubkey))
+        .await
+        .is_err());
+
+    // === bundle larger than the pulls left is rejected
+
+    let ix = instruction::BuyPulls::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_purchase_token_account,
+        purchase_mint_pubkey,
+        PaymentMode::Token,
+        2,
+        None,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_buy_pulls_native_sol() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config_with_payment_mode(pull_price, PaymentMode::NativeSol)
+        .await?;
+
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    for _ in 0..2 {
+        env.create_verified_pull(SignerProofAccount::new(), expected_amount)
+            .await?;
+    }
+
+    let bundle_price = 150_000_000;
+    let mut bundles = [PullBundle::default(); MAX_PULL_BUNDLES];
+    bundles[0] = PullBundle {
+        size: 2,
+        price: bundle_price,
+    };
+
+    let ix = instruction::UpdateGameConfig::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        None,
+        None,
+        UpdateGameConfigParams {
+            bundles: Some(bundles),
+            ..Default::default()
+        },
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let treasury_pubkey = env.treasury_pubkey();
+    let treasury_rent = Rent::default().minimum_balance(0);
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+
+    env.transfer_lamports(&buyer_pubkey, LAMPORTS_PER_SOL)
+        .await?;
+    env.mint_purchase_token(&buyer_purchase_token_account, bundle_price)
+        .await?;
+
+    // === paying with tokens is rejected
+
+    let ix = instruction::BuyPulls::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_purchase_token_account,
+        purchase_mint_pubkey,
+        PaymentMode::Token,
+        2,
+        None,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === pay the bundle in native SOL, escrowed in the treasury
+
+    let ix = instruction::BuyPulls::populate(
+        env.game_id,
+        buyer_pubkey,
+        Pubkey::default(),
+        purchase_mint_pubkey,
+        PaymentMode::NativeSol,
+        2,
+        None,
+    );
+
+    env.process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await?;
+
+    let pull_ids = env.reveal_pulls(&buyer_pubkey).await?;
+
+    assert_eq!(pull_ids.len(), 2);
+    assert_eq!(
+        env.get_lamports(&treasury_pubkey).await?,
+        treasury_rent + bundle_price
+    );
+    assert_eq!(
+        env.get_token_balance(&buyer_purchase_token_account).await?,
+        bundle_price
+    );
+    assert_eq!(env.get_game_config().await?.escrowed_amount, bundle_price);
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use anyhow::Result;
+use gacha_sol::{instruction, state::PaymentMode};
+use solana_sdk::{signature::Keypair, signer::Signer};
+use spl_token_2022::ui_amount_to_amount;
+
+use crate::test_utils::{
+    gacha_sol_test_environment::{GachaSolTestEnvironment, REFUND_TIMEOUT},
+    proof_account::SignerProofAccount,
+};
+
+#[tokio::test]
+async fn test_cancel_ticket() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    for _ in 0..2 {
+        env.create_verified_pull(SignerProofAccount::new(), expected_amount)
+            .await?;
+    }
+
+    // === buy a ticket and leave it unrevealed
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, pull_price * 2)
+        .await?;
+
+    let buy_pull_ix = || {
+        instruction::BuyPull::populate(
+            env.game_id,
+            buyer_pubkey,
+            buyer_purchase_token_account,
+            purchase_mint_pubkey,
+            None,
+            PaymentMode::Token,
+            None,
+        )
+    };
+
+    env.process_instruction(buy_pull_ix(), &vec![&buyer], Some(&env.payer))
+        .await?;
+
+    assert_eq!(env.get_pull_pool().await?.pending_tickets, 1);
+    assert_eq!(env.get_player_state(&buyer_pubkey).await?.pulls_bought, 1);
+
+    // === a ticket that can still be revealed is not cancelled
+
+    let cancel_ticket_ix = || {
+        instruction::CancelTicket::populate(
+            env.game_id,
+            buyer_pubkey,
+            buyer_purchase_token_account,
+            purchase_mint_pubkey,
+            PaymentMode::Token,
+        )
+    };
+
+    let result = env
+        .process_instruction(cancel_ticket_ix(), &vec![&buyer], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === buyer skipping the reveal cannot cancel before the refund timeout
+
+    env.expire_pull_ticket(&buyer_pubkey).await?;
+
+    let result = env
+        .process_instruction(cancel_ticket_ix(), &vec![&buyer], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // Without the commit slot hash only the authority reveals
+    let result = env.reveal_pull(&buyer_pubkey).await;
+
+    assert!(result.is_err());
+
+    // === authority reveals the expired ticket with fallback randomness
+
+    let pull_ids = env.reveal_expired_pulls(&buyer_pubkey).await?;
+
+    assert_eq!(env.get_pull(pull_ids[0]).await?.buyer, buyer_pubkey);
+    assert_eq!(env.get_pull_pool().await?.pending_tickets, 0);
+    assert_eq!(env.get_game_config().await?.escrowed_amount, pull_price);
+    assert!(env.get_pull_ticket(&buyer_pubkey).await.is_err());
+
+    // === cancel once the authority let the refund timeout pass
+
+    env.process_instruction(buy_pull_ix(), &vec![&buyer], Some(&env.payer))
+        .await?;
+
+    env.expire_pull_ticket(&buyer_pubkey).await?;
+
+    let pull_ticket = env.get_pull_ticket(&buyer_pubkey).await?;
+    env.warp_to_timestamp(pull_ticket.purchased_at + REFUND_TIMEOUT)
+        .await?;
+
+    env.process_instruction(cancel_ticket_ix(), &vec![&buyer], Some(&env.payer))
+        .await?;
+
+    assert_eq!(
+        env.get_token_balance(&buyer_purchase_token_account).await?,
+        pull_price
+    );
+    assert_eq!(
+        env.get_token_balance(&env.game_vault_pubkey()).await?,
+        pull_price
+    );
+    assert_eq!(env.get_game_config().await?.escrowed_amount, pull_price);
+    assert_eq!(env.get_pull_pool().await?.pending_tickets, 0);
+    assert_eq!(env.get_player_state(&buyer_pubkey).await?.pulls_bought, 1);
+
+    // Ticket is closed once cancelled
+    assert!(env.get_pull_ticket(&buyer_pubkey).await.is_err());
+
+    // === the pull is back on sale
+
+    env.mint_purchase_token(&buyer_purchase_token_account, pull_price)
+        .await?;
+    let pull_id = env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+
+    assert_eq!(env.get_pull(pull_id).await?.buyer, buyer_pubkey);
+
+    Ok(())
+}
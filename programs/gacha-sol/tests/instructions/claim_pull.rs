@@ -0,0 +1,161 @@
+use anyhow::Result;
+use gacha_sol::instruction;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use spl_token_2022::ui_amount_to_amount;
+
+use crate::test_utils::{
+    gacha_sol_test_environment::GachaSolTestEnvironment, proof_account::SignerProofAccount,
+};
+
+#[tokio::test]
+async fn test_claim_pull() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let pull_proof_account = SignerProofAccount::new();
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    let pull_id = env
+        .create_verified_pull(pull_proof_account.clone(), expected_amount)
+        .await?;
+
+    let withdraw_proofs = env
+        .create_published_withdraw_proofs(pull_id, pull_proof_account.clone(), expected_amount)
+        .await?;
+
+    // === withdraw proofs of an unsold pull are rejected, they would give its tier away
+
+    let ix = instruction::PublishWithdrawProofs::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        withdraw_proofs.equality_proof,
+        withdraw_proofs.range_proof,
+        withdraw_proofs.zero_balance_proof,
+        pull_id,
+        withdraw_proofs.amount,
+        withdraw_proofs.new_decryptable_available_balance,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === buy pull
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let reward_mint_pubkey = env.reward_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+    let buyer_reward_token_account = env.create_ata(&reward_mint_pubkey, &buyer_pubkey).await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+
+    env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+
+    // === withdraw proofs the authority could close are rejected
+
+    let authority_withdraw_proofs = env
+        .create_withdraw_proofs(pull_id, pull_proof_account.clone(), expected_amount)
+        .await?;
+
+    let ix = instruction::PublishWithdrawProofs::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        authority_withdraw_proofs.equality_proof,
+        authority_withdraw_proofs.range_proof,
+        authority_withdraw_proofs.zero_balance_proof,
+        pull_id,
+        authority_withdraw_proofs.amount,
+        authority_withdraw_proofs.new_decryptable_available_balance,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === authority publishes the withdraw proofs
+
+    let ix = instruction::PublishWithdrawProofs::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        withdraw_proofs.equality_proof,
+        withdraw_proofs.range_proof,
+        withdraw_proofs.zero_balance_proof,
+        pull_id,
+        withdraw_proofs.amount,
+        withdraw_proofs.new_decryptable_available_balance,
+    );
+
+    let tx = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    println!("publish withdraw proofs tx: {}", tx);
+
+    let pull = env.get_pull(pull_id).await?;
+
+    assert!(pull.withdraw_proofs_published);
+    assert_eq!(pull.withdraw_equality_proof, withdraw_proofs.equality_proof);
+    assert_eq!(pull.withdraw_range_proof, withdraw_proofs.range_proof);
+
+    // === someone other than the buyer cannot claim
+
+    let other = Keypair::new();
+    let other_reward_token_account = env.create_ata(&reward_mint_pubkey, &other.pubkey()).await?;
+
+    let ix = instruction::ClaimPull::populate(
+        env.game_id,
+        other.pubkey(),
+        other_reward_token_account,
+        reward_mint_pubkey,
+        None,
+        withdraw_proofs.equality_proof,
+        withdraw_proofs.range_proof,
+        withdraw_proofs.zero_balance_proof,
+        pull_id,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&other], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === buyer claims without the authority
+
+    let ix = instruction::ClaimPull::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_reward_token_account,
+        reward_mint_pubkey,
+        None,
+        withdraw_proofs.equality_proof,
+        withdraw_proofs.range_proof,
+        withdraw_proofs.zero_balance_proof,
+        pull_id,
+    );
+
+    let tx = env
+        .process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await?;
+
+    println!("claim pull tx: {}", tx);
+
+    let pull = env.get_pull(pull_id).await?;
+
+    assert!(pull.claimed);
+    assert_eq!(pull.revealed_amount, expected_amount);
+    assert_eq!(
+        env.get_game_config().await?.withdrawable_amount()?,
+        pull_price
+    );
+
+    Ok(())
+}
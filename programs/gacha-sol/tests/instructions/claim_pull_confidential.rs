@@ -0,0 +1,112 @@
+use anyhow::Result;
+use gacha_sol::instruction;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use spl_token_2022::ui_amount_to_amount;
+
+use crate::test_utils::{
+    gacha_sol_test_environment::GachaSolTestEnvironment, proof_account::SignerProofAccount,
+};
+
+#[tokio::test]
+async fn test_claim_pull_confidential() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let pull_proof_account = SignerProofAccount::new();
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    let pull_id = env
+        .create_verified_pull(pull_proof_account.clone(), expected_amount)
+        .await?;
+
+    // === buy pull
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let reward_mint_pubkey = env.reward_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+
+    env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+
+    let buyer_token_proof_account = SignerProofAccount::new();
+    let buyer_reward_token_account = buyer_token_proof_account.pubkey();
+
+    env.create_ct_token_account(
+        &reward_mint_pubkey,
+        &buyer,
+        buyer_token_proof_account.clone(),
+    )
+    .await?;
+
+    // === authority verifies the transfer proofs
+
+    let transfer_proofs = env
+        .create_transfer_proofs(pull_id, pull_proof_account, &buyer_reward_token_account)
+        .await?;
+
+    let ix = instruction::VerifyPullTransfer::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        buyer_reward_token_account,
+        reward_mint_pubkey,
+        transfer_proofs.equality_proof,
+        transfer_proofs.ciphertext_validity_proof,
+        transfer_proofs.range_proof,
+        transfer_proofs.zero_balance_proof,
+        pull_id,
+        transfer_proofs.transfer_amount_auditor_ciphertext_lo,
+        transfer_proofs.transfer_amount_auditor_ciphertext_hi,
+        transfer_proofs.new_decryptable_available_balance,
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    // === buyer claims the reward confidentially
+
+    let ix = instruction::ClaimPullConfidential::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_reward_token_account,
+        reward_mint_pubkey,
+        transfer_proofs.equality_proof,
+        transfer_proofs.ciphertext_validity_proof,
+        transfer_proofs.range_proof,
+        pull_id,
+    );
+
+    let tx = env
+        .process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await?;
+
+    println!("claim pull confidential tx: {}", tx);
+
+    let pull = env.get_pull(pull_id).await?;
+
+    assert!(pull.claimed);
+    assert_eq!(pull.revealed_amount, 0);
+    assert_eq!(
+        env.get_game_config().await?.withdrawable_amount()?,
+        pull_price
+    );
+
+    // Reward lands in the buyer confidential balance
+    env.apply_pending_balance(buyer_token_proof_account.clone(), &buyer)
+        .await?;
+
+    let available_balance = {
+        let mut test_fixtures = env.test_fixtures.lock().unwrap();
+        test_fixtures
+            .get_token_account_decrypted_decryptable_available_balance(&buyer_token_proof_account)
+            .await?
+    };
+
+    assert_eq!(available_balance, expected_amount);
+
+    Ok(())
+}
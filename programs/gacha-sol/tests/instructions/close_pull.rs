@@ -0,0 +1,148 @@
+use anyhow::Result;
+use gacha_sol::instruction;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use spl_token_2022::ui_amount_to_amount;
+
+use crate::test_utils::{
+    gacha_sol_test_environment::GachaSolTestEnvironment, proof_account::SignerProofAccount,
+};
+
+#[tokio::test]
+async fn test_close_pull() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let pull_proof_account = SignerProofAccount::new();
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    let pull_id = env
+        .create_verified_pull(pull_proof_account.clone(), expected_amount)
+        .await?;
+
+    // === buy pull
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let reward_mint_pubkey = env.reward_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+    let buyer_reward_token_account = env.create_ata(&reward_mint_pubkey, &buyer_pubkey).await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+
+    env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+
+    let withdraw_proofs = env
+        .create_published_withdraw_proofs(pull_id, pull_proof_account, expected_amount)
+        .await?;
+
+    let ix = instruction::PublishWithdrawProofs::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        withdraw_proofs.equality_proof,
+        withdraw_proofs.range_proof,
+        withdraw_proofs.zero_balance_proof,
+        pull_id,
+        withdraw_proofs.amount,
+        withdraw_proofs.new_decryptable_available_balance,
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    let rent_receiver = Keypair::new().pubkey();
+    let published_withdraw_proofs =
+        Some([withdraw_proofs.equality_proof, withdraw_proofs.range_proof]);
+
+    // === unclaimed pull cannot be closed
+
+    let ix = instruction::ClosePull::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        withdraw_proofs.zero_balance_proof,
+        published_withdraw_proofs,
+        rent_receiver,
+        pull_id,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === claim pull
+
+    let ix = instruction::ClaimPull::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_reward_token_account,
+        reward_mint_pubkey,
+        None,
+        withdraw_proofs.equality_proof,
+        withdraw_proofs.range_proof,
+        withdraw_proofs.zero_balance_proof,
+        pull_id,
+    );
+
+    env.process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await?;
+
+    // === published withdraw proofs have to be closed with the pull
+
+    let ix = instruction::ClosePull::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        withdraw_proofs.zero_balance_proof,
+        None,
+        rent_receiver,
+        pull_id,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === close pull
+
+    let pull_pubkey = env.pull_pubkey(pull_id);
+    let reward_vault_pubkey = env.reward_vault_pubkey(pull_pubkey);
+    let mut reclaimed_lamports =
+        env.get_lamports(&pull_pubkey).await? + env.get_lamports(&reward_vault_pubkey).await?;
+    for proof_pubkey in [
+        withdraw_proofs.equality_proof,
+        withdraw_proofs.range_proof,
+        withdraw_proofs.zero_balance_proof,
+    ] {
+        reclaimed_lamports += env.get_lamports(&proof_pubkey).await?;
+    }
+
+    let ix = instruction::ClosePull::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        withdraw_proofs.zero_balance_proof,
+        published_withdraw_proofs,
+        rent_receiver,
+        pull_id,
+    );
+
+    let tx = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    println!("close pull tx: {}", tx);
+
+    assert!(env.get_pull(pull_id).await.is_err());
+    assert!(env.get_lamports(&reward_vault_pubkey).await.is_err());
+    assert!(env
+        .get_lamports(&withdraw_proofs.equality_proof)
+        .await
+        .is_err());
+    assert_eq!(env.get_lamports(&rent_receiver).await?, reclaimed_lamports);
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use anyhow::Result;
+use gacha_sol::state::PrizeTier;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use spl_token_2022::ui_amount_to_amount;
+
+use crate::test_utils::{
+    gacha_sol_test_environment::GachaSolTestEnvironment, proof_account::SignerProofAccount,
+};
+
+#[tokio::test]
+async fn test_commit_prize_table() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    // === tier with an empty amount range is rejected
+
+    let result = env
+        .commit_prize_table(vec![PrizeTier {
+            count: 2,
+            min_amount: ui_amount_to_amount(150.0, env.decimals),
+            max_amount: ui_amount_to_amount(50.0, env.decimals),
+        }])
+        .await;
+
+    assert!(result.is_err());
+
+    // === commit prize table
+
+    let tier = PrizeTier {
+        count: 2,
+        min_amount: ui_amount_to_amount(50.0, env.decimals),
+        max_amount: ui_amount_to_amount(150.0, env.decimals),
+    };
+    env.commit_prize_table(vec![tier]).await?;
+
+    let prize_table = env.get_prize_table().await?;
+    assert_eq!(prize_table.game_config, env.game_config_pubkey());
+    assert_eq!(prize_table.tiers, vec![tier]);
+
+    let game_config = env.get_game_config().await?;
+    assert_eq!(game_config.prize_table, env.prize_table_pubkey());
+    assert_eq!(game_config.unsold_pulls_per_tier[0], 0);
+
+    // === committed prize table cannot be replaced
+
+    let result = env.commit_prize_table(vec![tier]).await;
+
+    assert!(result.is_err());
+
+    // === reward outside the prize range never enters the pool
+
+    let expected_amount = ui_amount_to_amount(200.0, env.decimals);
+    let result = env
+        .create_verified_pull(SignerProofAccount::new(), expected_amount)
+        .await;
+
+    assert!(result.is_err());
+    assert!(env.get_pull_pool().await?.pull_ids.is_empty());
+
+    // === reward within the prize range is verified
+
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    let pull_id = env
+        .create_verified_pull(SignerProofAccount::new(), expected_amount)
+        .await?;
+
+    assert_eq!(env.get_pull_pool().await?.pull_ids, vec![pull_id]);
+
+    let prize_table = env.get_prize_table().await?;
+    assert_eq!(prize_table.created_per_tier[0], 2);
+
+    // Both created pulls stay unsold until assigned to a buyer
+    assert_eq!(env.get_game_config().await?.unsold_pulls_per_tier[0], 2);
+
+    // === pulls beyond the tier count are rejected
+
+    let result = env
+        .create_pull(pull_id + 1, SignerProofAccount::new(), expected_amount)
+        .await;
+
+    assert!(result.is_err());
+
+    // === the assigned pull is no longer unsold
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_purchase_token_account = env
+        .create_ata(&purchase_mint_pubkey, &buyer.pubkey())
+        .await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+
+    assert_eq!(
+        env.buy_pull(&buyer, &buyer_purchase_token_account).await?,
+        pull_id
+    );
+    assert_eq!(env.get_game_config().await?.unsold_pulls_per_tier[0], 1);
+
+    Ok(())
+}
@@ -4,7 +4,10 @@ use gacha_sol::{
     state::{AE_CIPHERTEXT_MAX_BASE64_LEN, ELGAMAL_PUBKEY_MAX_BASE64_LEN},
 };
 use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
-use spl_token_2022::extension::confidential_transfer::instruction::PubkeyValidityProofData;
+use spl_token_2022::{
+    extension::confidential_transfer::instruction::PubkeyValidityProofData,
+    solana_zk_sdk::zk_elgamal_proof_program::instruction::ProofInstruction,
+};
 
 use crate::test_utils::{
     confidential_transfer::get_zk_proof_context_state_account_creation_instructions,
@@ -26,6 +29,7 @@ async fn test_create_pull() -> Result<()> {
     let pull_pubkey = env.pull_pubkey(pull_id);
     let reward_vault_pubkey = env.reward_vault_pubkey(pull_pubkey);
     let expected_amount = 200_000_000;
+    let tier = 2;
 
     let pull_proof_account = SignerProofAccount::new();
 
@@ -77,12 +81,15 @@ async fn test_create_pull() -> Result<()> {
         )?;
 
     let ix = instruction::CreatePull::populate(
+        env.game_id,
         authority_pubkey,
         reward_mint_pubkey,
+        None,
         payer_pubkey,
-        pubkey_validity_proof_data_pubkey,
+        Some(pubkey_validity_proof_data_pubkey),
         pull_id,
         encrypted_amount_array,
+        tier,
         decryptable_zero_balance_array,
     );
 
@@ -102,8 +109,10 @@ async fn test_create_pull() -> Result<()> {
 
     let pull = env.get_pull(pull_id).await?;
     assert_eq!(pull.id, pull_id);
+    assert_eq!(pull.game_config, env.game_config_pubkey());
     assert_eq!(pull.reward_vault, reward_vault_pubkey);
     assert_eq!(pull.encrypted_amount, encrypted_amount_array);
+    assert_eq!(pull.tier, tier);
     assert_eq!(pull.buyer, Pubkey::default());
     assert_eq!(pull.verified, false);
     assert_eq!(pull.claimed, false);
@@ -112,6 +121,71 @@ async fn test_create_pull() -> Result<()> {
 
     let game_config = env.get_game_config().await?;
     assert_eq!(game_config.last_pull_id, pull.id);
+    // Unsold from creation until assigned to a buyer
+    assert_eq!(game_config.unsold_pulls_per_tier[tier as usize], 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_create_pull_with_proof_instruction() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+    let pull_id = env.get_game_config().await?.last_pull_id + 1;
+
+    let payer_pubkey = env.payer.pubkey();
+    let authority_pubkey = env.authority.pubkey();
+    let reward_mint_pubkey = env.reward_mint_pubkey();
+    let expected_amount = 200_000_000;
+    let tier = 0;
+
+    let pull_proof_account = SignerProofAccount::new();
+
+    let decryptable_zero_balance_base64 = pull_proof_account.encrypt_supply(0)?.to_string();
+    let encrypted_amount_base64 = pull_proof_account
+        .encrypt_amount_ciphertext(expected_amount)?
+        .to_string();
+
+    let mut decryptable_zero_balance_array = [0u8; AE_CIPHERTEXT_MAX_BASE64_LEN];
+    let mut encrypted_amount_array = [0u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN];
+    decryptable_zero_balance_array.copy_from_slice(decryptable_zero_balance_base64.as_bytes());
+    encrypted_amount_array.copy_from_slice(encrypted_amount_base64.as_bytes());
+
+    let pubkey_validity_proof_data =
+        PubkeyValidityProofData::new(&pull_proof_account.get_pod_elgamal_keypair()?)
+            .map_err(|_| anyhow::anyhow!("proof generation failed"))?;
+
+    // Proof is verified by the instruction right after create pull
+    let ix = instruction::CreatePull::populate(
+        env.game_id,
+        authority_pubkey,
+        reward_mint_pubkey,
+        None,
+        payer_pubkey,
+        None,
+        pull_id,
+        encrypted_amount_array,
+        tier,
+        decryptable_zero_balance_array,
+    );
+    let pubkey_proof_verify_ix = ProofInstruction::VerifyPubkeyValidity
+        .encode_verify_proof(None, &pubkey_validity_proof_data);
+
+    let tx = env
+        .process_instructions(
+            &[ix, pubkey_proof_verify_ix],
+            &vec![&env.payer, &env.authority],
+            None,
+        )
+        .await?;
+
+    println!("create pull tx: {}", tx);
+
+    let pull = env.get_pull(pull_id).await?;
+    assert_eq!(pull.id, pull_id);
+    assert_eq!(pull.encrypted_amount, encrypted_amount_array);
 
     Ok(())
 }
@@ -0,0 +1,145 @@
+use anyhow::Result;
+use gacha_sol::{
+    instruction,
+    sdk::CREATE_PULLS_BATCH_SIZE,
+    state::{NewPull, AE_CIPHERTEXT_MAX_BASE64_LEN, ELGAMAL_PUBKEY_MAX_BASE64_LEN},
+};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use spl_token_2022::extension::confidential_transfer::instruction::PubkeyValidityProofData;
+
+use crate::test_utils::{
+    confidential_transfer::get_zk_proof_context_state_account_creation_instructions,
+    gacha_sol_test_environment::GachaSolTestEnvironment,
+    proof_account::{ProofAccount, SignerProofAccount},
+};
+
+#[tokio::test]
+async fn test_create_pulls() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+    let first_pull_id = env.get_game_config().await?.last_pull_id + 1;
+
+    let payer_pubkey = env.payer.pubkey();
+    let authority_pubkey = env.authority.pubkey();
+    let reward_mint_pubkey = env.reward_mint_pubkey();
+
+    // Every reward vault of the batch is configured with the same key
+    let pull_proof_account = SignerProofAccount::new();
+
+    let mut decryptable_zero_balance_array = [0u8; AE_CIPHERTEXT_MAX_BASE64_LEN];
+    decryptable_zero_balance_array
+        .copy_from_slice(pull_proof_account.encrypt_supply(0)?.to_string().as_bytes());
+
+    let pull_count = CREATE_PULLS_BATCH_SIZE + 2;
+    let new_pulls = (0..pull_count)
+        .map(|index| {
+            let encrypted_amount = pull_proof_account
+                .encrypt_amount_ciphertext(100_000_000 * (index as u64 + 1))?
+                .to_string();
+            let mut encrypted_amount_array = [0u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN];
+            encrypted_amount_array.copy_from_slice(encrypted_amount.as_bytes());
+            Ok(NewPull {
+                encrypted_amount: encrypted_amount_array,
+                tier: (index % 3) as u8,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let pubkey_validity_proof_data_account = Keypair::new();
+    let pubkey_validity_proof_data_pubkey = pubkey_validity_proof_data_account.pubkey();
+
+    let pubkey_validity_proof_data =
+        PubkeyValidityProofData::new(&pull_proof_account.get_pod_elgamal_keypair()?)
+            .map_err(|_| anyhow::anyhow!("proof generation failed"))?;
+    let (pubkey_proof_create_ix, pubkey_proof_verify_ix) =
+        get_zk_proof_context_state_account_creation_instructions(
+            &payer_pubkey,
+            &pubkey_validity_proof_data_pubkey,
+            &authority_pubkey,
+            &pubkey_validity_proof_data,
+        )?;
+
+    env.process_instructions(
+        &[pubkey_proof_create_ix, pubkey_proof_verify_ix],
+        &vec![&env.payer, &pubkey_validity_proof_data_account],
+        None,
+    )
+    .await?;
+
+    // === pull accounts must follow the pull ids
+
+    let mut ix = instruction::CreatePulls::populate(
+        env.game_id,
+        authority_pubkey,
+        reward_mint_pubkey,
+        None,
+        payer_pubkey,
+        Some(pubkey_validity_proof_data_pubkey),
+        first_pull_id,
+        new_pulls[..2].to_vec(),
+        decryptable_zero_balance_array,
+    );
+    ix.accounts
+        .swap(ix.accounts.len() - 4, ix.accounts.len() - 2);
+
+    let result = env
+        .process_instructions(&[ix], &vec![&env.payer, &env.authority], None)
+        .await;
+
+    assert!(result.is_err());
+
+    // === create pulls in batches, addresses funded beforehand are still created
+
+    let first_pull_pubkey = env.pull_pubkey(first_pull_id);
+    env.transfer_lamports(&first_pull_pubkey, 1_000_000).await?;
+    env.transfer_lamports(&env.reward_vault_pubkey(first_pull_pubkey), 1_000_000)
+        .await?;
+
+    let ixs = instruction::CreatePulls::populate_batches(
+        env.game_id,
+        authority_pubkey,
+        reward_mint_pubkey,
+        None,
+        payer_pubkey,
+        Some(pubkey_validity_proof_data_pubkey),
+        first_pull_id,
+        &new_pulls,
+        decryptable_zero_balance_array,
+    );
+
+    assert_eq!(ixs.len(), 2);
+
+    for ix in ixs {
+        let tx = env
+            .process_instructions(&[ix], &vec![&env.payer, &env.authority], None)
+            .await?;
+
+        println!("create pulls tx: {}", tx);
+    }
+
+    for (index, new_pull) in new_pulls.iter().enumerate() {
+        let pull_id = first_pull_id + index as u64;
+        let pull = env.get_pull(pull_id).await?;
+
+        assert_eq!(pull.id, pull_id);
+        assert_eq!(pull.game_config, env.game_config_pubkey());
+        assert_eq!(
+            pull.reward_vault,
+            env.reward_vault_pubkey(env.pull_pubkey(pull_id))
+        );
+        assert_eq!(pull.encrypted_amount, new_pull.encrypted_amount);
+        assert_eq!(pull.tier, new_pull.tier);
+        assert_eq!(pull.buyer, Pubkey::default());
+        assert_eq!(pull.verified, false);
+    }
+
+    let game_config = env.get_game_config().await?;
+    assert_eq!(
+        game_config.last_pull_id,
+        first_pull_id + pull_count as u64 - 1
+    );
+
+    Ok(())
+}
@@ -1,8 +1,12 @@
 use anyhow::Result;
-use gacha_sol::instruction;
+use gacha_sol::{
+    instruction,
+    pda::get_game_config_pubkey,
+    state::{GameConfig, PaymentMode},
+};
 use solana_sdk::signer::Signer;
 
-use crate::test_utils::gacha_sol_test_environment::GachaSolTestEnvironment;
+use crate::test_utils::gacha_sol_test_environment::{GachaSolTestEnvironment, REFUND_TIMEOUT};
 
 #[tokio::test]
 async fn test_initialize_game_config() -> Result<()> {
@@ -16,12 +20,16 @@ async fn test_initialize_game_config() -> Result<()> {
     let pull_price = 1001234;
 
     let ix = instruction::InitializeGameConfig::populate(
+        env.game_id,
         authority_pubkey,
         purchase_mint_pubkey,
         reward_mint_pubkey,
-        game_vault_pubkey,
         env.payer.pubkey(),
         pull_price,
+        REFUND_TIMEOUT,
+        0,
+        0,
+        PaymentMode::Token,
     );
 
     let tx = env.process_instruction(ix, &vec![&env.payer], None).await?;
@@ -30,12 +38,70 @@ async fn test_initialize_game_config() -> Result<()> {
 
     let game_config = env.get_game_config().await?;
 
+    assert_eq!(game_config.game_id, env.game_id);
     assert_eq!(game_config.authority, authority_pubkey);
     assert_eq!(game_config.purchase_mint, purchase_mint_pubkey);
     assert_eq!(game_config.reward_mint, reward_mint_pubkey);
     assert_eq!(game_config.game_vault, game_vault_pubkey);
     assert_eq!(game_config.pull_price, pull_price);
+    assert_eq!(game_config.refund_timeout, REFUND_TIMEOUT);
+    assert_eq!(game_config.escrowed_amount, 0);
+    assert_eq!(game_config.released_amount, 0);
+    assert_eq!(game_config.withdrawn_amount, 0);
     assert_eq!(game_config.last_pull_id, 0);
+    assert_eq!(game_config.sale_start, 0);
+    assert_eq!(game_config.sale_end, 0);
+
+    // Token games leave the native SOL treasury out
+    assert!(env.get_lamports(&env.treasury_pubkey()).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_initialize_multiple_game_configs() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    env.initialize_game_config(100_000_000).await?;
+
+    let other_game_id = env.game_id + 1;
+    let other_pull_price = 200_000_000;
+
+    let ix = instruction::InitializeGameConfig::populate(
+        other_game_id,
+        env.authority.pubkey(),
+        env.purchase_mint_pubkey(),
+        env.reward_mint_pubkey(),
+        env.payer.pubkey(),
+        other_pull_price,
+        REFUND_TIMEOUT,
+        0,
+        0,
+        PaymentMode::Token,
+    );
+
+    let tx = env.process_instruction(ix, &vec![&env.payer], None).await?;
+
+    println!("initialize other game config tx: {}", tx);
+
+    let game_config = env.get_game_config().await?;
+    let other_game_config: GameConfig = {
+        let mut test_fixtures = env.test_fixtures.lock().unwrap();
+        test_fixtures
+            .program_simulator
+            .get_anchor_account_data(get_game_config_pubkey(other_game_id))
+            .await?
+    };
+
+    assert_ne!(
+        env.game_config_pubkey(),
+        get_game_config_pubkey(other_game_id)
+    );
+    assert_eq!(game_config.game_id, env.game_id);
+    assert_eq!(game_config.pull_price, 100_000_000);
+    assert_eq!(other_game_config.game_id, other_game_id);
+    assert_eq!(other_game_config.pull_price, other_pull_price);
+    assert_ne!(game_config.game_vault, other_game_config.game_vault);
 
     Ok(())
 }
@@ -1,7 +1,25 @@
+mod accept_authority;
 mod apply_pull_pending_balance;
 mod basic;
 mod buy_pull;
+mod buy_pulls;
+mod cancel_ticket;
+mod claim_pull;
+mod claim_pull_confidential;
+mod close_pull;
+mod commit_prize_table;
 mod create_pull;
+mod create_pulls;
 mod initialize_game_config;
 mod open_pull;
+mod pause_game;
+mod propose_authority;
+mod reclaim_pull;
+mod refund_pull;
+mod resume_game;
+mod reveal_pull;
+mod set_fee_schedule;
+mod update_game_config;
 mod verify_pull;
+mod verify_pull_transfer;
+mod withdraw_proceeds;
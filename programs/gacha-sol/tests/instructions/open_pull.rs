@@ -2,9 +2,13 @@ use anyhow::Result;
 use gacha_sol::{instruction, state::AE_CIPHERTEXT_MAX_BASE64_LEN};
 use solana_sdk::{signature::Keypair, signer::Signer};
 use spl_token_2022::{
-    extension::confidential_transfer::account_info::WithdrawAccountInfo,
-    solana_zk_sdk::encryption::pod::auth_encryption::PodAeCiphertext, ui_amount_to_amount,
+    extension::confidential_transfer::{
+        account_info::WithdrawAccountInfo, instruction::ZeroCiphertextProofData,
+    },
+    solana_zk_sdk::encryption::pod::auth_encryption::PodAeCiphertext,
+    ui_amount_to_amount,
 };
+use spl_token_confidential_transfer_ciphertext_arithmetic::subtract_from;
 use spl_token_confidential_transfer_proof_generation::withdraw::{
     withdraw_proof_data, WithdrawProofData,
 };
@@ -111,18 +115,48 @@ async fn test_open_pull() -> Result<()> {
     env.mint_purchase_token(&buyer_purchase_token_account, mint_amount)
         .await?;
 
-    env.buy_pull(&buyer, &buyer_purchase_token_account, pull_id)
-        .await?;
+    let bought_pull_id = env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+
+    assert_eq!(bought_pull_id, pull_id);
 
     let pull = env.get_pull(pull_id).await?;
 
     assert_eq!(pull.buyer, buyer_pubkey);
 
-    // === open pull
+    // === partial withdraw is rejected
+
+    let partial_withdraw_proofs = env
+        .create_withdraw_proofs(pull_id, pull_proof_account.clone(), expected_amount / 2)
+        .await?;
 
     let reward_mint_pubkey = env.reward_mint_pubkey();
     let buyer_reward_token_account = env.create_ata(&reward_mint_pubkey, &buyer_pubkey).await?;
 
+    let ix = instruction::OpenPull::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_reward_token_account,
+        reward_mint_pubkey,
+        None,
+        Some(partial_withdraw_proofs.equality_proof),
+        Some(partial_withdraw_proofs.range_proof),
+        Some(partial_withdraw_proofs.zero_balance_proof),
+        None,
+        env.authority.pubkey(),
+        pull_id,
+        partial_withdraw_proofs.amount,
+        env.decimals,
+        partial_withdraw_proofs.new_decryptable_available_balance,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === open pull
+
     let (encrypted_available_balance, new_decryptable_available_balance, current_balance) = {
         let mut test_fixtures = env.test_fixtures.lock().unwrap();
 
@@ -189,6 +223,26 @@ async fn test_open_pull() -> Result<()> {
             &equality_proof_data,
         )?;
 
+    // Zero Balance Proof Instructions-----------------------------------------------------------------------
+    let zero_ciphertext = subtract_from(&encrypted_available_balance, current_balance)
+        .ok_or_else(|| anyhow::anyhow!("zero balance ciphertext computation failed"))?;
+
+    let zero_proof_data = ZeroCiphertextProofData::new(
+        &pull_proof_account.get_pod_elgamal_keypair()?,
+        &zero_ciphertext.try_into()?,
+    )?;
+
+    let zero_proof_context_state_account = Keypair::new();
+    let zero_proof_pubkey = zero_proof_context_state_account.pubkey();
+
+    let (zero_proof_create_ix, zero_proof_verify_ix) =
+        get_zk_proof_context_state_account_creation_instructions(
+            &payer_pubkey,
+            &zero_proof_pubkey,
+            &authority_pubkey,
+            &zero_proof_data,
+        )?;
+
     let proof_accounts_ixs = [
         range_create_ix,
         equality_create_ix,
@@ -209,6 +263,16 @@ async fn test_open_pull() -> Result<()> {
 
     println!("proof accounts tx: {}", proof_account_tx);
 
+    let zero_proof_account_tx = env
+        .process_instructions(
+            &[zero_proof_create_ix, zero_proof_verify_ix],
+            &vec![&zero_proof_context_state_account],
+            Some(&env.payer),
+        )
+        .await?;
+
+    println!("zero proof account tx: {}", zero_proof_account_tx);
+
     let decryptable_new_decryptable_available_balance_array = {
         let new_decryptable_available_balance_base64 =
             new_decryptable_available_balance.to_string();
@@ -229,11 +293,15 @@ async fn test_open_pull() -> Result<()> {
     };
 
     let ix = instruction::OpenPull::populate(
+        env.game_id,
         buyer_pubkey,
         buyer_reward_token_account,
         reward_mint_pubkey,
-        equality_proof_pubkey,
-        range_proof_pubkey,
+        None,
+        Some(equality_proof_pubkey),
+        Some(range_proof_pubkey),
+        Some(zero_proof_pubkey),
+        Some(payer_pubkey),
         authority_pubkey,
         pull_id,
         current_balance,
@@ -252,5 +320,22 @@ async fn test_open_pull() -> Result<()> {
     assert_eq!(pull.claimed, true);
     assert_eq!(pull.revealed_amount, current_balance);
 
+    // Purchase is released for withdrawal
+    let game_config = env.get_game_config().await?;
+
+    assert_eq!(game_config.escrowed_amount, 0);
+    assert_eq!(game_config.released_amount, pull_price);
+    assert_eq!(game_config.withdrawable_amount()?, pull_price);
+
+    let player_state = env.get_player_state(&buyer_pubkey).await?;
+
+    assert_eq!(player_state.pulls_bought, 1);
+    assert_eq!(player_state.pulls_opened, 1);
+
+    // Proof accounts are closed by the program
+    for proof_pubkey in [equality_proof_pubkey, range_proof_pubkey, zero_proof_pubkey] {
+        assert!(env.get_lamports(&proof_pubkey).await.is_err());
+    }
+
     Ok(())
 }
@@ -0,0 +1,149 @@
+use anyhow::Result;
+use gacha_sol::instruction;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use spl_token_2022::ui_amount_to_amount;
+
+use crate::test_utils::{
+    gacha_sol_test_environment::{GachaSolTestEnvironment, REFUND_TIMEOUT},
+    proof_account::SignerProofAccount,
+};
+
+#[tokio::test]
+async fn test_reclaim_pull() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let pull_proof_account = SignerProofAccount::new();
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    let pull_id = env
+        .create_verified_pull(pull_proof_account.clone(), expected_amount)
+        .await?;
+
+    // === buy pull
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let reward_mint_pubkey = env.reward_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+
+    env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+
+    // === authority publishes the withdraw proofs once the pull is sold
+
+    let withdraw_proofs = env
+        .create_published_withdraw_proofs(pull_id, pull_proof_account, expected_amount)
+        .await?;
+
+    let ix = instruction::PublishWithdrawProofs::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        withdraw_proofs.equality_proof,
+        withdraw_proofs.range_proof,
+        withdraw_proofs.zero_balance_proof,
+        pull_id,
+        withdraw_proofs.amount,
+        withdraw_proofs.new_decryptable_available_balance,
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    let authority_reward_token_account = env
+        .create_ata(&reward_mint_pubkey, &env.authority.pubkey())
+        .await?;
+
+    // === pull not refunded cannot be reclaimed
+
+    let ix = instruction::ReclaimPull::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        authority_reward_token_account,
+        reward_mint_pubkey,
+        withdraw_proofs.equality_proof,
+        withdraw_proofs.range_proof,
+        withdraw_proofs.zero_balance_proof,
+        pull_id,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === refund after the timeout
+
+    let pull = env.get_pull(pull_id).await?;
+    env.warp_to_timestamp(pull.purchased_at + REFUND_TIMEOUT)
+        .await?;
+
+    let ix = instruction::RefundPull::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_purchase_token_account,
+        purchase_mint_pubkey,
+        pull_id,
+    );
+
+    env.process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await?;
+
+    // === reclaim the reward of the refunded pull
+
+    let ix = instruction::ReclaimPull::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        authority_reward_token_account,
+        reward_mint_pubkey,
+        withdraw_proofs.equality_proof,
+        withdraw_proofs.range_proof,
+        withdraw_proofs.zero_balance_proof,
+        pull_id,
+    );
+
+    let tx = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    println!("reclaim pull tx: {}", tx);
+
+    assert!(env.get_pull(pull_id).await?.reclaimed);
+    assert_eq!(
+        env.get_token_balance(&authority_reward_token_account)
+            .await?,
+        expected_amount
+    );
+
+    // === reclaimed pull can be closed
+
+    let pull_pubkey = env.pull_pubkey(pull_id);
+    let reward_vault_pubkey = env.reward_vault_pubkey(pull_pubkey);
+    let rent_receiver = Keypair::new().pubkey();
+
+    let ix = instruction::ClosePull::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        withdraw_proofs.zero_balance_proof,
+        Some([withdraw_proofs.equality_proof, withdraw_proofs.range_proof]),
+        rent_receiver,
+        pull_id,
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    assert!(env.get_pull(pull_id).await.is_err());
+    assert!(env.get_lamports(&reward_vault_pubkey).await.is_err());
+    assert!(env
+        .get_lamports(&withdraw_proofs.equality_proof)
+        .await
+        .is_err());
+
+    Ok(())
+}
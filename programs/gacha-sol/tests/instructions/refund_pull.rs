@@ -0,0 +1,106 @@
+use anyhow::Result;
+use gacha_sol::instruction;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use spl_token_2022::ui_amount_to_amount;
+
+use crate::test_utils::{
+    gacha_sol_test_environment::{GachaSolTestEnvironment, REFUND_TIMEOUT},
+    proof_account::SignerProofAccount,
+};
+
+#[tokio::test]
+async fn test_refund_pull() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    env.create_verified_pull(SignerProofAccount::new(), expected_amount)
+        .await?;
+
+    // === buy pull
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+
+    let mint_amount = 200_000_000_000;
+    env.mint_purchase_token(&buyer_purchase_token_account, mint_amount)
+        .await?;
+
+    let pull_id = env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+
+    let pull = env.get_pull(pull_id).await?;
+
+    assert_eq!(pull.purchase_price, pull_price);
+    assert_eq!(
+        env.get_token_balance(&buyer_purchase_token_account).await?,
+        mint_amount - pull_price
+    );
+
+    // === refund before the timeout is rejected
+
+    let ix = instruction::RefundPull::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_purchase_token_account,
+        purchase_mint_pubkey,
+        pull_id,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === refund after the timeout
+
+    env.warp_to_timestamp(pull.purchased_at + REFUND_TIMEOUT)
+        .await?;
+
+    let ix = instruction::RefundPull::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_purchase_token_account,
+        purchase_mint_pubkey,
+        pull_id,
+    );
+
+    let tx = env
+        .process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await?;
+
+    println!("refund pull tx: {}", tx);
+
+    let pull = env.get_pull(pull_id).await?;
+
+    assert!(pull.refunded);
+    assert!(!pull.claimed);
+    assert_eq!(
+        env.get_token_balance(&buyer_purchase_token_account).await?,
+        mint_amount
+    );
+    assert_eq!(env.get_token_balance(&env.game_vault_pubkey()).await?, 0);
+    assert_eq!(env.get_game_config().await?.escrowed_amount, 0);
+
+    // === second refund is rejected
+
+    let ix = instruction::RefundPull::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_purchase_token_account,
+        purchase_mint_pubkey,
+        pull_id,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    Ok(())
+}
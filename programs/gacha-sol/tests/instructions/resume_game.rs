@@ -0,0 +1,34 @@
+use anyhow::Result;
+use spl_token_2022::ui_amount_to_amount;
+
+use crate::test_utils::{
+    gacha_sol_test_environment::GachaSolTestEnvironment, proof_account::SignerProofAccount,
+};
+
+#[tokio::test]
+async fn test_resume_game() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    env.pause_game().await?;
+    env.resume_game().await?;
+
+    let game_config = env.get_game_config().await?;
+
+    assert!(!game_config.paused);
+
+    // === create pull works again once resumed
+
+    let pull_id = game_config.last_pull_id + 1;
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+
+    env.create_pull(pull_id, SignerProofAccount::new(), expected_amount)
+        .await?;
+
+    let game_config = env.get_game_config().await?;
+    assert_eq!(game_config.last_pull_id, pull_id);
+
+    Ok(())
+}
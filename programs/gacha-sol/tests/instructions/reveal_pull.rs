@@ -0,0 +1,327 @@
+use anyhow::Result;
+use gacha_sol::{
+    instruction,
+    state::{
+        PaymentMode, PullBundle, UpdateGameConfigParams, MAX_BUNDLE_SIZE, MAX_PULL_BUNDLES,
+        PULL_POOL_CAPACITY,
+    },
+};
+use solana_sdk::{signature::Keypair, signer::Signer};
+use spl_token_2022::ui_amount_to_amount;
+
+use crate::test_utils::{
+    gacha_sol_test_environment::GachaSolTestEnvironment, proof_account::SignerProofAccount,
+};
+
+#[tokio::test]
+async fn test_reveal_pull() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    let first_pull_id = env
+        .create_verified_pull(SignerProofAccount::new(), expected_amount)
+        .await?;
+    let second_pull_id = env
+        .create_verified_pull(SignerProofAccount::new(), expected_amount)
+        .await?;
+
+    // === buy pull ticket
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+
+    let ix = instruction::BuyPull::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_purchase_token_account,
+        purchase_mint_pubkey,
+        None,
+        PaymentMode::Token,
+        None,
+    );
+
+    env.process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await?;
+
+    let selected_pull_id = env.get_selected_pull_id(&buyer_pubkey).await?;
+    let ticket_lamports = env
+        .get_lamports(&env.pull_ticket_pubkey(&buyer_pubkey))
+        .await?;
+    let buyer_lamports = env.get_lamports(&buyer_pubkey).await?;
+
+    // === a pull other than the selected one is rejected
+
+    let other_pull_id = if selected_pull_id == first_pull_id {
+        second_pull_id
+    } else {
+        first_pull_id
+    };
+
+    let ix = instruction::RevealPull::populate(env.game_id, buyer_pubkey, other_pull_id);
+
+    let result = env.process_instruction(ix, &vec![&env.payer], None).await;
+
+    assert!(result.is_err());
+
+    // === anyone can reveal, the selected pull is assigned to the buyer
+
+    let pull_id = env.reveal_pull(&buyer_pubkey).await?;
+
+    assert_eq!(pull_id, selected_pull_id);
+
+    let pull = env.get_pull(pull_id).await?;
+
+    assert_eq!(pull.buyer, buyer_pubkey);
+
+    // The assigned pull is no longer unsold, the other one still is
+    let game_config = env.get_game_config().await?;
+    assert_eq!(game_config.unsold_pulls_per_tier[0], 1);
+
+    let pull_pool = env.get_pull_pool().await?;
+
+    assert_eq!(pull_pool.pending_tickets, 0);
+    assert_eq!(pull_pool.pull_ids, vec![other_pull_id]);
+
+    // Ticket is closed once revealed, its rent goes back to the buyer
+    assert!(env.get_pull_ticket(&buyer_pubkey).await.is_err());
+    assert_eq!(
+        env.get_lamports(&buyer_pubkey).await?,
+        buyer_lamports + ticket_lamports
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reveal_pull_pity() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    // === guarantee a tier 1 pull after a single miss
+
+    let pity_tier = 1;
+    let ix = instruction::UpdateGameConfig::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        None,
+        None,
+        UpdateGameConfigParams {
+            pity_threshold: Some(1),
+            pity_tier: Some(pity_tier),
+            ..Default::default()
+        },
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    for _ in 0..2 {
+        env.create_verified_pull(SignerProofAccount::new(), expected_amount)
+            .await?;
+    }
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+
+    // Only tier 0 pulls are in the pool, the first pull is a miss
+    let pull_id = env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+
+    assert_eq!(env.get_pull(pull_id).await?.tier, 0);
+
+    let player_state = env.get_player_state(&buyer_pubkey).await?;
+
+    assert_eq!(player_state.pulls_bought, 1);
+    assert_eq!(player_state.pulls_since_rare, 1);
+
+    // === next pull comes from the pity tier despite tier 0 pulls left in the pool
+
+    env.create_verified_pull(SignerProofAccount::new(), expected_amount)
+        .await?;
+    let rare_pull_id = env
+        .create_verified_pull_with_tier(SignerProofAccount::new(), expected_amount, pity_tier)
+        .await?;
+
+    let pull_id = env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+
+    assert_eq!(pull_id, rare_pull_id);
+    assert_eq!(env.get_pull(pull_id).await?.tier, pity_tier);
+
+    let player_state = env.get_player_state(&buyer_pubkey).await?;
+
+    assert_eq!(player_state.pulls_bought, 2);
+    assert_eq!(player_state.pulls_opened, 0);
+    assert_eq!(player_state.pulls_since_rare, 0);
+
+    let pull_pool = env.get_pull_pool().await?;
+
+    assert_eq!(pull_pool.pull_ids.len(), 2);
+    assert_eq!(pull_pool.pull_pity, vec![false, false]);
+
+    // === guaranteed pull is rejected at purchase while no pity tier pull is in the pool
+
+    env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+
+    let player_state = env.get_player_state(&buyer_pubkey).await?;
+
+    assert_eq!(player_state.pulls_since_rare, 1);
+
+    let result = env.buy_pull(&buyer, &buyer_purchase_token_account).await;
+
+    assert!(result.is_err());
+
+    let pull_pool = env.get_pull_pool().await?;
+
+    assert_eq!(pull_pool.pending_tickets, 0);
+    assert_eq!(pull_pool.pending_pity_draws, 0);
+
+    // === pity tier pull reserved by a guaranteed ticket is left to its buyer
+
+    let rare_pull_id = env
+        .create_verified_pull_with_tier(SignerProofAccount::new(), expected_amount, pity_tier)
+        .await?;
+
+    let ix = instruction::BuyPull::populate(
+        env.game_id,
+        buyer_pubkey,
+        buyer_purchase_token_account,
+        purchase_mint_pubkey,
+        None,
+        PaymentMode::Token,
+        None,
+    );
+
+    env.process_instruction(ix, &vec![&buyer], Some(&env.payer))
+        .await?;
+
+    let pull_ticket = env.get_pull_ticket(&buyer_pubkey).await?;
+
+    assert_eq!(pull_ticket.pity_draws, 1);
+    assert_eq!(env.get_pull_pool().await?.pending_pity_draws, 1);
+
+    let other_buyer = Keypair::new();
+    let other_buyer_purchase_token_account = env
+        .create_ata(&purchase_mint_pubkey, &other_buyer.pubkey())
+        .await?;
+
+    env.mint_purchase_token(&other_buyer_purchase_token_account, pull_price)
+        .await?;
+
+    let other_pull_id = env
+        .buy_pull(&other_buyer, &other_buyer_purchase_token_account)
+        .await?;
+
+    assert_ne!(other_pull_id, rare_pull_id);
+    assert_eq!(env.reveal_pull(&buyer_pubkey).await?, rare_pull_id);
+    assert_eq!(env.get_pull_pool().await?.pending_pity_draws, 0);
+
+    // === pity settings are locked once pulls are created
+
+    let ix = instruction::UpdateGameConfig::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        None,
+        None,
+        UpdateGameConfigParams {
+            pity_tier: Some(0),
+            ..Default::default()
+        },
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reveal_pull_bundle_full_pool() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let mut bundles = [PullBundle::default(); MAX_PULL_BUNDLES];
+    bundles[0] = PullBundle {
+        size: MAX_BUNDLE_SIZE,
+        price: pull_price * MAX_BUNDLE_SIZE as u64,
+    };
+
+    let ix = instruction::UpdateGameConfig::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        None,
+        None,
+        UpdateGameConfigParams {
+            bundles: Some(bundles),
+            ..Default::default()
+        },
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    // === fill the pool to capacity
+
+    env.create_verified_empty_pulls(PULL_POOL_CAPACITY).await?;
+
+    assert_eq!(
+        env.get_pull_pool().await?.pull_ids.len(),
+        PULL_POOL_CAPACITY
+    );
+
+    let result = env.create_verified_pull(SignerProofAccount::new(), 0).await;
+
+    assert!(result.is_err());
+
+    // === the largest bundle is revealed against the full pool within the default limits
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+
+    let pull_ids = env
+        .buy_pulls(&buyer, &buyer_purchase_token_account, MAX_BUNDLE_SIZE)
+        .await?;
+
+    assert_eq!(pull_ids.len(), MAX_BUNDLE_SIZE as usize);
+
+    for pull_id in pull_ids.iter() {
+        assert_eq!(env.get_pull(*pull_id).await?.buyer, buyer_pubkey);
+    }
+
+    let pull_pool = env.get_pull_pool().await?;
+
+    assert_eq!(pull_pool.pending_tickets, 0);
+    assert_eq!(
+        pull_pool.pull_ids.len(),
+        PULL_POOL_CAPACITY - MAX_BUNDLE_SIZE as usize
+    );
+    assert!(pull_ids
+        .iter()
+        .all(|pull_id| !pull_pool.pull_ids.contains(pull_id)));
+
+    Ok(())
+}
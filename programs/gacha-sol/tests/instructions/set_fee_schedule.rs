@@ -0,0 +1,183 @@
+use anyhow::Result;
+use gacha_sol::{instruction, state::FeeRecipient};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use spl_token_2022::ui_amount_to_amount;
+
+use crate::test_utils::{
+    gacha_sol_test_environment::GachaSolTestEnvironment, proof_account::SignerProofAccount,
+};
+
+#[tokio::test]
+async fn test_set_fee_schedule() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    let pull_proof_account = SignerProofAccount::new();
+    env.create_verified_pull(pull_proof_account.clone(), expected_amount)
+        .await?;
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let authority_pubkey = env.authority.pubkey();
+    let studio_purchase_token_account = env
+        .create_ata(&purchase_mint_pubkey, &Pubkey::new_unique())
+        .await?;
+    let ip_holder_purchase_token_account = env
+        .create_ata(&purchase_mint_pubkey, &Pubkey::new_unique())
+        .await?;
+    let authority_purchase_token_account = env
+        .create_ata(&purchase_mint_pubkey, &authority_pubkey)
+        .await?;
+    let game_vault_pubkey = env.game_vault_pubkey();
+
+    // === shares not summing to 10000 bps are rejected
+
+    let result = env
+        .set_fee_schedule(vec![
+            FeeRecipient {
+                token_account: studio_purchase_token_account,
+                bps: 5_000,
+            },
+            FeeRecipient {
+                token_account: ip_holder_purchase_token_account,
+                bps: 3_000,
+            },
+        ])
+        .await;
+
+    assert!(result.is_err());
+
+    // === the game vault cannot be a recipient
+
+    let result = env
+        .set_fee_schedule(vec![
+            FeeRecipient {
+                token_account: studio_purchase_token_account,
+                bps: 8_000,
+            },
+            FeeRecipient {
+                token_account: game_vault_pubkey,
+                bps: 2_000,
+            },
+        ])
+        .await;
+
+    assert!(result.is_err());
+
+    // === set fee schedule
+
+    let recipients = vec![
+        FeeRecipient {
+            token_account: studio_purchase_token_account,
+            bps: 5_000,
+        },
+        FeeRecipient {
+            token_account: ip_holder_purchase_token_account,
+            bps: 3_000,
+        },
+        FeeRecipient {
+            token_account: authority_purchase_token_account,
+            bps: 2_000,
+        },
+    ];
+    env.set_fee_schedule(recipients.clone()).await?;
+
+    let fee_schedule = env.get_fee_schedule().await?;
+    assert_eq!(fee_schedule.game_config, env.game_config_pubkey());
+    assert_eq!(fee_schedule.recipients, recipients);
+
+    let game_config = env.get_game_config().await?;
+    assert_eq!(game_config.fee_schedule, env.fee_schedule_pubkey());
+
+    // === the full price is escrowed at purchase
+
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+    env.mint_purchase_token(&buyer_purchase_token_account, pull_price)
+        .await?;
+
+    let pull_id = env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+
+    assert_eq!(env.get_token_balance(&game_vault_pubkey).await?, pull_price);
+    assert_eq!(env.get_game_config().await?.escrowed_amount, pull_price);
+    assert_eq!(
+        env.get_token_balance(&studio_purchase_token_account)
+            .await?,
+        0
+    );
+
+    env.open_pull(pull_id, pull_proof_account, &buyer_pubkey, expected_amount)
+        .await?;
+
+    // === withdrawing to a single destination is rejected
+
+    let ix = instruction::WithdrawProceeds::populate(
+        env.game_id,
+        authority_pubkey,
+        purchase_mint_pubkey,
+        authority_purchase_token_account,
+        pull_price,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === withdrawal without the fee recipients is rejected
+
+    let ix = instruction::WithdrawProceeds::populate_fee_schedule(
+        env.game_id,
+        authority_pubkey,
+        purchase_mint_pubkey,
+        &[],
+        pull_price,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === proceeds are split at withdrawal
+
+    let fee_recipients: Vec<Pubkey> = recipients
+        .iter()
+        .map(|recipient| recipient.token_account)
+        .collect();
+    let ix = instruction::WithdrawProceeds::populate_fee_schedule(
+        env.game_id,
+        authority_pubkey,
+        purchase_mint_pubkey,
+        &fee_recipients,
+        pull_price,
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    assert_eq!(
+        env.get_token_balance(&studio_purchase_token_account)
+            .await?,
+        50_000_000
+    );
+    assert_eq!(
+        env.get_token_balance(&ip_holder_purchase_token_account)
+            .await?,
+        30_000_000
+    );
+    assert_eq!(
+        env.get_token_balance(&authority_purchase_token_account)
+            .await?,
+        20_000_000
+    );
+    assert_eq!(env.get_token_balance(&game_vault_pubkey).await?, 0);
+    assert_eq!(env.get_game_config().await?.withdrawn_amount, pull_price);
+
+    Ok(())
+}
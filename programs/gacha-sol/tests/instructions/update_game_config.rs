@@ -0,0 +1,148 @@
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anyhow::Result;
+use gacha_sol::{accounts, instruction, pda::get_game_vault_pubkey, state::UpdateGameConfigParams};
+use solana_sdk::{instruction::Instruction, signature::Keypair, signer::Signer};
+
+use crate::test_utils::gacha_sol_test_environment::GachaSolTestEnvironment;
+
+#[tokio::test]
+async fn test_update_game_config() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let authority_pubkey = env.authority.pubkey();
+
+    // === update pull price
+
+    let new_pull_price = 150_000_000;
+
+    let ix = instruction::UpdateGameConfig::populate(
+        env.game_id,
+        authority_pubkey,
+        None,
+        None,
+        UpdateGameConfigParams {
+            pull_price: Some(new_pull_price),
+            ..Default::default()
+        },
+    );
+
+    let tx = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    println!("update pull price tx: {}", tx);
+
+    let game_config = env.get_game_config().await?;
+
+    assert_eq!(game_config.pull_price, new_pull_price);
+    assert_eq!(game_config.purchase_mint, env.purchase_mint_pubkey());
+    assert_eq!(game_config.game_vault, env.game_vault_pubkey());
+
+    // === update purchase mint, the program creates the new game vault
+
+    let new_purchase_mint_authority = Keypair::new();
+    let new_purchase_mint_pubkey = {
+        let mut test_fixtures = env.test_fixtures.lock().unwrap();
+        test_fixtures
+            .create_mint(&new_purchase_mint_authority.pubkey(), env.decimals)
+            .await?
+    };
+    let new_game_vault_pubkey =
+        get_game_vault_pubkey(env.game_config_pubkey(), new_purchase_mint_pubkey);
+
+    let ix = instruction::UpdateGameConfig::populate(
+        env.game_id,
+        authority_pubkey,
+        Some(new_purchase_mint_pubkey),
+        None,
+        UpdateGameConfigParams::default(),
+    );
+
+    let tx = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    println!("update purchase mint tx: {}", tx);
+
+    let game_config = env.get_game_config().await?;
+
+    assert_eq!(game_config.pull_price, new_pull_price);
+    assert_eq!(game_config.purchase_mint, new_purchase_mint_pubkey);
+    assert_eq!(game_config.reward_mint, env.reward_mint_pubkey());
+    assert_eq!(game_config.game_vault, new_game_vault_pubkey);
+    assert_eq!(env.get_token_balance(&new_game_vault_pubkey).await?, 0);
+
+    // === purchase mint with a vault not derived by the program is rejected
+
+    let other_game_vault_pubkey = env
+        .create_ata(&env.purchase_mint_pubkey(), &authority_pubkey)
+        .await?;
+
+    let mut update_game_config_accounts = accounts::UpdateGameConfig::populate(
+        env.game_id,
+        authority_pubkey,
+        Some(env.purchase_mint_pubkey()),
+        None,
+    );
+    update_game_config_accounts.game_vault = Some(other_game_vault_pubkey);
+
+    let ix = Instruction {
+        program_id: gacha_sol::ID,
+        accounts: update_game_config_accounts.to_account_metas(None),
+        data: instruction::UpdateGameConfig {
+            params: UpdateGameConfigParams::default(),
+        }
+        .data(),
+    };
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === switching back to the original purchase mint reuses its vault
+
+    let ix = instruction::UpdateGameConfig::populate(
+        env.game_id,
+        authority_pubkey,
+        Some(env.purchase_mint_pubkey()),
+        None,
+        UpdateGameConfigParams::default(),
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    let game_config = env.get_game_config().await?;
+
+    assert_eq!(game_config.purchase_mint, env.purchase_mint_pubkey());
+    assert_eq!(game_config.game_vault, env.game_vault_pubkey());
+
+    // === update refund timeout
+
+    let new_refund_timeout = 3_600;
+
+    let ix = instruction::UpdateGameConfig::populate(
+        env.game_id,
+        authority_pubkey,
+        None,
+        None,
+        UpdateGameConfigParams {
+            refund_timeout: Some(new_refund_timeout),
+            ..Default::default()
+        },
+    );
+
+    env.process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    let game_config = env.get_game_config().await?;
+
+    assert_eq!(game_config.refund_timeout, new_refund_timeout);
+
+    Ok(())
+}
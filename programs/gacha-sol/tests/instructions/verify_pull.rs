@@ -18,9 +18,7 @@ use spl_token_2022::{
 use spl_token_confidential_transfer_ciphertext_arithmetic::subtract;
 
 use crate::test_utils::{
-    confidential_transfer::{
-        create_close_context_state_ixs, get_zk_proof_context_state_account_creation_instructions,
-    },
+    confidential_transfer::get_zk_proof_context_state_account_creation_instructions,
     gacha_sol_test_environment::GachaSolTestEnvironment,
     proof_account::{ProofAccount, SignerProofAccount},
 };
@@ -186,8 +184,13 @@ async fn test_verify_pull() -> Result<()> {
 
     println!("proof accounts tx: {}", proof_account_tx);
 
-    let ix =
-        instruction::VerifyPull::populate(authority_pubkey, zero_ciphertext_proof_pubkey, pull_id);
+    let ix = instruction::VerifyPull::populate(
+        env.game_id,
+        authority_pubkey,
+        Some(zero_ciphertext_proof_pubkey),
+        Some(payer_pubkey),
+        pull_id,
+    );
 
     let tx = env
         .process_instructions(&[ix], &vec![&env.authority], None)
@@ -199,19 +202,15 @@ async fn test_verify_pull() -> Result<()> {
 
     assert_eq!(pull.verified, true);
 
-    // Close context states Instructions ---------------------------------------------------------------
-
-    let close_context_state_ixs = create_close_context_state_ixs(
-        &[zero_ciphertext_proof_pubkey],
-        &authority_pubkey,
-        &payer_pubkey,
-    );
-
-    let close_context_state_tx = env
-        .process_instructions(&close_context_state_ixs, &vec![&env.authority], None)
-        .await?;
+    let pull_pool = env.get_pull_pool().await?;
+    assert_eq!(pull_pool.pull_ids, vec![pull_id]);
+    assert_eq!(pull_pool.pull_pity, vec![false]);
 
-    println!("close context accounts tx: {}", close_context_state_tx);
+    // Proof account is closed by the program
+    assert!(env
+        .get_lamports(&zero_ciphertext_proof_pubkey)
+        .await
+        .is_err());
 
     Ok(())
 }
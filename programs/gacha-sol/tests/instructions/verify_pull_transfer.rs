@@ -0,0 +1,217 @@
+use anyhow::Result;
+use gacha_sol::{instruction, state::PrizeTier};
+use solana_sdk::{signature::Keypair, signer::Signer};
+use spl_token_2022::ui_amount_to_amount;
+
+use crate::test_utils::{
+    gacha_sol_test_environment::GachaSolTestEnvironment, proof_account::SignerProofAccount,
+};
+
+#[tokio::test]
+async fn test_verify_pull_transfer() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let pull_proof_account = SignerProofAccount::new();
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    let pull_id = env
+        .create_verified_pull(pull_proof_account.clone(), expected_amount)
+        .await?;
+
+    // === buy pull
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let reward_mint_pubkey = env.reward_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+
+    env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+
+    // === proofs to an account not owned by the buyer are rejected
+
+    let other = Keypair::new();
+    let other_token_proof_account = SignerProofAccount::new();
+    let other_reward_token_account = other_token_proof_account.pubkey();
+
+    env.create_ct_token_account(&reward_mint_pubkey, &other, other_token_proof_account)
+        .await?;
+
+    let other_transfer_proofs = env
+        .create_transfer_proofs(
+            pull_id,
+            pull_proof_account.clone(),
+            &other_reward_token_account,
+        )
+        .await?;
+
+    let ix = instruction::VerifyPullTransfer::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        other_reward_token_account,
+        reward_mint_pubkey,
+        other_transfer_proofs.equality_proof,
+        other_transfer_proofs.ciphertext_validity_proof,
+        other_transfer_proofs.range_proof,
+        other_transfer_proofs.zero_balance_proof,
+        pull_id,
+        other_transfer_proofs.transfer_amount_auditor_ciphertext_lo,
+        other_transfer_proofs.transfer_amount_auditor_ciphertext_hi,
+        other_transfer_proofs.new_decryptable_available_balance,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === verify transfer proofs to the buyer
+
+    let buyer_token_proof_account = SignerProofAccount::new();
+    let buyer_reward_token_account = buyer_token_proof_account.pubkey();
+
+    env.create_ct_token_account(&reward_mint_pubkey, &buyer, buyer_token_proof_account)
+        .await?;
+
+    let transfer_proofs = env
+        .create_transfer_proofs(pull_id, pull_proof_account, &buyer_reward_token_account)
+        .await?;
+
+    // Auditor ciphertexts must match the validity proof
+    let ix = instruction::VerifyPullTransfer::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        buyer_reward_token_account,
+        reward_mint_pubkey,
+        transfer_proofs.equality_proof,
+        transfer_proofs.ciphertext_validity_proof,
+        transfer_proofs.range_proof,
+        transfer_proofs.zero_balance_proof,
+        pull_id,
+        transfer_proofs.transfer_amount_auditor_ciphertext_hi,
+        transfer_proofs.transfer_amount_auditor_ciphertext_lo,
+        transfer_proofs.new_decryptable_available_balance,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    let ix = instruction::VerifyPullTransfer::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        buyer_reward_token_account,
+        reward_mint_pubkey,
+        transfer_proofs.equality_proof,
+        transfer_proofs.ciphertext_validity_proof,
+        transfer_proofs.range_proof,
+        transfer_proofs.zero_balance_proof,
+        pull_id,
+        transfer_proofs.transfer_amount_auditor_ciphertext_lo,
+        transfer_proofs.transfer_amount_auditor_ciphertext_hi,
+        transfer_proofs.new_decryptable_available_balance,
+    );
+
+    // Paused game rejects the proofs
+    env.pause_game().await?;
+
+    let result = env
+        .process_instruction(ix.clone(), &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    env.resume_game().await?;
+
+    let tx = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    println!("verify pull transfer tx: {}", tx);
+
+    let pull = env.get_pull(pull_id).await?;
+
+    assert!(pull.transfer_proofs_verified);
+    assert_eq!(pull.transfer_equality_proof, transfer_proofs.equality_proof);
+    assert_eq!(
+        pull.transfer_ciphertext_validity_proof,
+        transfer_proofs.ciphertext_validity_proof
+    );
+    assert_eq!(pull.transfer_range_proof, transfer_proofs.range_proof);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_verify_pull_transfer_with_prize_table() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+    env.commit_prize_table(vec![PrizeTier {
+        count: 1,
+        min_amount: ui_amount_to_amount(50.0, env.decimals),
+        max_amount: ui_amount_to_amount(150.0, env.decimals),
+    }])
+    .await?;
+
+    let pull_proof_account = SignerProofAccount::new();
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    let pull_id = env
+        .create_verified_pull(pull_proof_account.clone(), expected_amount)
+        .await?;
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let reward_mint_pubkey = env.reward_mint_pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+
+    env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+
+    let buyer_token_proof_account = SignerProofAccount::new();
+    let buyer_reward_token_account = buyer_token_proof_account.pubkey();
+
+    env.create_ct_token_account(&reward_mint_pubkey, &buyer, buyer_token_proof_account)
+        .await?;
+
+    // === confidential delivery cannot be checked against the prize table
+
+    let transfer_proofs = env
+        .create_transfer_proofs(pull_id, pull_proof_account, &buyer_reward_token_account)
+        .await?;
+
+    let ix = instruction::VerifyPullTransfer::populate(
+        env.game_id,
+        env.authority.pubkey(),
+        buyer_reward_token_account,
+        reward_mint_pubkey,
+        transfer_proofs.equality_proof,
+        transfer_proofs.ciphertext_validity_proof,
+        transfer_proofs.range_proof,
+        transfer_proofs.zero_balance_proof,
+        pull_id,
+        transfer_proofs.transfer_amount_auditor_ciphertext_lo,
+        transfer_proofs.transfer_amount_auditor_ciphertext_hi,
+        transfer_proofs.new_decryptable_available_balance,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    Ok(())
+}
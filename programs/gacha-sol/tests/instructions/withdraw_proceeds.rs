@@ -0,0 +1,99 @@
+use anyhow::Result;
+use gacha_sol::instruction;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use spl_token_2022::ui_amount_to_amount;
+
+use crate::test_utils::{
+    gacha_sol_test_environment::GachaSolTestEnvironment, proof_account::SignerProofAccount,
+};
+
+#[tokio::test]
+async fn test_withdraw_proceeds() -> Result<()> {
+    let env = GachaSolTestEnvironment::new().await?;
+
+    let pull_price = 100_000_000;
+    env.initialize_game_config(pull_price).await?;
+
+    let pull_proof_account = SignerProofAccount::new();
+    let expected_amount = ui_amount_to_amount(100.0, env.decimals);
+    let pull_id = env
+        .create_verified_pull(pull_proof_account.clone(), expected_amount)
+        .await?;
+
+    // === buy pull
+
+    let purchase_mint_pubkey = env.purchase_mint_pubkey();
+    let authority_pubkey = env.authority.pubkey();
+    let buyer = Keypair::new();
+    let buyer_pubkey = buyer.pubkey();
+    let buyer_purchase_token_account = env.create_ata(&purchase_mint_pubkey, &buyer_pubkey).await?;
+
+    env.mint_purchase_token(&buyer_purchase_token_account, 200_000_000_000)
+        .await?;
+
+    env.buy_pull(&buyer, &buyer_purchase_token_account).await?;
+
+    let destination = env
+        .create_ata(&purchase_mint_pubkey, &authority_pubkey)
+        .await?;
+
+    // === proceeds of an unopened pull are locked
+
+    let ix = instruction::WithdrawProceeds::populate(
+        env.game_id,
+        authority_pubkey,
+        purchase_mint_pubkey,
+        destination,
+        pull_price,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    // === withdraw after the pull is opened
+
+    env.open_pull(pull_id, pull_proof_account, &buyer_pubkey, expected_amount)
+        .await?;
+
+    let ix = instruction::WithdrawProceeds::populate(
+        env.game_id,
+        authority_pubkey,
+        purchase_mint_pubkey,
+        destination,
+        pull_price,
+    );
+
+    let tx = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await?;
+
+    println!("withdraw proceeds tx: {}", tx);
+
+    let game_config = env.get_game_config().await?;
+
+    assert_eq!(game_config.withdrawn_amount, pull_price);
+    assert_eq!(game_config.withdrawable_amount()?, 0);
+    assert_eq!(env.get_token_balance(&destination).await?, pull_price);
+    assert_eq!(env.get_token_balance(&env.game_vault_pubkey()).await?, 0);
+
+    // === nothing left to withdraw
+
+    let ix = instruction::WithdrawProceeds::populate(
+        env.game_id,
+        authority_pubkey,
+        purchase_mint_pubkey,
+        destination,
+        1,
+    );
+
+    let result = env
+        .process_instruction(ix, &vec![&env.authority], Some(&env.payer))
+        .await;
+
+    assert!(result.is_err());
+
+    Ok(())
+}
@@ -5,33 +5,55 @@ use std::{
 };
 
 use anyhow::Result;
+use bytemuck::Zeroable;
 use gacha_sol::{
     instruction,
-    pda::{get_game_config_pubkey, get_pull_pubkey, get_reward_vault_pubkey},
-    state::{GameConfig, Pull, AE_CIPHERTEXT_MAX_BASE64_LEN, ELGAMAL_PUBKEY_MAX_BASE64_LEN},
+    pda::{
+        get_fee_schedule_pubkey, get_game_config_pubkey, get_game_vault_pubkey,
+        get_player_state_pubkey, get_prize_table_pubkey, get_pull_pool_pubkey, get_pull_pubkey,
+        get_pull_ticket_pubkey, get_reward_vault_pubkey, get_treasury_pubkey,
+    },
+    state::{
+        AllowlistProof, FeeRecipient, FeeSchedule, GameConfig, NewPull, PaymentMode, PlayerState,
+        PrizeTable, PrizeTier, Pull, PullPool, PullTicket, AE_CIPHERTEXT_MAX_BASE64_LEN,
+        ELGAMAL_PUBKEY_MAX_BASE64_LEN,
+    },
 };
 use solana_banks_interface::BanksTransactionResultWithSimulation;
 use solana_program::pubkey;
 use solana_sdk::{
+    clock::Clock,
     instruction::Instruction,
     pubkey::Pubkey,
     signature::{Keypair, Signature},
     signer::Signer,
+    slot_hashes::SlotHashes,
+    system_instruction, sysvar,
     transaction::Transaction,
 };
 use spl_token_2022::{
     extension::confidential_transfer::{
-        account_info::{ApplyPendingBalanceAccountInfo, TransferAccountInfo},
-        instruction::{deposit, PubkeyValidityProofData, ZeroCiphertextProofData, ZkProofData},
+        account_info::{ApplyPendingBalanceAccountInfo, TransferAccountInfo, WithdrawAccountInfo},
+        instruction::{
+            deposit, BatchedRangeProofU128Data, CiphertextCommitmentEqualityProofData,
+            PubkeyValidityProofData, ZeroCiphertextProofData, ZkProofData,
+        },
     },
     instruction::mint_to,
-    solana_zk_sdk::encryption::pod::{
-        auth_encryption::PodAeCiphertext, elgamal::PodElGamalCiphertext,
+    solana_zk_sdk::{
+        encryption::{
+            pedersen::Pedersen,
+            pod::{auth_encryption::PodAeCiphertext, elgamal::PodElGamalCiphertext},
+        },
+        zk_elgamal_proof_program::instruction::ProofInstruction,
     },
 };
-use spl_token_confidential_transfer_ciphertext_arithmetic::subtract;
+use spl_token_confidential_transfer_ciphertext_arithmetic::{add_to, subtract, subtract_from};
 use spl_token_confidential_transfer_proof_extraction::instruction::ProofLocation;
-use spl_token_confidential_transfer_proof_generation::transfer::TransferProofData;
+use spl_token_confidential_transfer_proof_generation::{
+    transfer::TransferProofData,
+    withdraw::{withdraw_proof_data, WithdrawProofData},
+};
 
 use crate::test_utils::confidential_transfer::{
     confidential_mint_to_ixs, confidential_transfer_ixs, create_close_context_state_ixs,
@@ -44,6 +66,26 @@ use super::{
     proof_account::{self, ProofAccount, SignerProofAccount},
 };
 
+pub const REFUND_TIMEOUT: i64 = 86_400;
+
+pub struct WithdrawProofs {
+    pub equality_proof: Pubkey,
+    pub range_proof: Pubkey,
+    pub zero_balance_proof: Pubkey,
+    pub amount: u64,
+    pub new_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+}
+
+pub struct TransferProofs {
+    pub equality_proof: Pubkey,
+    pub ciphertext_validity_proof: Pubkey,
+    pub range_proof: Pubkey,
+    pub zero_balance_proof: Pubkey,
+    pub transfer_amount_auditor_ciphertext_lo: [u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
+    pub transfer_amount_auditor_ciphertext_hi: [u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN],
+    pub new_decryptable_available_balance: [u8; AE_CIPHERTEXT_MAX_BASE64_LEN],
+}
+
 pub struct GachaSolTestEnvironment {
     pub test_fixtures: Arc<Mutex<ProgramTestFixtures>>,
     pub payer: Keypair,
@@ -52,7 +94,7 @@ pub struct GachaSolTestEnvironment {
     pub purchase_mint_authority: Keypair,
     pub reward_mint_authority: Keypair,
     pub reward_mint_proof_account: SignerProofAccount,
-    pub game_vault: Pubkey,
+    pub game_id: u64,
     pub decimals: u8,
 }
 
@@ -149,16 +191,6 @@ impl GachaSolTestEnvironment {
 
         println!("xxx reward_mint {}", reward_mint_proof_account.pubkey());
 
-        let game_vault = {
-            let mut test_fixtures = test_fixtures.lock().unwrap();
-
-            test_fixtures
-                .create_ata(&purchase_mint, &authority.pubkey())
-                .await?
-        };
-
-        println!("xxx game_vault {}", game_vault);
-
         Ok(Self {
             test_fixtures,
             payer: payer.insecure_clone(),
@@ -167,7 +199,7 @@ impl GachaSolTestEnvironment {
             purchase_mint_authority,
             reward_mint_authority,
             reward_mint_proof_account,
-            game_vault,
+            game_id: 1,
             decimals: 9,
         })
     }
@@ -181,34 +213,79 @@ impl GachaSolTestEnvironment {
     }
 
     pub fn game_vault_pubkey(&self) -> Pubkey {
-        self.game_vault
+        get_game_vault_pubkey(self.game_config_pubkey(), self.purchase_mint)
     }
 
     pub fn game_config_pubkey(&self) -> Pubkey {
-        get_game_config_pubkey()
+        get_game_config_pubkey(self.game_id)
     }
 
     pub fn pull_pubkey(&self, pull_id: u64) -> Pubkey {
-        get_pull_pubkey(pull_id)
+        get_pull_pubkey(self.game_config_pubkey(), pull_id)
+    }
+
+    pub fn pull_pool_pubkey(&self) -> Pubkey {
+        get_pull_pool_pubkey(self.game_config_pubkey())
+    }
+
+    pub fn pull_ticket_pubkey(&self, buyer: &Pubkey) -> Pubkey {
+        get_pull_ticket_pubkey(self.game_config_pubkey(), *buyer)
+    }
+
+    pub fn player_state_pubkey(&self, buyer: &Pubkey) -> Pubkey {
+        get_player_state_pubkey(self.game_config_pubkey(), *buyer)
     }
 
     pub fn reward_vault_pubkey(&self, pull: Pubkey) -> Pubkey {
         get_reward_vault_pubkey(pull)
     }
 
+    pub fn prize_table_pubkey(&self) -> Pubkey {
+        get_prize_table_pubkey(self.game_config_pubkey())
+    }
+
+    pub fn treasury_pubkey(&self) -> Pubkey {
+        get_treasury_pubkey(self.game_config_pubkey())
+    }
+
+    pub fn fee_schedule_pubkey(&self) -> Pubkey {
+        get_fee_schedule_pubkey(self.game_config_pubkey())
+    }
+
+    /// Prize table to pass along pull instructions, when the game has committed one
+    pub async fn committed_prize_table(&self) -> Result<Option<Pubkey>> {
+        let game_config = self.get_game_config().await?;
+
+        Ok(game_config
+            .has_prize_table()
+            .then_some(game_config.prize_table))
+    }
+
     pub async fn initialize_game_config(&self, pull_price: u64) -> Result<Signature> {
+        self.initialize_game_config_with_payment_mode(pull_price, PaymentMode::Token)
+            .await
+    }
+
+    pub async fn initialize_game_config_with_payment_mode(
+        &self,
+        pull_price: u64,
+        payment_mode: PaymentMode,
+    ) -> Result<Signature> {
         let authority_pubkey = self.authority.pubkey();
         let purchase_mint_pubkey = self.purchase_mint_pubkey();
         let reward_mint_pubkey = self.reward_mint_pubkey();
-        let game_vault_pubkey = self.game_vault_pubkey();
 
         let ix = instruction::InitializeGameConfig::populate(
+            self.game_id,
             authority_pubkey,
             purchase_mint_pubkey,
             reward_mint_pubkey,
-            game_vault_pubkey,
             self.payer.pubkey(),
             pull_price,
+            REFUND_TIMEOUT,
+            0,
+            0,
+            payment_mode,
         );
 
         let tx = self
@@ -220,16 +297,86 @@ impl GachaSolTestEnvironment {
         Ok(tx)
     }
 
+    pub async fn commit_prize_table(&self, tiers: Vec<PrizeTier>) -> Result<Signature> {
+        let ix = instruction::CommitPrizeTable::populate(
+            self.game_id,
+            self.authority.pubkey(),
+            self.payer.pubkey(),
+            tiers,
+        );
+
+        let tx = self
+            .process_instruction(ix, &vec![&self.authority], Some(&self.payer))
+            .await?;
+
+        println!("commit prize table tx: {}", tx);
+
+        Ok(tx)
+    }
+
+    pub async fn set_fee_schedule(&self, recipients: Vec<FeeRecipient>) -> Result<Signature> {
+        let ix = instruction::SetFeeSchedule::populate(
+            self.game_id,
+            self.authority.pubkey(),
+            self.payer.pubkey(),
+            recipients,
+        );
+
+        let tx = self
+            .process_instruction(ix, &vec![&self.authority], Some(&self.payer))
+            .await?;
+
+        println!("set fee schedule tx: {}", tx);
+
+        Ok(tx)
+    }
+
+    pub async fn pause_game(&self) -> Result<Signature> {
+        let ix = instruction::PauseGame::populate(self.game_id, self.authority.pubkey());
+
+        let tx = self
+            .process_instruction(ix, &vec![&self.authority], Some(&self.payer))
+            .await?;
+
+        println!("pause game tx: {}", tx);
+
+        Ok(tx)
+    }
+
+    pub async fn resume_game(&self) -> Result<Signature> {
+        let ix = instruction::ResumeGame::populate(self.game_id, self.authority.pubkey());
+
+        let tx = self
+            .process_instruction(ix, &vec![&self.authority], Some(&self.payer))
+            .await?;
+
+        println!("resume game tx: {}", tx);
+
+        Ok(tx)
+    }
+
     pub async fn create_pull(
         &self,
         pull_id: u64,
         pull_proof_account: impl ProofAccount,
         expected_amount: u64,
+    ) -> Result<Signature> {
+        self.create_pull_with_tier(pull_id, pull_proof_account, expected_amount, 0)
+            .await
+    }
+
+    pub async fn create_pull_with_tier(
+        &self,
+        pull_id: u64,
+        pull_proof_account: impl ProofAccount,
+        expected_amount: u64,
+        tier: u8,
     ) -> Result<Signature> {
         let payer_pubkey = self.payer.pubkey();
         let authority_pubkey = self.authority.pubkey();
         let reward_mint_pubkey = self.reward_mint_pubkey();
         let pull_pubkey = self.pull_pubkey(pull_id);
+        let prize_table = self.committed_prize_table().await?;
 
         let decryptable_zero_balance = pull_proof_account.encrypt_supply(0)?;
         let encrypted_amount = pull_proof_account.encrypt_amount_ciphertext(expected_amount)?;
@@ -279,12 +426,15 @@ impl GachaSolTestEnvironment {
             )?;
 
         let ix = instruction::CreatePull::populate(
+            self.game_id,
             authority_pubkey,
             reward_mint_pubkey,
+            prize_table,
             payer_pubkey,
-            pubkey_validity_proof_data_pubkey,
+            Some(pubkey_validity_proof_data_pubkey),
             pull_id,
             encrypted_amount_array,
+            tier,
             decryptable_zero_balance_array,
         );
 
@@ -353,6 +503,7 @@ impl GachaSolTestEnvironment {
         };
 
         let ix = instruction::ApplyPullPendingBalance::populate(
+            self.game_id,
             authority_pubkey,
             pull_id,
             decryptable_new_decryptable_available_balance_array,
@@ -377,6 +528,28 @@ impl GachaSolTestEnvironment {
         Ok(game_config)
     }
 
+    pub async fn get_prize_table(&self) -> Result<PrizeTable> {
+        let prize_table_pubkey = self.prize_table_pubkey();
+        let mut test_fixtures = self.test_fixtures.lock().unwrap();
+        let prize_table = test_fixtures
+            .program_simulator
+            .get_anchor_account_data(prize_table_pubkey)
+            .await?;
+
+        Ok(prize_table)
+    }
+
+    pub async fn get_fee_schedule(&self) -> Result<FeeSchedule> {
+        let fee_schedule_pubkey = self.fee_schedule_pubkey();
+        let mut test_fixtures = self.test_fixtures.lock().unwrap();
+        let fee_schedule = test_fixtures
+            .program_simulator
+            .get_anchor_account_data(fee_schedule_pubkey)
+            .await?;
+
+        Ok(fee_schedule)
+    }
+
     pub async fn get_pull(&self, pull_id: u64) -> Result<Pull> {
         let pull_pubkey = self.pull_pubkey(pull_id);
         let mut test_fixtures = self.test_fixtures.lock().unwrap();
@@ -388,6 +561,39 @@ impl GachaSolTestEnvironment {
         Ok(game_config)
     }
 
+    pub async fn get_pull_pool(&self) -> Result<PullPool> {
+        let pull_pool_pubkey = self.pull_pool_pubkey();
+        let mut test_fixtures = self.test_fixtures.lock().unwrap();
+        let pull_pool = test_fixtures
+            .program_simulator
+            .get_anchor_account_data(pull_pool_pubkey)
+            .await?;
+
+        Ok(pull_pool)
+    }
+
+    pub async fn get_player_state(&self, buyer: &Pubkey) -> Result<PlayerState> {
+        let player_state_pubkey = self.player_state_pubkey(buyer);
+        let mut test_fixtures = self.test_fixtures.lock().unwrap();
+        let player_state = test_fixtures
+            .program_simulator
+            .get_anchor_account_data(player_state_pubkey)
+            .await?;
+
+        Ok(player_state)
+    }
+
+    pub async fn get_pull_ticket(&self, buyer: &Pubkey) -> Result<PullTicket> {
+        let pull_ticket_pubkey = self.pull_ticket_pubkey(buyer);
+        let mut test_fixtures = self.test_fixtures.lock().unwrap();
+        let pull_ticket = test_fixtures
+            .program_simulator
+            .get_anchor_account_data(pull_ticket_pubkey)
+            .await?;
+
+        Ok(pull_ticket)
+    }
+
     pub async fn create_ct_token_account(
         &self,
         mint_pubkey: &Pubkey,
@@ -610,9 +816,21 @@ impl GachaSolTestEnvironment {
 
         println!("proof accounts tx: {}", proof_account_tx);
 
-        let ix = instruction::VerifyPull::populate(
+        // Games with a prize table also check the reward against the prize range
+        let amount_range_proofs = match self.committed_prize_table().await? {
+            Some(_) => Some(
+                self.create_amount_range_proofs(pull_id, pull_proof_account.clone())
+                    .await?,
+            ),
+            None => None,
+        };
+
+        let ix = instruction::VerifyPull::populate_prize_range(
+            self.game_id,
             authority_pubkey,
-            zero_ciphertext_proof_pubkey,
+            Some(zero_ciphertext_proof_pubkey),
+            None,
+            amount_range_proofs,
             pull_id,
         );
 
@@ -628,11 +846,12 @@ impl GachaSolTestEnvironment {
 
         // Close context states Instructions ---------------------------------------------------------------
 
-        let close_context_state_ixs = create_close_context_state_ixs(
-            &[zero_ciphertext_proof_pubkey],
-            &authority_pubkey,
-            &payer_pubkey,
-        );
+        let proof_pubkeys: Vec<Pubkey> = [zero_ciphertext_proof_pubkey]
+            .into_iter()
+            .chain(amount_range_proofs.into_iter().flatten())
+            .collect();
+        let close_context_state_ixs =
+            create_close_context_state_ixs(&proof_pubkeys, &authority_pubkey, &payer_pubkey);
 
         let close_context_state_tx = self
             .process_instructions(&close_context_state_ixs, &vec![&self.authority], None)
@@ -643,21 +862,165 @@ impl GachaSolTestEnvironment {
         Ok(())
     }
 
+    /// Create proof context accounts showing the reward of a pull lies within the range of its
+    /// prize tier, in the order verify_pull expects them
+    pub async fn create_amount_range_proofs(
+        &self,
+        pull_id: u64,
+        pull_proof_account: SignerProofAccount,
+    ) -> Result<[Pubkey; 3]> {
+        let pull = self.get_pull(pull_id).await?;
+        let (min_amount, max_amount) = self
+            .get_prize_table()
+            .await?
+            .amount_range(pull.tier)
+            .map_err(|_| anyhow::anyhow!("no prize tier for the pull"))?;
+
+        let reward_vault_pubkey = self.reward_vault_pubkey(self.pull_pubkey(pull_id));
+        let amount = {
+            let mut test_fixtures = self.test_fixtures.lock().unwrap();
+
+            let confidential_transfer_account = test_fixtures
+                .get_token_account_credential_transfer_account(&reward_vault_pubkey)
+                .await?;
+
+            pull_proof_account
+                .decrypt_supply(&confidential_transfer_account.decryptable_available_balance)?
+        };
+
+        let expected_amount = PodElGamalCiphertext::from_str(
+            std::str::from_utf8(&pull.encrypted_amount)
+                .map_err(|_| anyhow::anyhow!("ciphertext expected amount conversion failed"))?,
+        )
+        .map_err(|_| anyhow::anyhow!("ciphertext expected amount conversion failed"))?;
+        let above_min_amount = subtract_from(&expected_amount, min_amount)
+            .ok_or_else(|| anyhow::anyhow!("ciphertext arithmetic failed"))?;
+        let below_max_amount = add_to(&PodElGamalCiphertext::zeroed(), max_amount)
+            .and_then(|max_amount| subtract(&max_amount, &expected_amount))
+            .ok_or_else(|| anyhow::anyhow!("ciphertext arithmetic failed"))?;
+
+        // A reward outside the range wraps around and cannot be proven
+        let above_min = amount.wrapping_sub(min_amount);
+        let below_max = max_amount.wrapping_sub(amount);
+        let (above_min_commitment, above_min_opening) = Pedersen::new(above_min);
+        let (below_max_commitment, below_max_opening) = Pedersen::new(below_max);
+
+        let elgamal_keypair = pull_proof_account.get_pod_elgamal_keypair()?;
+        let min_amount_equality_proof_data = CiphertextCommitmentEqualityProofData::new(
+            &elgamal_keypair,
+            &above_min_amount.try_into()?,
+            &above_min_commitment,
+            &above_min_opening,
+            above_min,
+        )?;
+        let max_amount_equality_proof_data = CiphertextCommitmentEqualityProofData::new(
+            &elgamal_keypair,
+            &below_max_amount.try_into()?,
+            &below_max_commitment,
+            &below_max_opening,
+            below_max,
+        )?;
+        let amount_range_proof_data = BatchedRangeProofU128Data::new(
+            vec![&above_min_commitment, &below_max_commitment],
+            vec![above_min, below_max],
+            vec![64, 64],
+            vec![&above_min_opening, &below_max_opening],
+        )?;
+
+        let min_amount_equality_proof_context_state_account = Keypair::new();
+        let max_amount_equality_proof_context_state_account = Keypair::new();
+        let amount_range_proof_context_state_account = Keypair::new();
+
+        let payer_pubkey = self.payer.pubkey();
+        let authority_pubkey = self.authority.pubkey();
+
+        let (min_equality_create_ix, min_equality_verify_ix) =
+            get_zk_proof_context_state_account_creation_instructions(
+                &payer_pubkey,
+                &min_amount_equality_proof_context_state_account.pubkey(),
+                &authority_pubkey,
+                &min_amount_equality_proof_data,
+            )?;
+
+        let (max_equality_create_ix, max_equality_verify_ix) =
+            get_zk_proof_context_state_account_creation_instructions(
+                &payer_pubkey,
+                &max_amount_equality_proof_context_state_account.pubkey(),
+                &authority_pubkey,
+                &max_amount_equality_proof_data,
+            )?;
+
+        let (range_create_ix, range_verify_ix) =
+            get_zk_proof_context_state_account_creation_instructions(
+                &payer_pubkey,
+                &amount_range_proof_context_state_account.pubkey(),
+                &authority_pubkey,
+                &amount_range_proof_data,
+            )?;
+
+        let equality_proofs_tx = self
+            .process_instructions(
+                &[
+                    min_equality_create_ix,
+                    max_equality_create_ix,
+                    min_equality_verify_ix,
+                    max_equality_verify_ix,
+                ],
+                &vec![
+                    &min_amount_equality_proof_context_state_account,
+                    &max_amount_equality_proof_context_state_account,
+                ],
+                Some(&self.payer),
+            )
+            .await?;
+
+        println!("amount equality proofs tx: {}", equality_proofs_tx);
+
+        let range_proof_tx = self
+            .process_instructions(
+                &[range_create_ix, range_verify_ix],
+                &vec![&amount_range_proof_context_state_account],
+                Some(&self.payer),
+            )
+            .await?;
+
+        println!("amount range proof tx: {}", range_proof_tx);
+
+        Ok([
+            min_amount_equality_proof_context_state_account.pubkey(),
+            max_amount_equality_proof_context_state_account.pubkey(),
+            amount_range_proof_context_state_account.pubkey(),
+        ])
+    }
+
+    /// Buy a pull ticket and reveal it, returning the assigned pull id
     pub async fn buy_pull(
         &self,
         buyer: &Keypair,
         buyer_purchase_token_account: &Pubkey,
-        pull_id: u64,
-    ) -> Result<()> {
+    ) -> Result<u64> {
+        self.buy_pull_with_allowlist(buyer, buyer_purchase_token_account, None)
+            .await
+    }
+
+    /// Buy a pull ticket with a presale allowlist proof and reveal it
+    pub async fn buy_pull_with_allowlist(
+        &self,
+        buyer: &Keypair,
+        buyer_purchase_token_account: &Pubkey,
+        allowlist: Option<AllowlistProof>,
+    ) -> Result<u64> {
         let buyer_pubkey = buyer.pubkey();
         let purchase_mint_pubkey = self.purchase_mint_pubkey();
 
         let ix: solana_sdk::instruction::Instruction = instruction::BuyPull::populate(
+            self.game_id,
             buyer_pubkey,
             *buyer_purchase_token_account,
-            self.game_vault_pubkey(),
             purchase_mint_pubkey,
-            pull_id,
+            None,
+            PaymentMode::Token,
+            allowlist,
         );
 
         let tx = self
@@ -665,9 +1028,755 @@ impl GachaSolTestEnvironment {
             .await?;
 
         println!("buy pull tx: {}", tx);
+
+        self.reveal_pull(&buyer_pubkey).await
+    }
+
+    /// Buy a bundle of `bundle_size` pulls and reveal it, returning the assigned pull ids
+    pub async fn buy_pulls(
+        &self,
+        buyer: &Keypair,
+        buyer_purchase_token_account: &Pubkey,
+        bundle_size: u8,
+    ) -> Result<Vec<u64>> {
+        let buyer_pubkey = buyer.pubkey();
+        let purchase_mint_pubkey = self.purchase_mint_pubkey();
+
+        let ix = instruction::BuyPulls::populate(
+            self.game_id,
+            buyer_pubkey,
+            *buyer_purchase_token_account,
+            purchase_mint_pubkey,
+            PaymentMode::Token,
+            bundle_size,
+            None,
+        );
+
+        let tx = self
+            .process_instruction(ix, &vec![&buyer], Some(&self.payer))
+            .await?;
+
+        println!("buy pulls tx: {}", tx);
+
+        self.reveal_pulls(&buyer_pubkey).await
+    }
+
+    /// Reveal the pull ticket of `buyer`, returning the assigned pull id
+    pub async fn reveal_pull(&self, buyer: &Pubkey) -> Result<u64> {
+        Ok(self.reveal_pulls(buyer).await?[0])
+    }
+
+    /// Reveal the pull ticket of `buyer`, returning every assigned pull id.
+    /// Revealing is permissionless, only the payer signs
+    pub async fn reveal_pulls(&self, buyer: &Pubkey) -> Result<Vec<u64>> {
+        let pull_ids = self.get_selected_pull_ids(buyer).await?;
+
+        let ix = instruction::RevealPull::populate_bundle(self.game_id, *buyer, &pull_ids, None);
+
+        let tx = self
+            .process_instruction(ix, &vec![&self.payer], None)
+            .await?;
+
+        println!("reveal pull tx: {}", tx);
+
+        Ok(pull_ids)
+    }
+
+    /// Reveal the pull ticket of `buyer` after its commit slot hash expired, the authority
+    /// signs and the most recent slot hash is used instead
+    pub async fn reveal_expired_pulls(&self, buyer: &Pubkey) -> Result<Vec<u64>> {
+        let pull_ids = self.get_selected_pull_ids(buyer).await?;
+
+        let ix = instruction::RevealPull::populate_bundle(
+            self.game_id,
+            *buyer,
+            &pull_ids,
+            Some(self.authority.pubkey()),
+        );
+
+        let tx = self
+            .process_instruction(ix, &vec![&self.authority], Some(&self.payer))
+            .await?;
+
+        println!("reveal expired pull tx: {}", tx);
+
+        Ok(pull_ids)
+    }
+
+    /// Compute the pull reveal_pull will select, moving past the commit slot first
+    pub async fn get_selected_pull_id(&self, buyer: &Pubkey) -> Result<u64> {
+        Ok(self.get_selected_pull_ids(buyer).await?[0])
+    }
+
+    /// Compute every pull reveal_pull will select for the ticket, in draw order. Once the
+    /// commit slot hash expired the most recent slot hash is used, as the authority does.
+    pub async fn get_selected_pull_ids(&self, buyer: &Pubkey) -> Result<Vec<u64>> {
+        let pull_ticket_pubkey = self.pull_ticket_pubkey(buyer);
+        let pull_ticket = self.get_pull_ticket(buyer).await?;
+        let mut pull_pool = self.get_pull_pool().await?;
+        let mut player_state = self.get_player_state(buyer).await?;
+        let game_config = self.get_game_config().await?;
+
+        let mut test_fixtures = self.test_fixtures.lock().unwrap();
+
+        let clock: Clock = bincode::deserialize(
+            &test_fixtures
+                .program_simulator
+                .get_account(sysvar::clock::ID)
+                .await?
+                .data,
+        )?;
+        if clock.slot <= pull_ticket.commit_slot {
+            test_fixtures
+                .program_simulator
+                .warp_to_slot(pull_ticket.commit_slot + 1)?;
+        }
+
+        let slot_hashes: SlotHashes = bincode::deserialize(
+            &test_fixtures
+                .program_simulator
+                .get_account(sysvar::slot_hashes::ID)
+                .await?
+                .data,
+        )?;
+        let slot_hash = slot_hashes
+            .get(&pull_ticket.commit_slot)
+            .or_else(|| slot_hashes.first().map(|(_, slot_hash)| slot_hash))
+            .ok_or_else(|| anyhow::anyhow!("slot hash not found"))?;
+
+        let randomness = PullTicket::compute_randomness(&pull_ticket_pubkey, &slot_hash.to_bytes());
+
+        let pull_count = pull_ticket.pull_count.max(1);
+        pull_pool.pending_pity_draws -= pull_ticket.pity_draws as u64;
+
+        (0..pull_count)
+            .map(|draw| {
+                let pity_reserved = pull_pool.pending_pity_draws
+                    + player_state.guaranteed_pity_draws(&game_config, pull_count - draw);
+                let (pull_id, pity) = pull_pool
+                    .take_pull(
+                        &randomness,
+                        draw,
+                        player_state.pity_guaranteed(&game_config),
+                        pity_reserved,
+                    )
+                    .ok_or_else(|| anyhow::anyhow!("pull pool is empty"))?;
+                player_state.record_pull(pity);
+
+                Ok(pull_id)
+            })
+            .collect()
+    }
+
+    /// Move past slots until the commit slot hash of the ticket of `buyer` drops out of
+    /// the SlotHashes sysvar, the ticket can then only be cancelled
+    pub async fn expire_pull_ticket(&self, buyer: &Pubkey) -> Result<()> {
+        let pull_ticket = self.get_pull_ticket(buyer).await?;
+
+        let mut test_fixtures = self.test_fixtures.lock().unwrap();
+
+        loop {
+            let clock: Clock = bincode::deserialize(
+                &test_fixtures
+                    .program_simulator
+                    .get_account(sysvar::clock::ID)
+                    .await?
+                    .data,
+            )?;
+            let slot_hashes: SlotHashes = bincode::deserialize(
+                &test_fixtures
+                    .program_simulator
+                    .get_account(sysvar::slot_hashes::ID)
+                    .await?
+                    .data,
+            )?;
+            if clock.slot > pull_ticket.commit_slot
+                && slot_hashes.get(&pull_ticket.commit_slot).is_none()
+            {
+                return Ok(());
+            }
+
+            test_fixtures
+                .program_simulator
+                .warp_to_slot(clock.slot + 1)?;
+        }
+    }
+
+    /// Move the clock forward to `unix_timestamp`
+    pub async fn warp_to_timestamp(&self, unix_timestamp: i64) -> Result<()> {
+        let mut test_fixtures = self.test_fixtures.lock().unwrap();
+        test_fixtures
+            .program_simulator
+            .warp_to_timestamp(unix_timestamp)
+            .await?;
+
         Ok(())
     }
 
+    pub async fn get_token_balance(&self, token_account: &Pubkey) -> Result<u64> {
+        let mut test_fixtures = self.test_fixtures.lock().unwrap();
+        let ta: anchor_spl::token::spl_token::state::Account = test_fixtures
+            .program_simulator
+            .get_packed_account_data(*token_account)
+            .await?;
+
+        Ok(ta.amount)
+    }
+
+    pub async fn transfer_lamports(&self, destination: &Pubkey, lamports: u64) -> Result<()> {
+        let ix = system_instruction::transfer(&self.payer.pubkey(), destination, lamports);
+
+        self.process_instruction(ix, &vec![&self.payer], None)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_lamports(&self, pubkey: &Pubkey) -> Result<u64> {
+        let mut test_fixtures = self.test_fixtures.lock().unwrap();
+        let account = test_fixtures.program_simulator.get_account(*pubkey).await?;
+
+        Ok(account.lamports)
+    }
+
+    /// Create a pull, fund its reward vault with `expected_amount` and verify it
+    pub async fn create_verified_pull(
+        &self,
+        pull_proof_account: SignerProofAccount,
+        expected_amount: u64,
+    ) -> Result<u64> {
+        self.create_verified_pull_with_tier(pull_proof_account, expected_amount, 0)
+            .await
+    }
+
+    /// Create a verified pull of the given prize tier
+    pub async fn create_verified_pull_with_tier(
+        &self,
+        pull_proof_account: SignerProofAccount,
+        expected_amount: u64,
+        tier: u8,
+    ) -> Result<u64> {
+        let pull_id = self.get_game_config().await?.last_pull_id + 1;
+
+        self.create_pull_with_tier(pull_id, pull_proof_account.clone(), expected_amount, tier)
+            .await?;
+
+        let reward_mint_pubkey = self.reward_mint_pubkey();
+        let token_account_proof_account = SignerProofAccount::new();
+        let token_account_pubkey = token_account_proof_account.pubkey();
+        let reward_vault_pubkey = self.reward_vault_pubkey(self.pull_pubkey(pull_id));
+
+        self.create_ct_token_account(
+            &reward_mint_pubkey,
+            &self.authority,
+            token_account_proof_account.clone(),
+        )
+        .await?;
+
+        self.mint_reward_token(&token_account_pubkey, expected_amount)
+            .await?;
+
+        self.deposit_reward(&token_account_pubkey, &self.authority, expected_amount)
+            .await?;
+
+        self.apply_pending_balance(token_account_proof_account.clone(), &self.authority)
+            .await?;
+
+        self.ct_transfer_reward_token(
+            token_account_proof_account,
+            &self.authority,
+            &reward_vault_pubkey,
+            expected_amount,
+        )
+        .await?;
+
+        self.apply_pull_pending_balance(pull_id, pull_proof_account.clone())
+            .await?;
+
+        self.verify_pull(pull_id, pull_proof_account).await?;
+
+        Ok(pull_id)
+    }
+
+    /// Create `count` verified tier 0 pulls with an empty reward in a game without a prize
+    /// table, cheap enough to fill the pull pool. Pulls are created in batches and each one
+    /// is verified in a single transaction with its zero ciphertext proof as a sibling
+    /// instruction.
+    pub async fn create_verified_empty_pulls(&self, count: usize) -> Result<Vec<u64>> {
+        let payer_pubkey = self.payer.pubkey();
+        let authority_pubkey = self.authority.pubkey();
+        let reward_mint_pubkey = self.reward_mint_pubkey();
+        let first_pull_id = self.get_game_config().await?.last_pull_id + 1;
+
+        // Every reward vault is configured with the same key
+        let pull_proof_account = SignerProofAccount::new();
+
+        let mut decryptable_zero_balance_array = [0u8; AE_CIPHERTEXT_MAX_BASE64_LEN];
+        decryptable_zero_balance_array
+            .copy_from_slice(pull_proof_account.encrypt_supply(0)?.to_string().as_bytes());
+
+        let new_pulls = (0..count)
+            .map(|_| {
+                let mut encrypted_amount_array = [0u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN];
+                encrypted_amount_array.copy_from_slice(
+                    pull_proof_account
+                        .encrypt_amount_ciphertext(0)?
+                        .to_string()
+                        .as_bytes(),
+                );
+
+                Ok(NewPull {
+                    encrypted_amount: encrypted_amount_array,
+                    tier: 0,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let pubkey_validity_proof_data_account = Keypair::new();
+        let pubkey_validity_proof_data_pubkey = pubkey_validity_proof_data_account.pubkey();
+
+        let pubkey_validity_proof_data =
+            PubkeyValidityProofData::new(&pull_proof_account.get_pod_elgamal_keypair()?)
+                .map_err(|_| anyhow::anyhow!("proof generation failed"))?;
+        let (pubkey_proof_create_ix, pubkey_proof_verify_ix) =
+            get_zk_proof_context_state_account_creation_instructions(
+                &payer_pubkey,
+                &pubkey_validity_proof_data_pubkey,
+                &authority_pubkey,
+                &pubkey_validity_proof_data,
+            )?;
+
+        self.process_instructions(
+            &[pubkey_proof_create_ix, pubkey_proof_verify_ix],
+            &vec![&self.payer, &pubkey_validity_proof_data_account],
+            None,
+        )
+        .await?;
+
+        let ixs = instruction::CreatePulls::populate_batches(
+            self.game_id,
+            authority_pubkey,
+            reward_mint_pubkey,
+            None,
+            payer_pubkey,
+            Some(pubkey_validity_proof_data_pubkey),
+            first_pull_id,
+            &new_pulls,
+            decryptable_zero_balance_array,
+        );
+
+        for ix in ixs {
+            self.process_instructions(&[ix], &vec![&self.payer, &self.authority], None)
+                .await?;
+        }
+
+        let pull_ids: Vec<u64> = (first_pull_id..first_pull_id + count as u64).collect();
+        for pull_id in pull_ids.iter().copied() {
+            let pull = self.get_pull(pull_id).await?;
+            let reward_vault_pubkey = self.reward_vault_pubkey(self.pull_pubkey(pull_id));
+
+            let ciphertext_available_balance = {
+                let mut test_fixtures = self.test_fixtures.lock().unwrap();
+
+                test_fixtures
+                    .get_token_account_credential_transfer_account(&reward_vault_pubkey)
+                    .await?
+                    .available_balance
+            };
+            let ciphertext_expected_amount = PodElGamalCiphertext::from_str(
+                std::str::from_utf8(&pull.encrypted_amount)
+                    .map_err(|_| anyhow::anyhow!("ciphertext expected amount conversion failed"))?,
+            )
+            .map_err(|_| anyhow::anyhow!("ciphertext expected amount conversion failed"))?;
+
+            let zero_ciphertext =
+                subtract(&ciphertext_available_balance, &ciphertext_expected_amount).unwrap();
+            let zero_proof_data = ZeroCiphertextProofData::new(
+                &pull_proof_account.get_pod_elgamal_keypair()?,
+                &zero_ciphertext.try_into()?,
+            )?;
+
+            let verify_pull_ix = instruction::VerifyPull::populate(
+                self.game_id,
+                authority_pubkey,
+                None,
+                None,
+                pull_id,
+            );
+            let zero_proof_verify_ix =
+                ProofInstruction::VerifyZeroCiphertext.encode_verify_proof(None, &zero_proof_data);
+
+            self.process_instructions(
+                &[verify_pull_ix, zero_proof_verify_ix],
+                &vec![&self.payer, &self.authority],
+                None,
+            )
+            .await?;
+        }
+
+        Ok(pull_ids)
+    }
+
+    /// Create proof context accounts withdrawing `amount` from the reward vault of a pull,
+    /// along with a zero balance proof for the vault after a full withdraw
+    pub async fn create_withdraw_proofs(
+        &self,
+        pull_id: u64,
+        pull_proof_account: SignerProofAccount,
+        amount: u64,
+    ) -> Result<WithdrawProofs> {
+        self.create_withdraw_proofs_with_authority(
+            pull_id,
+            pull_proof_account,
+            amount,
+            &self.authority.pubkey(),
+        )
+        .await
+    }
+
+    /// Create withdraw proofs for publish_withdraw_proofs, the pull being the context state
+    /// authority so only the program can close them
+    pub async fn create_published_withdraw_proofs(
+        &self,
+        pull_id: u64,
+        pull_proof_account: SignerProofAccount,
+        amount: u64,
+    ) -> Result<WithdrawProofs> {
+        self.create_withdraw_proofs_with_authority(
+            pull_id,
+            pull_proof_account,
+            amount,
+            &self.pull_pubkey(pull_id),
+        )
+        .await
+    }
+
+    async fn create_withdraw_proofs_with_authority(
+        &self,
+        pull_id: u64,
+        pull_proof_account: SignerProofAccount,
+        amount: u64,
+        context_state_authority: &Pubkey,
+    ) -> Result<WithdrawProofs> {
+        let reward_vault_pubkey = self.reward_vault_pubkey(self.pull_pubkey(pull_id));
+
+        let (encrypted_available_balance, new_decryptable_available_balance, current_balance) = {
+            let mut test_fixtures = self.test_fixtures.lock().unwrap();
+
+            let confidential_transfer_account = test_fixtures
+                .get_token_account_credential_transfer_account(&reward_vault_pubkey)
+                .await?;
+
+            let available_balance = pull_proof_account
+                .decrypt_supply(&confidential_transfer_account.decryptable_available_balance)?;
+
+            let withdraw_account_info = WithdrawAccountInfo::new(&confidential_transfer_account);
+
+            let new_decryptable_available_balance = withdraw_account_info
+                .new_decryptable_available_balance(
+                    amount,
+                    &pull_proof_account.get_ae_key()?.try_into()?,
+                )?;
+
+            (
+                confidential_transfer_account.available_balance,
+                PodAeCiphertext::from(new_decryptable_available_balance),
+                available_balance,
+            )
+        };
+
+        let WithdrawProofData {
+            equality_proof_data,
+            range_proof_data,
+        } = withdraw_proof_data(
+            &encrypted_available_balance.try_into()?,
+            current_balance,
+            amount,
+            &pull_proof_account.get_pod_elgamal_keypair()?,
+        )?;
+
+        let zero_ciphertext = subtract_from(&encrypted_available_balance, current_balance)
+            .ok_or_else(|| anyhow::anyhow!("zero balance ciphertext computation failed"))?;
+
+        let zero_proof_data = ZeroCiphertextProofData::new(
+            &pull_proof_account.get_pod_elgamal_keypair()?,
+            &zero_ciphertext.try_into()?,
+        )?;
+
+        let equality_proof_context_state_account = Keypair::new();
+        let range_proof_context_state_account = Keypair::new();
+        let zero_proof_context_state_account = Keypair::new();
+
+        let payer_pubkey = self.payer.pubkey();
+
+        let (range_create_ix, range_verify_ix) =
+            get_zk_proof_context_state_account_creation_instructions(
+                &payer_pubkey,
+                &range_proof_context_state_account.pubkey(),
+                context_state_authority,
+                &range_proof_data,
+            )?;
+
+        let (equality_create_ix, equality_verify_ix) =
+            get_zk_proof_context_state_account_creation_instructions(
+                &payer_pubkey,
+                &equality_proof_context_state_account.pubkey(),
+                context_state_authority,
+                &equality_proof_data,
+            )?;
+
+        let (zero_proof_create_ix, zero_proof_verify_ix) =
+            get_zk_proof_context_state_account_creation_instructions(
+                &payer_pubkey,
+                &zero_proof_context_state_account.pubkey(),
+                context_state_authority,
+                &zero_proof_data,
+            )?;
+
+        let proof_account_tx = self
+            .process_instructions(
+                &[
+                    range_create_ix,
+                    equality_create_ix,
+                    range_verify_ix,
+                    equality_verify_ix,
+                ],
+                &vec![
+                    &range_proof_context_state_account,
+                    &equality_proof_context_state_account,
+                ],
+                Some(&self.payer),
+            )
+            .await?;
+
+        println!("proof accounts tx: {}", proof_account_tx);
+
+        let zero_proof_account_tx = self
+            .process_instructions(
+                &[zero_proof_create_ix, zero_proof_verify_ix],
+                &vec![&zero_proof_context_state_account],
+                Some(&self.payer),
+            )
+            .await?;
+
+        println!("zero proof account tx: {}", zero_proof_account_tx);
+
+        let mut new_decryptable_available_balance_array = [0u8; AE_CIPHERTEXT_MAX_BASE64_LEN];
+        new_decryptable_available_balance_array
+            .copy_from_slice(new_decryptable_available_balance.to_string().as_bytes());
+
+        Ok(WithdrawProofs {
+            equality_proof: equality_proof_context_state_account.pubkey(),
+            range_proof: range_proof_context_state_account.pubkey(),
+            zero_balance_proof: zero_proof_context_state_account.pubkey(),
+            amount,
+            new_decryptable_available_balance: new_decryptable_available_balance_array,
+        })
+    }
+
+    /// Create proof context accounts for a confidential transfer of the whole reward vault
+    /// of a pull into `destination_token_account`, along with a zero balance proof for the vault
+    pub async fn create_transfer_proofs(
+        &self,
+        pull_id: u64,
+        pull_proof_account: SignerProofAccount,
+        destination_token_account: &Pubkey,
+    ) -> Result<TransferProofs> {
+        let reward_vault_pubkey = self.reward_vault_pubkey(self.pull_pubkey(pull_id));
+
+        let (transfer_account_info, current_balance, destination_elgamal_pubkey) = {
+            let mut test_fixtures = self.test_fixtures.lock().unwrap();
+
+            let confidential_transfer_account = test_fixtures
+                .get_token_account_credential_transfer_account(&reward_vault_pubkey)
+                .await?;
+
+            let current_balance = pull_proof_account
+                .decrypt_supply(&confidential_transfer_account.decryptable_available_balance)?;
+
+            let destination_elgamal_pubkey = test_fixtures
+                .get_token_account_elgamal_pubkey(destination_token_account)
+                .await?;
+
+            (
+                TransferAccountInfo::new(&confidential_transfer_account),
+                current_balance,
+                destination_elgamal_pubkey,
+            )
+        };
+
+        let new_decryptable_available_balance = PodAeCiphertext::from(
+            transfer_account_info
+                .new_decryptable_available_balance(
+                    current_balance,
+                    &pull_proof_account.get_ae_key()?,
+                )
+                .map_err(|e| anyhow::anyhow!("new decryptable balance failed: {}", e))?,
+        );
+
+        let TransferProofData {
+            equality_proof_data,
+            ciphertext_validity_proof_data_with_ciphertext,
+            range_proof_data,
+        } = transfer_account_info
+            .generate_split_transfer_proof_data(
+                current_balance,
+                &pull_proof_account.get_pod_elgamal_keypair()?,
+                &pull_proof_account.get_ae_key()?,
+                &destination_elgamal_pubkey.try_into()?,
+                None,
+            )
+            .map_err(|_| anyhow::anyhow!("proof generation failed"))?;
+
+        let zero_proof_data = ZeroCiphertextProofData::new(
+            &pull_proof_account.get_pod_elgamal_keypair()?,
+            &equality_proof_data.context.ciphertext.try_into()?,
+        )?;
+
+        let equality_proof_context_state_account = Keypair::new();
+        let ciphertext_validity_proof_context_state_account = Keypair::new();
+        let range_proof_context_state_account = Keypair::new();
+        let zero_proof_context_state_account = Keypair::new();
+
+        let payer_pubkey = self.payer.pubkey();
+        let authority_pubkey = self.authority.pubkey();
+
+        let (range_create_ix, range_verify_ix) =
+            get_zk_proof_context_state_account_creation_instructions(
+                &payer_pubkey,
+                &range_proof_context_state_account.pubkey(),
+                &authority_pubkey,
+                &range_proof_data,
+            )?;
+
+        let (equality_create_ix, equality_verify_ix) =
+            get_zk_proof_context_state_account_creation_instructions(
+                &payer_pubkey,
+                &equality_proof_context_state_account.pubkey(),
+                &authority_pubkey,
+                &equality_proof_data,
+            )?;
+
+        let (cv_create_ix, cv_verify_ix) =
+            get_zk_proof_context_state_account_creation_instructions(
+                &payer_pubkey,
+                &ciphertext_validity_proof_context_state_account.pubkey(),
+                &authority_pubkey,
+                &ciphertext_validity_proof_data_with_ciphertext.proof_data,
+            )?;
+
+        let (zero_proof_create_ix, zero_proof_verify_ix) =
+            get_zk_proof_context_state_account_creation_instructions(
+                &payer_pubkey,
+                &zero_proof_context_state_account.pubkey(),
+                &authority_pubkey,
+                &zero_proof_data,
+            )?;
+
+        let proof_account_tx = self
+            .process_instructions(
+                &[
+                    range_create_ix,
+                    equality_create_ix,
+                    cv_create_ix,
+                    range_verify_ix,
+                    equality_verify_ix,
+                    cv_verify_ix,
+                ],
+                &vec![
+                    &range_proof_context_state_account,
+                    &equality_proof_context_state_account,
+                    &ciphertext_validity_proof_context_state_account,
+                ],
+                Some(&self.payer),
+            )
+            .await?;
+
+        println!("proof accounts tx: {}", proof_account_tx);
+
+        let zero_proof_account_tx = self
+            .process_instructions(
+                &[zero_proof_create_ix, zero_proof_verify_ix],
+                &vec![&zero_proof_context_state_account],
+                Some(&self.payer),
+            )
+            .await?;
+
+        println!("zero proof account tx: {}", zero_proof_account_tx);
+
+        let mut transfer_amount_auditor_ciphertext_lo = [0u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN];
+        transfer_amount_auditor_ciphertext_lo.copy_from_slice(
+            ciphertext_validity_proof_data_with_ciphertext
+                .ciphertext_lo
+                .to_string()
+                .as_bytes(),
+        );
+
+        let mut transfer_amount_auditor_ciphertext_hi = [0u8; ELGAMAL_PUBKEY_MAX_BASE64_LEN];
+        transfer_amount_auditor_ciphertext_hi.copy_from_slice(
+            ciphertext_validity_proof_data_with_ciphertext
+                .ciphertext_hi
+                .to_string()
+                .as_bytes(),
+        );
+
+        let mut new_decryptable_available_balance_array = [0u8; AE_CIPHERTEXT_MAX_BASE64_LEN];
+        new_decryptable_available_balance_array
+            .copy_from_slice(new_decryptable_available_balance.to_string().as_bytes());
+
+        Ok(TransferProofs {
+            equality_proof: equality_proof_context_state_account.pubkey(),
+            ciphertext_validity_proof: ciphertext_validity_proof_context_state_account.pubkey(),
+            range_proof: range_proof_context_state_account.pubkey(),
+            zero_balance_proof: zero_proof_context_state_account.pubkey(),
+            transfer_amount_auditor_ciphertext_lo,
+            transfer_amount_auditor_ciphertext_hi,
+            new_decryptable_available_balance: new_decryptable_available_balance_array,
+        })
+    }
+
+    /// Withdraw `amount` from the reward vault of a bought pull to the buyer
+    pub async fn open_pull(
+        &self,
+        pull_id: u64,
+        pull_proof_account: SignerProofAccount,
+        buyer: &Pubkey,
+        amount: u64,
+    ) -> Result<Signature> {
+        let reward_mint_pubkey = self.reward_mint_pubkey();
+        let buyer_reward_token_account = self.create_ata(&reward_mint_pubkey, buyer).await?;
+
+        let withdraw_proofs = self
+            .create_withdraw_proofs(pull_id, pull_proof_account, amount)
+            .await?;
+        let prize_table = self.committed_prize_table().await?;
+
+        let ix = instruction::OpenPull::populate(
+            self.game_id,
+            *buyer,
+            buyer_reward_token_account,
+            reward_mint_pubkey,
+            prize_table,
+            Some(withdraw_proofs.equality_proof),
+            Some(withdraw_proofs.range_proof),
+            Some(withdraw_proofs.zero_balance_proof),
+            None,
+            self.authority.pubkey(),
+            pull_id,
+            withdraw_proofs.amount,
+            self.decimals,
+            withdraw_proofs.new_decryptable_available_balance,
+        );
+
+        let tx = self
+            .process_instruction(ix, &vec![&self.authority], Some(&self.payer))
+            .await?;
+
+        println!("open pull tx: {}", tx);
+
+        Ok(tx)
+    }
+
     pub async fn ct_transfer_reward_token(
         &self,
         token_account_proof_account: SignerProofAccount,